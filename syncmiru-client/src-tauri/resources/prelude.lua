@@ -1,19 +1,48 @@
 local assdraw = require 'mp.assdraw'
+local utils = require 'mp.utils'
 
 local MOOD_NEUTRAL = 0
 local MOOD_BAD = 1
 local MOOD_GOOD = 2
 local MOOD_WARNING = 3
+local MOOD_COMMENT = 4
 
 local COLOR_WARNING = "00FFFF"
 local COLOR_NEUTRAL = "FFFF00"
 local COLOR_BAD = "0000FF"
 local COLOR_GOOD = "00FF00"
+local COLOR_COMMENT = "FFFFFF"
 
 local FONT_SIZE = 28
 
+local REACTION_DURATION = 3
+local REACTION_FONT_SIZE = 56
+local REACTION_NAME_FONT_SIZE = 20
+local REACTION_FPS = 30
+
+local CHAT_DURATION = 10
+local CHAT_MAX_LINES = 8
+local CHAT_FONT_SIZE = 24
+local CHAT_FADE = 2
+
+local COMMENT_DURATION = 5
+local COMMENTS_MAX_SHOWN = 5
+-- larger jumps of time-pos are seeks, comments in between are skipped
+local COMMENT_MAX_TIME_STEP = 2
+
 local order = {}
 local msgs = {}
+local comments = {}
+local comment_order = {}
+local next_comment_id = -1
+local last_time_pos = nil
+local reactions = {}
+local reactions_overlay = mp.create_osd_overlay("ass-events")
+local reactions_timer = nil
+local chat_lines = {}
+local chat_shown = true
+local chat_overlay = mp.create_osd_overlay("ass-events")
+local chat_timer = nil
 Message = { text = "", timestamp = nil, duration = nil, mood = MOOD_NEUTRAL }
 
 function Message:new(o)
@@ -42,6 +71,8 @@ local function ass_color(mood)
         color = "{\\1c&H" .. COLOR_GOOD .. "}"
     elseif mood == MOOD_WARNING then
         color = "{\\1c&H" .. COLOR_WARNING .. "}"
+    elseif mood == MOOD_COMMENT then
+        color = "{\\1c&H" .. COLOR_COMMENT .. "}"
     end
     return color
 end
@@ -51,6 +82,7 @@ local function render_msgs()
     local msgs_bad = {}
     local msgs_neutral = {}
     local msgs_warning = {}
+    local msgs_comment = {}
     for _, id in ipairs(order) do
         if msgs[id] ~= nil then
             if msgs[id].mood == MOOD_GOOD then
@@ -61,6 +93,8 @@ local function render_msgs()
                 table.insert(msgs_neutral, msgs[id])
             elseif msgs[id].mood == MOOD_WARNING then
                 table.insert(msgs_warning, msgs[id])
+            elseif msgs[id].mood == MOOD_COMMENT then
+                table.insert(msgs_comment, msgs[id])
             end
         end
     end
@@ -82,6 +116,10 @@ local function render_msgs()
         table.insert(msgs_array, v)
     end
 
+    for _, v in ipairs(msgs_comment) do
+        table.insert(msgs_array, v)
+    end
+
     local osd_w, osd_h, osd_aspect = mp.get_osd_size()
     local ass = assdraw.ass_new()
     local w = math.ceil(osd_w * 0.01) + 5
@@ -106,12 +144,8 @@ local function table_remove_by_value(tbl, value)
 end
 
 
-mp.register_script_message('msg-add', function(text, id_str, duration_str, mood_str)
+local function add_msg(text, id, duration, mood)
     local time = mp.get_time()
-    local id = tonumber(id_str)
-    local duration = tonumber(duration_str)
-    local mood = tonumber(mood_str)
-
     local msg = Message:new{text = text, time = time, duration = duration, mood = mood}
     msgs[id] = msg
     table.insert(order, id)
@@ -124,6 +158,10 @@ mp.register_script_message('msg-add', function(text, id_str, duration_str, mood_
         end)
     end
     render_msgs()
+end
+
+mp.register_script_message('msg-add', function(text, id_str, duration_str, mood_str)
+    add_msg(text, tonumber(id_str), tonumber(duration_str), tonumber(mood_str))
 end)
 
 mp.register_script_message('msg-del', function(id_str) 
@@ -147,8 +185,164 @@ mp.register_script_message('msg-edit', function(id_str, text)
     render_msgs()
 end)
 
+local function ass_escape(text)
+    local escaped = text
+        :gsub("\\", "\\\226\129\160")
+        :gsub("{", "\\{")
+        :gsub("}", "\\}")
+    return escaped
+end
+
+local function show_comment(comment)
+    local shown = {}
+    for _, id in ipairs(comment_order) do
+        if msgs[id] ~= nil then
+            table.insert(shown, id)
+        end
+    end
+    if #shown >= COMMENTS_MAX_SHOWN then
+        local id = table.remove(shown, 1)
+        table_remove_by_value(order, id)
+        msgs[id] = nil
+    end
+    table.insert(shown, next_comment_id)
+    comment_order = shown
+
+    add_msg(ass_escape(comment.text), next_comment_id, COMMENT_DURATION, MOOD_COMMENT)
+    next_comment_id = next_comment_id - 1
+end
+
+mp.register_script_message('comments-set', function(comments_json)
+    comments = utils.parse_json(comments_json) or {}
+end)
+
+mp.register_script_message('comment-add', function(comment_json)
+    local comment = utils.parse_json(comment_json)
+    if comment == nil then
+        return
+    end
+    table.insert(comments, comment)
+
+    -- the comment was posted just now, viewers around its time see it right away
+    local time_pos = mp.get_property_number("time-pos")
+    if time_pos ~= nil and comment.timestamp <= time_pos and time_pos - comment.timestamp < COMMENT_DURATION then
+        show_comment(comment)
+    end
+end)
+
+local function on_time_pos_change(name, time_pos)
+    if time_pos ~= nil and last_time_pos ~= nil
+            and time_pos > last_time_pos and time_pos - last_time_pos < COMMENT_MAX_TIME_STEP then
+        for _, comment in ipairs(comments) do
+            if comment.timestamp > last_time_pos and comment.timestamp <= time_pos then
+                show_comment(comment)
+            end
+        end
+    end
+    last_time_pos = time_pos
+end
+mp.observe_property("time-pos", "number", on_time_pos_change)
+
+local function render_reactions()
+    local now = mp.get_time()
+    local osd_w, osd_h, osd_aspect = mp.get_osd_size()
+    local shown = {}
+    local events = {}
+    for _, reaction in ipairs(reactions) do
+        local progress = (now - reaction.time) / REACTION_DURATION
+        if progress < 1 then
+            table.insert(shown, reaction)
+            -- floats up from the bottom and fades out in the second half
+            local x = math.floor(osd_w * reaction.x)
+            local y = math.floor(osd_h * (0.9 - 0.4 * progress))
+            local alpha = math.floor(255 * math.max(0, progress * 2 - 1))
+            table.insert(events, string.format(
+                "{\\an2\\pos(%d,%d)\\alpha&H%02X&\\fs%d}%s{\\fs%d}\\N%s",
+                x, y, alpha, REACTION_FONT_SIZE, reaction.reaction, REACTION_NAME_FONT_SIZE, reaction.name
+            ))
+        end
+    end
+    reactions = shown
+
+    reactions_overlay.res_x = osd_w
+    reactions_overlay.res_y = osd_h
+    reactions_overlay.data = table.concat(events, "\n")
+    reactions_overlay:update()
+    if #reactions == 0 and reactions_timer ~= nil then
+        reactions_timer:kill()
+        reactions_timer = nil
+    end
+end
+
+mp.register_script_message('reaction-add', function(name, reaction)
+    table.insert(reactions, {
+        name = ass_escape(name),
+        reaction = ass_escape(reaction),
+        time = mp.get_time(),
+        x = 0.6 + math.random() * 0.35
+    })
+    if reactions_timer == nil then
+        reactions_timer = mp.add_periodic_timer(1 / REACTION_FPS, render_reactions)
+    end
+    render_reactions()
+end)
+
+local function render_chat()
+    local now = mp.get_time()
+    local osd_w, osd_h, osd_aspect = mp.get_osd_size()
+    local shown = {}
+    local text = ""
+    for _, line in ipairs(chat_lines) do
+        local age = now - line.time
+        if age < CHAT_DURATION then
+            table.insert(shown, line)
+            -- lines fade out during the last seconds of their duration
+            local alpha = math.floor(255 * math.max(0, (age - CHAT_DURATION + CHAT_FADE) / CHAT_FADE))
+            text = text .. string.format("{\\alpha&H%02X&}", alpha)
+                .. ass_color(MOOD_NEUTRAL) .. line.name .. ": "
+                .. ass_color(MOOD_COMMENT) .. line.text .. ass_newline()
+        end
+    end
+    chat_lines = shown
+
+    chat_overlay.res_x = osd_w
+    chat_overlay.res_y = osd_h
+    if chat_shown and #chat_lines > 0 then
+        chat_overlay.data = string.format(
+            "{\\an1\\pos(%d,%d)\\fs%d}%s",
+            math.ceil(osd_w * 0.01) + 5, math.floor(osd_h * 0.85), CHAT_FONT_SIZE, text
+        )
+    else
+        chat_overlay.data = ""
+    end
+    chat_overlay:update()
+    if #chat_lines == 0 and chat_timer ~= nil then
+        chat_timer:kill()
+        chat_timer = nil
+    end
+end
+
+mp.register_script_message('chat-add', function(name, text)
+    table.insert(chat_lines, { name = ass_escape(name), text = ass_escape(text), time = mp.get_time() })
+    while #chat_lines > CHAT_MAX_LINES do
+        table.remove(chat_lines, 1)
+    end
+    if chat_timer == nil then
+        chat_timer = mp.add_periodic_timer(0.1, render_chat)
+    end
+    render_chat()
+end)
+
+local function toggle_chat()
+    chat_shown = not chat_shown
+    render_chat()
+    mp.command_native_async({"script-message", "chat-toggled", chat_shown and "yes" or "no"}, function(success, result, error_msg) end)
+end
+mp.add_key_binding("c", "toggle-chat", toggle_chat)
+
 local function on_window_size_change(name, dimensions)
     render_msgs()
+    render_chat()
 end
 mp.observe_property("osd-dimensions", "native", on_window_size_change)
 
@@ -31,6 +31,7 @@ pub struct AppState {
     pub mpv_ignore_next_sub_change_event: RwLock<bool>,
     pub mpv_ignore_next_audio_delay_event: RwLock<bool>,
     pub mpv_ignore_next_sub_delay_event: RwLock<bool>,
+    pub mpv_file_chapters: RwLock<Option<Vec<mpv::models::Chapter>>>,
 
     #[cfg(target_family = "unix")]
     pub x11_conn: RwLock<Option<RustConnection>>,
@@ -58,6 +58,7 @@ async fn main() -> Result<()> {
         mpv_ignore_next_sub_change_event: false.into(),
         mpv_ignore_next_audio_delay_event: false.into(),
         mpv_ignore_next_sub_delay_event: false.into(),
+        mpv_file_chapters: None.into(),
 
         #[cfg(target_family = "unix")]
         x11_conn: None.into(),
@@ -113,6 +114,7 @@ async fn main() -> Result<()> {
             mpv::frontend::mpv_wrapper_size_changed,
             mpv::frontend::mpv_reposition_to_small,
             mpv::frontend::mpv_load_from_source,
+            mpv::frontend::mpv_sub_add,
             mpv::frontend::mpv_load_from_url,
             mpv::frontend::mpv_remove_current_from_playlist,
             mpv::frontend::mpv_get_loaded_info,
@@ -131,6 +133,12 @@ async fn main() -> Result<()> {
             mpv::frontend::mpv_set_audio_delay,
             mpv::frontend::mpv_get_sub_delay,
             mpv::frontend::mpv_set_sub_delay,
+            mpv::frontend::mpv_set_ab_loop,
+            mpv::frontend::mpv_set_bookmarks,
+            mpv::frontend::mpv_set_comments,
+            mpv::frontend::mpv_add_comment,
+            mpv::frontend::mpv_show_reaction,
+            mpv::frontend::mpv_show_chat,
             mpv::frontend::mpv_clear_msgs,
             mpv::frontend::mpv_increase_playback_speed,
             mpv::frontend::mpv_decrease_playback_speed,
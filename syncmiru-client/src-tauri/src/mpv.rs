@@ -1,7 +1,7 @@
 pub mod frontend;
 pub mod ipc;
 pub mod window;
-mod models;
+pub mod models;
 mod utils;
 
 use std::fs;
@@ -5,7 +5,7 @@ use tauri::{Emitter};
 use crate::appstate::{AppState, MpvMsg};
 use crate::mpv::{gen_pipe_id, start_ipc, start_process, stop_ipc, stop_process, utils, window};
 use crate::mpv::ipc::{Interface, IpcData, MsgMood};
-use crate::mpv::models::{LoadFromSource, LoadFromUrl, UserLoadedInfo};
+use crate::mpv::models::{AbLoop, Bookmark, Chapter, LoadFromSource, LoadFromUrl, SubAdd, TimedComment, UserLoadedInfo};
 use crate::mpv::window::HtmlElementRect;
 use tokio::time::{Instant};
 use crate::result::Result;
@@ -166,7 +166,25 @@ pub async fn mpv_load_from_source(
     let mpv_ipc_tx = mpv_ipc_tx_rl.as_ref().unwrap();
     mpv_ipc_tx.send(Interface::LoadFromSource {
         source_url: data_obj.source_url,
-        jwt: data_obj.jwt
+        jwt: data_obj.jwt,
+        start: data_obj.start
+    }).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn mpv_sub_add(
+    state: tauri::State<'_, Arc<AppState>>,
+    data: String
+) -> Result<()> {
+    let data_obj: SubAdd = serde_json::from_str(&data)?;
+
+    let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
+    let mpv_ipc_tx = mpv_ipc_tx_rl.as_ref().unwrap();
+    mpv_ipc_tx.send(Interface::SubAdd {
+        source_url: data_obj.source_url,
+        jwt: data_obj.jwt,
+        title: data_obj.title
     }).await?;
     Ok(())
 }
@@ -184,7 +202,7 @@ pub async fn mpv_load_from_url(
     let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
     let mpv_ipc_tx = mpv_ipc_tx_rl.as_ref().unwrap();
 
-    mpv_ipc_tx.send(Interface::LoadFromUrl(data_obj.url)).await?;
+    mpv_ipc_tx.send(Interface::LoadFromUrl { url: data_obj.url, start: data_obj.start }).await?;
     Ok(())
 }
 
@@ -457,6 +475,90 @@ pub async fn mpv_set_sub_delay(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn mpv_set_ab_loop(
+    state: tauri::State<'_, Arc<AppState>>,
+    ab_loop: Option<AbLoop>
+) -> Result<()> {
+    let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
+    let mpv_ipc_tx = mpv_ipc_tx_rl.as_ref().unwrap();
+    mpv_ipc_tx.send(Interface::SetAbLoop(ab_loop.map(|x| (x.a, x.b)))).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn mpv_set_bookmarks(
+    state: tauri::State<'_, Arc<AppState>>,
+    window: tauri::Window,
+    bookmarks: Vec<Bookmark>
+) -> Result<()> {
+    let ipc_data = IpcData { app_state: state.inner().clone(), window };
+
+    // chapter-list is overwritten by the bookmarks, the chapters of the file are kept aside on the first call
+    let mut mpv_file_chapters_wl = state.mpv_file_chapters.write().await;
+    if mpv_file_chapters_wl.is_none() {
+        *mpv_file_chapters_wl = Some(ipc::get_chapter_list(&ipc_data).await?);
+    }
+    let mut chapters = mpv_file_chapters_wl.clone().unwrap();
+    drop(mpv_file_chapters_wl);
+
+    chapters.extend(bookmarks.into_iter().map(|x| Chapter { title: x.label, time: x.timestamp }));
+    chapters.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
+    let mpv_ipc_tx = mpv_ipc_tx_rl.as_ref().unwrap();
+    mpv_ipc_tx.send(Interface::SetChapters(chapters)).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn mpv_set_comments(
+    state: tauri::State<'_, Arc<AppState>>,
+    comments: Vec<TimedComment>
+) -> Result<()> {
+    let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
+    let mpv_ipc_tx = mpv_ipc_tx_rl.as_ref().unwrap();
+    mpv_ipc_tx.send(Interface::SetComments(comments)).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn mpv_add_comment(
+    state: tauri::State<'_, Arc<AppState>>,
+    comment: TimedComment
+) -> Result<()> {
+    let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
+    let mpv_ipc_tx = mpv_ipc_tx_rl.as_ref().unwrap();
+    mpv_ipc_tx.send(Interface::AddComment(comment)).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn mpv_show_reaction(
+    state: tauri::State<'_, Arc<AppState>>,
+    name: String,
+    reaction: String
+) -> Result<()> {
+    let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
+    if let Some(mpv_ipc_tx) = mpv_ipc_tx_rl.as_ref() {
+        mpv_ipc_tx.send(Interface::ShowReaction { name, reaction }).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn mpv_show_chat(
+    state: tauri::State<'_, Arc<AppState>>,
+    name: String,
+    text: String
+) -> Result<()> {
+    let mpv_ipc_tx_rl = state.mpv_ipc_tx.read().await;
+    if let Some(mpv_ipc_tx) = mpv_ipc_tx_rl.as_ref() {
+        mpv_ipc_tx.send(Interface::ShowChat { name, text }).await?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn mpv_clear_msgs(
     state: tauri::State<'_, Arc<AppState>>,
@@ -23,12 +23,14 @@ use tokio::time::{sleep, Instant};
 use crate::appstate::AppState;
 use crate::{constants, mpv};
 use crate::error::SyncmiruError;
+use crate::mpv::models::{Chapter, TimedComment};
 use crate::result::Result;
 
 #[derive(Debug, PartialEq)]
 pub enum Interface {
-    LoadFromSource { source_url: String, jwt: String },
-    LoadFromUrl(String),
+    LoadFromSource { source_url: String, jwt: Option<String>, start: f64 },
+    SubAdd { source_url: String, jwt: Option<String>, title: String },
+    LoadFromUrl { url: String, start: f64 },
     SetPause(bool),
     Seek(f64),
     SetAudio(Option<u64>),
@@ -37,6 +39,12 @@ pub enum Interface {
     SetPlaybackSpeed(Decimal),
     SetAudioDelay(f64),
     SetSubDelay(f64),
+    SetAbLoop(Option<(f64, f64)>),
+    SetChapters(Vec<Chapter>),
+    SetComments(Vec<TimedComment>),
+    AddComment(TimedComment),
+    ShowReaction { name: String, reaction: String },
+    ShowChat { name: String, text: String },
     GetAid(u32),
     GetSid(u32),
     GetFullscreen(u32),
@@ -45,6 +53,7 @@ pub enum Interface {
     GetPlaybackSpeed(u32),
     GetAudioDelay(u32),
     GetSubDelay(u32),
+    GetChapterList(u32),
     ShowNotReadyMsg(Vec<String>),
     ShowLoadingMsg(Vec<String>),
     ShowMsg { id: u32, text: String, duration: f64, mood: MsgMood },
@@ -63,7 +72,8 @@ enum Property {
     Pause,
     PlaybackSpeed,
     AudioDelay,
-    SubDelay
+    SubDelay,
+    ChapterList
 }
 
 #[derive(Debug, PartialEq, Deserialize_repr)]
@@ -206,6 +216,18 @@ pub async fn get_speed(ipc_data: &IpcData) -> Result<Decimal> {
     Err(SyncmiruError::MpvReceiveResponseError)
 }
 
+pub async fn get_chapter_list(ipc_data: &IpcData) -> Result<Vec<Chapter>> {
+    let mut rx = send_with_response(ipc_data, Property::ChapterList).await?;
+    if let Some(json) = rx.recv().await {
+        if let Some(data) = json.get("data") {
+            if let Ok(chapters) = serde_json::from_value::<Vec<Chapter>>(data.clone()) {
+                return Ok(chapters)
+            }
+        }
+    }
+    Err(SyncmiruError::MpvReceiveResponseError)
+}
+
 async fn send_with_response(ipc_data: &IpcData, property: Property) -> Result<Receiver<serde_json::Value>> {
     let req_id = ipc_data.app_state.get_mpv_next_req_id().await;
 
@@ -223,7 +245,8 @@ async fn send_with_response(ipc_data: &IpcData, property: Property) -> Result<Re
             Property::Pause => { mpv_ipc_tx.send(Interface::GetPause(req_id)).await? },
             Property::PlaybackSpeed => { mpv_ipc_tx.send(Interface::GetPlaybackSpeed(req_id)).await? },
             Property::AudioDelay => { mpv_ipc_tx.send(Interface::GetAudioDelay(req_id)).await? },
-            Property::SubDelay => { mpv_ipc_tx.send(Interface::GetSubDelay(req_id)).await? }
+            Property::SubDelay => { mpv_ipc_tx.send(Interface::GetSubDelay(req_id)).await? },
+            Property::ChapterList => { mpv_ipc_tx.send(Interface::GetChapterList(req_id)).await? }
         }
         Ok(rx)
     }
@@ -270,21 +293,58 @@ async fn write(
     mut exit_tx_opt: Option<oneshot::Sender<()>>,
 ) -> Result<()> {
     init_observe_property(&sender).await?;
+    let mut loaded_jwt: Option<String> = None;
     loop {
         let msg_opt = rx.recv().await;
         if let Some(msg) = msg_opt {
             match msg {
-                Interface::LoadFromSource {ref source_url, ref jwt } => {
+                Interface::LoadFromSource {ref source_url, ref jwt, start } => {
+                    let cmd = match jwt {
+                        Some(jwt) => format!(
+                            "{{\"command\":  [\"loadfile\", \"{}\", \"replace\", -1, {{\"start\": \"{}\", \"http-header-fields\": \"Authorization: Bearer {}\"}}]}}\n",
+                            source_url,
+                            start,
+                            jwt
+                        ),
+                        None => format!(
+                            "{{\"command\":  [\"loadfile\", \"{}\", \"replace\", -1, {{\"start\": \"{}\"}}]}}\n",
+                            source_url,
+                            start
+                        )
+                    };
+                    sender.write_all(cmd.as_bytes()).await?;
+                    loaded_jwt = jwt.clone();
+                },
+                Interface::SubAdd { ref source_url, ref jwt, ref title } => {
+                    // mpv has no per-track HTTP headers, the header of the loaded file is swapped
+                    // only for the duration of sub-add, which downloads the subtitles synchronously
+                    if let Some(jwt) = jwt {
+                        let cmd = utils::create_set_property_cmd(
+                            "file-local-options/http-header-fields",
+                            &format!("Authorization: Bearer {}", jwt)
+                        );
+                        sender.write_all(cmd.as_bytes()).await?;
+                    }
                     let cmd = format!(
-                        "{{\"command\":  [\"loadfile\", \"{}\", \"replace\", -1, {{\"start\": \"0\", \"http-header-fields\": \"Authorization: Bearer {}\"}}]}}\n",
-                        source_url,
-                        jwt
+                        "{{\"command\": [\"sub-add\", {}, \"auto\", {}]}}\n",
+                        serde_json::to_string(source_url)?,
+                        serde_json::to_string(title)?
                     );
                     sender.write_all(cmd.as_bytes()).await?;
+                    if jwt.is_some() {
+                        let header = loaded_jwt
+                            .as_ref()
+                            .map(|x| format!("Authorization: Bearer {}", x))
+                            .unwrap_or_default();
+                        let cmd = utils::create_set_property_cmd("file-local-options/http-header-fields", &header);
+                        sender.write_all(cmd.as_bytes()).await?;
+                    }
                 },
-                Interface::LoadFromUrl(ref url) => {
-                    let cmd = format!("{{\"command\":  [\"loadfile\", \"{}\", \"replace\"]}}\n",
-                        url
+                Interface::LoadFromUrl { ref url, start } => {
+                    loaded_jwt = None;
+                    let cmd = format!("{{\"command\":  [\"loadfile\", \"{}\", \"replace\", -1, {{\"start\": \"{}\"}}]}}\n",
+                        url,
+                        start
                     );
                     sender.write_all(cmd.as_bytes()).await?;
                 },
@@ -335,6 +395,23 @@ async fn write(
                     let cmd = utils::create_set_property_cmd("sub-delay", &sub_delay);
                     sender.write_all(cmd.as_bytes()).await?;
                 },
+                Interface::SetAbLoop(ab_loop_opt) => {
+                    let (a, b) = match ab_loop_opt {
+                        Some((a, b)) => (a.to_string(), b.to_string()),
+                        None => ("no".to_string(), "no".to_string())
+                    };
+                    let cmd = utils::create_set_property_cmd("ab-loop-a", &a);
+                    sender.write_all(cmd.as_bytes()).await?;
+                    let cmd = utils::create_set_property_cmd("ab-loop-b", &b);
+                    sender.write_all(cmd.as_bytes()).await?;
+                },
+                Interface::SetChapters(ref chapters) => {
+                    let cmd = format!(
+                        "{{\"command\": [\"set_property\", \"chapter-list\", {}]}}\n",
+                        serde_json::to_string(chapters)?
+                    );
+                    sender.write_all(cmd.as_bytes()).await?;
+                },
                 Interface::GetAid(req_id) => {
                     let cmd = utils::create_get_property_cmd("aid", req_id);
                     sender.write_all(cmd.as_bytes()).await?;
@@ -367,6 +444,10 @@ async fn write(
                     let cmd = utils::create_get_property_cmd("sub-delay", req_id);
                     sender.write_all(cmd.as_bytes()).await?;
                 },
+                Interface::GetChapterList(req_id) => {
+                    let cmd = utils::create_get_property_cmd("chapter-list", req_id);
+                    sender.write_all(cmd.as_bytes()).await?;
+                },
                 Interface::ShowNotReadyMsg(ref names) => {
                     let mut mpv_not_ready_msg_id_wl = ipc_data.app_state.mpv_not_ready_msg_id.write().await;
                     if names.is_empty() {
@@ -457,6 +538,36 @@ async fn write(
                     );
                     sender.write_all(cmd.as_bytes()).await?;
                 },
+                Interface::SetComments(ref comments) => {
+                    let cmd = format!(
+                        "{{\"command\": [\"script-message-to\", \"prelude\", \"comments-set\", {}]}}\n",
+                        serde_json::to_string(&serde_json::to_string(comments)?)?
+                    );
+                    sender.write_all(cmd.as_bytes()).await?;
+                },
+                Interface::AddComment(ref comment) => {
+                    let cmd = format!(
+                        "{{\"command\": [\"script-message-to\", \"prelude\", \"comment-add\", {}]}}\n",
+                        serde_json::to_string(&serde_json::to_string(comment)?)?
+                    );
+                    sender.write_all(cmd.as_bytes()).await?;
+                },
+                Interface::ShowReaction { ref name, ref reaction } => {
+                    let cmd = format!(
+                        "{{\"command\": [\"script-message-to\", \"prelude\", \"reaction-add\", {}, {}]}}\n",
+                        serde_json::to_string(name)?,
+                        serde_json::to_string(reaction)?
+                    );
+                    sender.write_all(cmd.as_bytes()).await?;
+                },
+                Interface::ShowChat { ref name, ref text } => {
+                    let cmd = format!(
+                        "{{\"command\": [\"script-message-to\", \"prelude\", \"chat-add\", {}, {}]}}\n",
+                        serde_json::to_string(name)?,
+                        serde_json::to_string(text)?
+                    );
+                    sender.write_all(cmd.as_bytes()).await?;
+                },
                 Interface::DeleteMsg(id) => {
                     let cmd = format!("{{\"command\": [\"script-message-to\", \"prelude\", \"msg-del\", \"{}\"]}}\n", id);
                     sender.write_all(cmd.as_bytes()).await?;
@@ -711,13 +822,20 @@ async fn fullscreen_changed(fullscreen_state: bool, ipc_data: &IpcData) -> Resul
 }
 
 async fn process_client_msg(msg: &serde_json::Value, ipc_data: &IpcData) -> Result<()> {
-    if !constants::SUPPORTED_WINDOW_SYSTEM.get().unwrap() {
-        return Ok(())
-    }
-
     if let Some(args_value) = msg.get("args") {
         if let Some(args) = args_value.as_array() {
-            let cmd = args.get(0).unwrap().as_str().unwrap();
+            let Some(cmd) = args.first().and_then(|x| x.as_str()) else {
+                return Ok(())
+            };
+            if cmd == "chat-toggled" {
+                let shown = args.get(1).and_then(|x| x.as_str()) == Some("yes");
+                ipc_data.window.emit("mpv-chat-toggled", shown)?;
+                return Ok(())
+            }
+            if !constants::SUPPORTED_WINDOW_SYSTEM.get().unwrap() {
+                return Ok(())
+            }
+
             if cmd == "mouse-enter" {
                 cfg_if! {
                     if #[cfg(target_family = "unix")] {
@@ -757,6 +875,9 @@ fn process_end_file(msg: &serde_json::Value, ipc_data: &IpcData) -> Result<()> {
         if reason == "error" {
             ipc_data.window.emit("mpv-file-load-failed", {}).ok();
         }
+        else if reason == "eof" {
+            ipc_data.window.emit("mpv-file-ended", {}).ok();
+        }
     }
     Ok(())
 }
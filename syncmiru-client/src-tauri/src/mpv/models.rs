@@ -3,14 +3,48 @@ use rust_decimal::Decimal;
 #[derive(Debug, serde::Deserialize)]
 pub struct LoadFromSource {
     pub source_url: String,
-    pub jwt: String,
-    pub playback_speed: Decimal
+    pub jwt: Option<String>,
+    pub playback_speed: Decimal,
+    pub start: f64
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SubAdd {
+    pub source_url: String,
+    pub jwt: Option<String>,
+    pub title: String
 }
 
 #[derive(Debug, serde::Deserialize)]
 pub struct LoadFromUrl {
     pub url: String,
-    pub playback_speed: Decimal
+    pub playback_speed: Decimal,
+    pub start: f64
+}
+
+#[derive(Debug, Copy, Clone, serde::Deserialize)]
+pub struct AbLoop {
+    pub a: f64,
+    pub b: f64
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Chapter {
+    #[serde(default)]
+    pub title: String,
+    pub time: f64
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Bookmark {
+    pub timestamp: f64,
+    pub label: String
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TimedComment {
+    pub timestamp: f64,
+    pub text: String
 }
 
 #[derive(Debug, Copy, Clone, serde::Serialize)]
@@ -46,6 +46,12 @@ pub async fn mpv_before_file_load(
         *mpv_ignore_next_sub_delay_event_wl = true;
         mpv_ipc_tx.send(Interface::SetSubDelay(0f64)).await?;
     }
+    mpv_ipc_tx.send(Interface::SetAbLoop(None)).await?;
+    mpv_ipc_tx.send(Interface::SetComments(Vec::new())).await?;
+    {
+        let mut mpv_file_chapters_wl = state.mpv_file_chapters.write().await;
+        *mpv_file_chapters_wl = None;
+    }
 
     {
         let mut mpv_ignore_next_audio_change_event_wl = state.mpv_ignore_next_audio_change_event.write().await;
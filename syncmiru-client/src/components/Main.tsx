@@ -33,10 +33,11 @@ import {RoomConnectionState} from "@models/context.ts";
 import {UserRoomMap, UserRoomPingsClient} from "@models/roomUser.ts";
 import Decimal from "decimal.js";
 import {useIsSupportedWindowSystem} from "@hooks/useIsSupportedWindowSystem.ts";
-import {PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
+import {Bookmark, PlayingAccess, PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
 import {invoke} from "@tauri-apps/api/core";
 import {UserReadyState} from "@components/widgets/ReadyState.tsx";
-import {UserAudioSubtitles} from "@models/mpv.ts";
+import {AbLoop, UserAudioSubtitles} from "@models/mpv.ts";
+import {PresenceClient} from "@models/presence.ts";
 
 export default function Main(): ReactElement {
     const [location, navigate] = useLocation()
@@ -76,7 +77,7 @@ export default function Main(): ReactElement {
     const [source2url, setSource2url] = useState<Map<string, string>>(new Map<string, string>())
     const [playlist, setPlaylist] = useState<Map<PlaylistEntryId, PlaylistEntry>>(new Map<PlaylistEntryId, PlaylistEntry>())
     const [playlistOrder, setPlaylistOrder] = useState<Array<PlaylistEntryId>>([])
-    const [jwts, setJwts] = useState<Map<PlaylistEntryId, string>>(new Map<PlaylistEntryId, string>())
+    const [jwts, setJwts] = useState<Map<PlaylistEntryId, PlayingAccess>>(new Map<PlaylistEntryId, PlayingAccess>())
     const [mpvRunning, setMpvRunning] = useState<boolean>(false)
     const [modalShown, setModalShown] = useState<boolean>(false)
     const [settingsShown, setSettingsShown] = useState<boolean>(false)
@@ -86,6 +87,9 @@ export default function Main(): ReactElement {
     const [uid2audioSub, setUid2audioSub] = useState<Map<UserId, UserAudioSubtitles>>(new Map<UserId, UserAudioSubtitles>())
     const timestampTimerRef = useRef<number>(-1)
     const [reportedPlaybackSpeed, setReportedPlaybackSpeed] = useState<Decimal | null>(null)
+    const [abLoop, setAbLoop] = useState<AbLoop | null>(null)
+    const [bookmarks, setBookmarks] = useState<Array<Bookmark>>([])
+    const [uid2presence, setUid2presence] = useState<Map<UserId, PresenceClient>>(new Map<UserId, PresenceClient>())
 
     useEffect(() => {
         const s = io(homeSrv, {
@@ -152,7 +156,7 @@ export default function Main(): ReactElement {
         setSource2url(new Map<string, string>())
         setPlaylist(new Map<PlaylistEntryId, PlaylistEntry>())
         setPlaylistOrder([])
-        setJwts(new Map<PlaylistEntryId, string>())
+        setJwts(new Map<PlaylistEntryId, PlayingAccess>())
         setUid2ready(new Map<UserId, UserReadyState>())
         setActiveVideoId(null)
         setMpvRunning(false)
@@ -324,7 +328,13 @@ export default function Main(): ReactElement {
                     setUid2audioSub: setUid2audioSub,
                     timestampTimerRef: timestampTimerRef,
                     reportedPlaybackSpeed: reportedPlaybackSpeed,
-                    setReportedPlaybackSpeed: setReportedPlaybackSpeed
+                    setReportedPlaybackSpeed: setReportedPlaybackSpeed,
+                    abLoop: abLoop,
+                    setAbLoop: setAbLoop,
+                    bookmarks: bookmarks,
+                    setBookmarks: setBookmarks,
+                    uid2presence: uid2presence,
+                    setUid2presence: setUid2presence
                 }}>
                 <div className={`flex w-dvw ${showMainContent() ? '' : 'hidden'}`}>
                     <div className="flex flex-col min-w-60 w-60 h-dvh">
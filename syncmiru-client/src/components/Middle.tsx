@@ -1,6 +1,7 @@
 import React, {ReactElement, useRef, useState} from "react";
 import Mpv from "@components/mpv/Mpv.tsx";
 import Playlist from "@components/playlist/Playlist.tsx";
+import RoomChat from "@components/chat/RoomChat.tsx";
 import {ImperativePanelHandle, Panel, PanelGroup, PanelResizeHandle} from "react-resizable-panels";
 import {useMainContext} from "@hooks/useMainContext.ts";
 
@@ -21,9 +22,23 @@ export default function Middle(): ReactElement {
                 <Panel
                     defaultSize={30}
                     minSize={27}
-                    style={{overflow: "auto"}}
                 >
-                    <Playlist/>
+                    <PanelGroup direction="horizontal">
+                        <Panel
+                            defaultSize={70}
+                            minSize={40}
+                            style={{overflow: "auto"}}
+                        >
+                            <Playlist/>
+                        </Panel>
+                        <PanelResizeHandle className="w-1 bg-slate-300 dark:bg-slate-400"/>
+                        <Panel
+                            defaultSize={30}
+                            minSize={20}
+                        >
+                            <RoomChat/>
+                        </Panel>
+                    </PanelGroup>
                 </Panel>
                 <PanelResizeHandle className={`h-1 bg-slate-300 dark:bg-slate-400 group ${mpvWinDetached ? 'hidden' : ''}`}>
                     <div
@@ -0,0 +1,96 @@
+import {FormEvent, ReactElement, useEffect, useRef, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {Input} from "@components/widgets/Input.tsx";
+import {RoomConnectionState} from "@models/context.ts";
+import {ChatMessage} from "@models/chat.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+
+const CHAT_MESSAGES_MAX = 200
+const CHAT_MESSAGE_MAX_LENGTH = 500
+
+export default function RoomChat(): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const [messages, setMessages] = useState<Array<ShownChatMessage>>([])
+    const [content, setContent] = useState<string>('')
+    const nextIdRef = useRef<number>(0)
+    const bottomRef = useRef<HTMLDivElement>(null)
+    const connectedToRoom = ctx.currentRid != null && ctx.roomConnection === RoomConnectionState.Established
+
+    useEffect(() => {
+        if (ctx.socket !== undefined) {
+            ctx.socket.on('chat_message', onChatMessage)
+        }
+        return () => {
+            if (ctx.socket !== undefined) {
+                ctx.socket.off('chat_message', onChatMessage)
+            }
+        }
+    }, [ctx.socket]);
+
+    useEffect(() => {
+        setMessages([])
+        setContent('')
+    }, [ctx.currentRid]);
+
+    useEffect(() => {
+        bottomRef.current?.scrollIntoView()
+    }, [messages.length > 0 ? messages[messages.length - 1].id : null]);
+
+    function onChatMessage(message: ChatMessage) {
+        const id = nextIdRef.current++
+        setMessages((m) => [...m, {id: id, ...message}].slice(-CHAT_MESSAGES_MAX))
+    }
+
+    function sendMessage(e: FormEvent<HTMLFormElement>) {
+        e.preventDefault()
+        if (content.trim().length === 0)
+            return
+
+        ctx.socket!.emitWithAck('send_chat_message', {content: content})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err) {
+                    showPersistentErrorAlert(t('chat-send-error'))
+                    return
+                }
+                setContent('')
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('chat-send-error'))
+            })
+    }
+
+    if (!connectedToRoom)
+        return <></>
+    return (
+        <div className="flex flex-col h-full p-2">
+            <p className="font-semibold mb-1">{t('chat-title')}</p>
+            <div className="flex flex-col flex-1 gap-y-1 overflow-y-auto mb-2">
+                {messages.length === 0 && <p className="self-center opacity-70">{t('chat-empty')}</p>}
+                {messages.map((message) => {
+                    return (
+                        <p key={message.id} className="break-words">
+                            <span className="font-semibold">{ctx.users.get(message.uid)?.displayname ?? ''}: </span>
+                            {message.content}
+                        </p>
+                    )
+                })}
+                <div ref={bottomRef}></div>
+            </div>
+            <form onSubmit={sendMessage} noValidate>
+                <Input
+                    placeholder={t('chat-placeholder')}
+                    maxLength={CHAT_MESSAGE_MAX_LENGTH}
+                    value={content}
+                    onChange={(e) => setContent(e.target.value)}
+                />
+            </form>
+        </div>
+    )
+}
+
+interface ShownChatMessage extends ChatMessage {
+    id: number
+}
\ No newline at end of file
@@ -7,25 +7,46 @@ import {disconnectFromRoom, forceDisconnectFromRoom} from "src/utils/room.ts";
 import {RoomConnectionState} from "@models/context.ts";
 import {invoke} from "@tauri-apps/api/core";
 import {showPersistentErrorAlert} from "src/utils/alert.ts";
-import {PlaylistEntry, PlaylistEntryId, PlaylistEntryUrl, PlaylistEntryVideo} from "@models/playlist.ts";
+import {
+    PlayingAccess,
+    PlaylistEntry,
+    PlaylistEntryId,
+    PlaylistEntryUrl,
+    PlaylistEntryVideo,
+    SubtitleId,
+    SubtitleInfo,
+    ClipEnded,
+    Bookmark,
+    BookmarkAdded,
+    BookmarkDeleted,
+    Comment,
+    CommentAdded,
+    CommentsWindow
+} from "@models/playlist.ts";
+import {loadFromSourceData, loadUploadedSubtitle, subAddData} from "src/utils/playlist.ts";
 import {
     MpvState,
+    AbLoop,
     UserAudioSubtitles,
     UserChangeAudio,
     UserChangeAudioDelay,
     UserChangeSub,
     UserChangeSubDelay,
     UserLoadedInfo,
+    UserLoop,
     UserPause,
+    UserReaction,
     UserPlayInfo,
     UserSeek,
     UserSpeedChangeClient,
     UserSpeedChangeSrv,
     UserUploadMpvState
 } from "@models/mpv.ts";
+import {ChatMessage} from "@models/chat.ts";
 import {UserReadyState} from "@components/widgets/ReadyState.tsx";
 import {UserId} from "@models/user.ts";
 import {MpvMsgMood, showMpvReadyMessages, timestampPretty} from "src/utils/mpv.ts";
+import {AB_LOOP_JUMP_TOLERANCE} from "src/utils/constants.ts";
 import Decimal from "decimal.js";
 import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
 import {useIsSupportedWindowSystem} from "@hooks/useIsSupportedWindowSystem.ts";
@@ -46,6 +67,10 @@ export default function Mpv(p: Props): ReactElement {
     const activeVideoIdRef = useRef(ctx.activeVideoId)
     const uid2readyRef = useRef(ctx.uid2ready)
     const uid2audioSubRef = useRef(ctx.uid2audioSub)
+    const abLoopRef = useRef(ctx.abLoop)
+    const bookmarksRef = useRef(ctx.bookmarks)
+    const loadedSubtitlesRef = useRef<Set<SubtitleId>>(new Set<SubtitleId>())
+    const commentsWindowRef = useRef<CommentsWindowRange | null>(null)
 
     useEffect(() => {
         if (ctx.socket !== undefined) {
@@ -58,7 +83,12 @@ export default function Mpv(p: Props): ReactElement {
             ctx.socket.on('major_desync_seek', onMajorDesyncSeek)
             ctx.socket.on('minor_desync_start', onMinorDesyncStart)
             ctx.socket.on('minor_desync_stop', onMinorDesyncStop)
-
+            ctx.socket.on('add_subtitle', onAddSubtitle)
+            ctx.socket.on('add_bookmark', onAddBookmark)
+            ctx.socket.on('del_bookmark', onDelBookmark)
+            ctx.socket.on('add_comment', onAddComment)
+            ctx.socket.on('react', onReact)
+            ctx.socket.on('chat_message', onChatMessage)
         }
         return () => {
             if (ctx.socket !== undefined) {
@@ -70,6 +100,12 @@ export default function Mpv(p: Props): ReactElement {
                 ctx.socket.off('major_desync_seek', onMajorDesyncSeek)
                 ctx.socket.off('minor_desync_start', onMinorDesyncStart)
                 ctx.socket.off('minor_desync_stop', onMinorDesyncStop)
+                ctx.socket.off('add_subtitle', onAddSubtitle)
+                ctx.socket.off('add_bookmark', onAddBookmark)
+                ctx.socket.off('del_bookmark', onDelBookmark)
+                ctx.socket.off('add_comment', onAddComment)
+                ctx.socket.off('react', onReact)
+                ctx.socket.off('chat_message', onChatMessage)
             }
         }
     }, [ctx.socket]);
@@ -91,7 +127,8 @@ export default function Mpv(p: Props): ReactElement {
             ctx.socket.on('mpv_pause', onMpvPause)
             ctx.socket.on("mpv_seek", onMpvSeek)
             ctx.socket.on("mpv_speed_change", onMpvSpeedChange)
-
+            ctx.socket.on("clip_ended", onClipEnded)
+            ctx.socket.on("loop", onLoop)
         }
         return () => {
             if (ctx.socket !== undefined) {
@@ -99,7 +136,8 @@ export default function Mpv(p: Props): ReactElement {
                 ctx.socket.off('mpv_pause', onMpvPause)
                 ctx.socket.off("mpv_seek", onMpvSeek)
                 ctx.socket.off("mpv_speed_change", onMpvSpeedChange)
-
+                ctx.socket.off("clip_ended", onClipEnded)
+                ctx.socket.off("loop", onLoop)
             }
         }
     }, [ctx.socket, ctx.uid, ctx.uid2ready]);
@@ -175,6 +213,14 @@ export default function Mpv(p: Props): ReactElement {
             ctx.setMpvWinDetached(e.payload)
         }))
 
+        unlisten.push(listen<boolean>('mpv-chat-toggled', (e: Event<boolean>) => {
+            const msgText = e.payload ? t('mpv-msg-chat-shown') : t('mpv-msg-chat-hidden')
+            invoke('mpv_show_msg', {text: msgText, duration: 3, mood: MpvMsgMood.Neutral})
+                .catch(() => {
+                    showPersistentErrorAlert(t('mpv-msg-show-failed'))
+                })
+        }))
+
         unlisten.push(listen<void>('mpv-file-loaded', (e: Event<void>) => {
             const entry = playlistRef.current.get(activeVideoIdRef.current as PlaylistEntryId) as PlaylistEntry
 
@@ -184,7 +230,54 @@ export default function Mpv(p: Props): ReactElement {
                     showPersistentErrorAlert(t('mpv-msg-show-failed'))
                 })
 
-            invoke<UserLoadedInfo>('mpv_get_loaded_info', {})
+            const entryId = activeVideoIdRef.current as PlaylistEntryId
+            loadedSubtitlesRef.current = new Set<SubtitleId>()
+            let subsAdded: Promise<unknown> = Promise.resolve()
+            if (entry instanceof PlaylistEntryVideo) {
+                const access = jwtsRef.current.get(activeVideoIdRef.current as PlaylistEntryId) as PlayingAccess
+                const source = source2urlRef.current.get(entry.source) as string
+                subsAdded = Promise.all(access.subtitles.map((sub) =>
+                    invoke('mpv_sub_add', {data: JSON.stringify(subAddData(sub, source))})
+                ))
+                    .catch(() => {
+                        showPersistentErrorAlert(t('mpv-sub-add-error'))
+                    })
+            }
+            // uploaded subtitles are added one by one in upload order, so that every member ends up with the same sid
+            subsAdded = subsAdded
+                .then(() => ctx.socket!.emitWithAck('get_entry_subtitles', {playlist_entry_id: entryId}))
+                .then(async (ack: SocketIoAck<Array<SubtitleInfo>>) => {
+                    if (ack.status === SocketIoAckType.Err)
+                        throw new Error('get_entry_subtitles failed')
+
+                    for (const sub of ack.payload as Array<SubtitleInfo>) {
+                        if (loadedSubtitlesRef.current.has(sub.id))
+                            continue
+                        loadedSubtitlesRef.current.add(sub.id)
+                        await loadUploadedSubtitle(ctx, entryId, sub.id)
+                    }
+                })
+                .catch(() => {
+                    showPersistentErrorAlert(t('mpv-sub-add-error'))
+                })
+
+            ctx.socket!.emitWithAck('get_bookmarks', {playlist_entry_id: entryId})
+                .then((ack: SocketIoAck<Array<Bookmark>>) => {
+                    if (ack.status === SocketIoAckType.Err)
+                        throw new Error('get_bookmarks failed')
+
+                    if (entryId === activeVideoIdRef.current)
+                        setBookmarks(ack.payload as Array<Bookmark>)
+                })
+                .catch(() => {
+                    showPersistentErrorAlert(t('bookmarks-load-error'))
+                })
+
+            commentsWindowRef.current = null
+            loadComments(entryId, 0)
+
+            subsAdded
+                .then(() => invoke<UserLoadedInfo>('mpv_get_loaded_info', {}))
                 .then((payload: UserLoadedInfo) => {
                     ctx.socket!.emitWithAck('mpv_file_loaded', payload)
                         .then((ack: SocketIoAck<null>) => {
@@ -206,6 +299,7 @@ export default function Mpv(p: Props): ReactElement {
                                                     invoke('mpv_seek', {timestamp: payload.timestamp})
                                                         .then(() => {
                                                             startTimestampTimer()
+                                                            setAbLoop(payload.ab_loop)
 
                                                             invoke<boolean>('mpv_get_pause', {})
                                                                 .then((pause: boolean) => {
@@ -267,6 +361,14 @@ export default function Mpv(p: Props): ReactElement {
                 })
         }))
 
+        unlisten.push(listen<void>('mpv-file-ended', (e: Event<void>) => {
+            if (activeVideoIdRef.current == null)
+                return
+
+            ctx.socket!.emitWithAck('mpv_file_ended', {playlist_entry_id: activeVideoIdRef.current})
+                .catch(() => {})
+        }))
+
         unlisten.push(listen<boolean>('mpv-pause-changed', (e: Event<boolean>) => {
             const readyState = ctx.uid2ready.get(ctx.uid)
             if (readyState == null || [UserReadyState.Loading, UserReadyState.Error].includes(readyState))
@@ -316,6 +418,12 @@ export default function Mpv(p: Props): ReactElement {
 
             invoke<number>('mpv_get_timestamp', {})
                 .then((time: number) => {
+                    // mpv jumping back to the start of the loop is not a seek of the user
+                    const abLoop = abLoopRef.current
+                    if (abLoop != null && Math.abs(time - abLoop.a) < AB_LOOP_JUMP_TOLERANCE) {
+                        mpvSeekingRef.current = false
+                        return
+                    }
                     ctx.socket!.emitWithAck('mpv_seek', time)
                         .then((ack: SocketIoAck<null>) => {
                             if (ack.status === SocketIoAckType.Err) {
@@ -441,7 +549,9 @@ export default function Mpv(p: Props): ReactElement {
         activeVideoIdRef.current = ctx.activeVideoId
         uid2readyRef.current = ctx.uid2ready
         uid2audioSubRef.current = ctx.uid2audioSub
-    }, [ctx.jwts, ctx.source2url, ctx.playlist, ctx.joinedRoomSettings, ctx.users, ctx.activeVideoId, ctx.uid2ready, ctx.uid2audioSub]);
+        abLoopRef.current = ctx.abLoop
+        bookmarksRef.current = ctx.bookmarks
+    }, [ctx.jwts, ctx.source2url, ctx.playlist, ctx.joinedRoomSettings, ctx.users, ctx.activeVideoId, ctx.uid2ready, ctx.uid2audioSub, ctx.abLoop, ctx.bookmarks]);
 
     useEffect(() => {
         if (ctx.mpvRunning && !ctx.mpvWinDetached && !ctx.mpvShowSmall)
@@ -468,14 +578,10 @@ export default function Mpv(p: Props): ReactElement {
         const id = ctx.activeVideoId as PlaylistEntryId
         const entry = playlistRef.current.get(id) as PlaylistEntry
         if (entry instanceof PlaylistEntryVideo) {
-            const jwt = jwtsRef.current.get(id) as string
+            const access = jwtsRef.current.get(id) as PlayingAccess
             const video = entry as PlaylistEntryVideo
             const source = source2urlRef.current.get(video.source) as string
-            const data = {
-                source_url: source,
-                jwt: jwt,
-                playback_speed: joinedRoomSettingsRef.current.playback_speed
-            }
+            const data = loadFromSourceData(access, source, joinedRoomSettingsRef.current.playback_speed, video.clip)
             invoke<UserLoadedInfo>('mpv_load_from_source', {data: JSON.stringify(data)})
                 .then(() => {
                     showMpvReadyMessages(uid2readyRef.current, usersRef.current, t)
@@ -488,7 +594,8 @@ export default function Mpv(p: Props): ReactElement {
             const video = entry as PlaylistEntryUrl
             const data = {
                 url: video.url,
-                playback_speed: joinedRoomSettingsRef.current.playback_speed
+                playback_speed: joinedRoomSettingsRef.current.playback_speed,
+                start: video.clip.start ?? 0
             }
             invoke('mpv_load_from_url', {data: JSON.stringify(data)})
                 .then(() => {
@@ -700,6 +807,59 @@ export default function Mpv(p: Props): ReactElement {
         }
     }
 
+    function onClipEnded(payload: ClipEnded) {
+        const readyState = ctx.uid2ready.get(ctx.uid)
+        if (readyState == null || [UserReadyState.Loading, UserReadyState.Error].includes(readyState))
+            return
+        if (payload.playlist_entry_id !== activeVideoIdRef.current)
+            return
+
+        invoke('mpv_seek', {timestamp: payload.timestamp})
+            .then(() => {
+                invoke('mpv_set_pause', {pause: true})
+                    .catch(() => {
+                        showPersistentErrorAlert(t('mpv-pause-error'))
+                        disconnectFromRoom(ctx, t)
+                    })
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-seek-error'))
+                disconnectFromRoom(ctx, t)
+            })
+        invoke('mpv_show_msg', {text: t('mpv-msg-clip-ended'), duration: 5, mood: MpvMsgMood.Neutral})
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-msg-show-failed'))
+            })
+    }
+
+    function onLoop(payload: UserLoop) {
+        const readyState = ctx.uid2ready.get(ctx.uid)
+        if (readyState == null || [UserReadyState.Loading, UserReadyState.Error].includes(readyState))
+            return
+
+        setAbLoop(payload.ab_loop)
+        const userValue = usersRef.current.get(payload.uid)
+        if (userValue != null) {
+            const msgText = payload.ab_loop != null
+                ? `${userValue.displayname} ${t('mpv-msg-user-loop')} ${timestampPretty(payload.ab_loop.a)} – ${timestampPretty(payload.ab_loop.b)}`
+                : `${userValue.displayname} ${t('mpv-msg-user-loop-cancel')}`
+            invoke('mpv_show_msg', {text: msgText, duration: 5, mood: MpvMsgMood.Neutral})
+                .catch(() => {
+                    showPersistentErrorAlert(t('mpv-msg-show-failed'))
+                })
+        }
+    }
+
+    function setAbLoop(abLoop: AbLoop | null) {
+        ctx.setAbLoop(abLoop)
+        abLoopRef.current = abLoop
+        invoke('mpv_set_ab_loop', {abLoop: abLoop})
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-loop-error'))
+                disconnectFromRoom(ctx, t)
+            })
+    }
+
     function onMpvSpeedChange(payload: UserSpeedChangeSrv) {
         const {speed, ...p} = payload
         const clientPayload: UserSpeedChangeClient = {
@@ -871,6 +1031,139 @@ export default function Mpv(p: Props): ReactElement {
         }
     }
 
+    function onAddSubtitle(payload: SubtitleInfo) {
+        const myReadyStatus = uid2readyRef.current.get(ctx.uid)
+        if (myReadyStatus == null || ![UserReadyState.NotReady, UserReadyState.Ready].includes(myReadyStatus))
+            return
+        if (payload.playlist_entry_id !== activeVideoIdRef.current || loadedSubtitlesRef.current.has(payload.id))
+            return
+
+        loadedSubtitlesRef.current.add(payload.id)
+        loadUploadedSubtitle(ctx, payload.playlist_entry_id, payload.id)
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-sub-add-error'))
+            })
+    }
+
+    function setBookmarks(bookmarks: Array<Bookmark>) {
+        ctx.setBookmarks(bookmarks)
+        bookmarksRef.current = bookmarks
+        invoke('mpv_set_bookmarks', {bookmarks: bookmarks})
+            .catch(() => {
+                showPersistentErrorAlert(t('bookmarks-load-error'))
+            })
+    }
+
+    function onAddBookmark(payload: BookmarkAdded) {
+        if (payload.playlist_entry_id !== activeVideoIdRef.current)
+            return
+
+        const bookmarks = [...bookmarksRef.current, payload.bookmark]
+            .sort((a, b) => a.timestamp - b.timestamp)
+        const myReadyStatus = uid2readyRef.current.get(ctx.uid)
+        if (myReadyStatus == null || ![UserReadyState.NotReady, UserReadyState.Ready].includes(myReadyStatus)) {
+            // the chapters are set once the file is loaded
+            ctx.setBookmarks(bookmarks)
+            bookmarksRef.current = bookmarks
+            return
+        }
+        setBookmarks(bookmarks)
+
+        const userValue = payload.bookmark.uid != null ? usersRef.current.get(payload.bookmark.uid) : undefined
+        if (userValue != null) {
+            const msgText = `${userValue.displayname} ${t('mpv-msg-bookmark-added')} ${timestampPretty(payload.bookmark.timestamp)} ${payload.bookmark.label}`
+            invoke('mpv_show_msg', {text: msgText, duration: 5, mood: MpvMsgMood.Neutral})
+                .catch(() => {
+                    showPersistentErrorAlert(t('mpv-msg-show-failed'))
+                })
+        }
+    }
+
+    function onDelBookmark(payload: BookmarkDeleted) {
+        if (payload.playlist_entry_id !== activeVideoIdRef.current)
+            return
+
+        const bookmarks = bookmarksRef.current.filter(x => x.id !== payload.bookmark_id)
+        const myReadyStatus = uid2readyRef.current.get(ctx.uid)
+        if (myReadyStatus == null || ![UserReadyState.NotReady, UserReadyState.Ready].includes(myReadyStatus)) {
+            ctx.setBookmarks(bookmarks)
+            bookmarksRef.current = bookmarks
+            return
+        }
+        setBookmarks(bookmarks)
+    }
+
+    function loadComments(entryId: PlaylistEntryId, timestamp: number) {
+        // a new window is requested once the playback leaves the loaded one or passes its middle,
+        // the window size is decided by the server
+        const loaded = commentsWindowRef.current
+        if (loaded != null
+            && timestamp >= loaded.from
+            && (loaded.to == null || timestamp < (loaded.from + loaded.to) / 2))
+            return
+
+        const from = Math.floor(timestamp)
+        commentsWindowRef.current = {from: from, to: null}
+        ctx.socket!.emitWithAck('get_comments', {playlist_entry_id: entryId, from: from})
+            .then((ack: SocketIoAck<CommentsWindow>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    throw new Error('get_comments failed')
+
+                const commentsWindow = ack.payload as CommentsWindow
+                if (entryId === activeVideoIdRef.current && from === commentsWindowRef.current?.from) {
+                    commentsWindowRef.current = {from: from, to: commentsWindow.to}
+                    const comments = commentsWindow.comments.map(commentToTimed)
+                    return invoke('mpv_set_comments', {comments: comments})
+                }
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('comments-load-error'))
+            })
+    }
+
+    function commentToTimed(comment: Comment) {
+        const userValue = comment.uid != null ? usersRef.current.get(comment.uid) : undefined
+        return {
+            timestamp: comment.timestamp,
+            text: userValue != null ? `${userValue.displayname}: ${comment.content}` : comment.content
+        }
+    }
+
+    function onAddComment(payload: CommentAdded) {
+        const myReadyStatus = uid2readyRef.current.get(ctx.uid)
+        if (myReadyStatus == null || ![UserReadyState.NotReady, UserReadyState.Ready].includes(myReadyStatus))
+            return
+        if (payload.playlist_entry_id !== activeVideoIdRef.current)
+            return
+
+        invoke('mpv_add_comment', {comment: commentToTimed(payload.comment)})
+            .catch(() => {
+                showPersistentErrorAlert(t('comments-load-error'))
+            })
+    }
+
+    function onReact(payload: UserReaction) {
+        const userValue = usersRef.current.get(payload.uid)
+        if (userValue == null)
+            return
+
+        invoke('mpv_show_reaction', {name: userValue.displayname, reaction: payload.reaction})
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-msg-show-failed'))
+            })
+    }
+
+    function onChatMessage(payload: ChatMessage) {
+        const userValue = usersRef.current.get(payload.uid)
+        if (userValue == null)
+            return
+
+        invoke('mpv_show_chat', {name: userValue.displayname, text: payload.content})
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-msg-show-failed'))
+            })
+    }
+
     function onMpvSubChange(payload: UserChangeSub) {
         const myReadyStatus = uid2readyRef.current.get(ctx.uid)
         if (myReadyStatus == null || ![UserReadyState.NotReady, UserReadyState.Ready].includes(myReadyStatus))
@@ -1202,6 +1495,8 @@ export default function Mpv(p: Props): ReactElement {
                 invoke<number>('mpv_get_timestamp', {})
                     .then((time: number) => {
                         ctx.socket!.emitWithAck('timestamp_tick', time)
+                        if (activeVideoIdRef.current != null)
+                            loadComments(activeVideoIdRef.current, time)
                     })
             }
         }, 1000)
@@ -1221,4 +1516,9 @@ export default function Mpv(p: Props): ReactElement {
 interface Props {
     mpvResizeVar: boolean,
     setMpvResizeVar: (b: boolean) => void
+}
+
+interface CommentsWindowRange {
+    from: number,
+    to: number | null
 }
\ No newline at end of file
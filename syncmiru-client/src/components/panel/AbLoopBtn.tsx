@@ -0,0 +1,73 @@
+import {ReactElement, useEffect, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {invoke} from "@tauri-apps/api/core";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {Clickable} from "@components/widgets/Button.tsx";
+import Repeat from "@components/svg/Repeat.tsx";
+import {UserReadyState} from "@components/widgets/ReadyState.tsx";
+import {AbLoop} from "@models/mpv.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import {timestampPretty} from "src/utils/mpv.ts";
+
+export default function AbLoopBtn(): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const [pendingA, setPendingA] = useState<number | null>(null)
+    const readyStatus = ctx.uid2ready.get(ctx.uid)
+
+    useEffect(() => {
+        setPendingA(null)
+    }, [ctx.activeVideoId, ctx.abLoop]);
+
+    function setLoop(abLoop: AbLoop | null) {
+        ctx.socket!.emitWithAck('set_loop', {ab_loop: abLoop})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('mpv-loop-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-loop-error'))
+            })
+    }
+
+    function abLoopClicked() {
+        if (ctx.abLoop != null) {
+            setLoop(null)
+            return
+        }
+        invoke<number>('mpv_get_timestamp', {})
+            .then((time: number) => {
+                if (pendingA == null) {
+                    setPendingA(time)
+                    return
+                }
+                setPendingA(null)
+                if (time !== pendingA)
+                    setLoop({a: Math.min(pendingA, time), b: Math.max(pendingA, time)})
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-loop-error'))
+            })
+    }
+
+    function title() {
+        if (ctx.abLoop != null)
+            return `${timestampPretty(ctx.abLoop.a)} – ${timestampPretty(ctx.abLoop.b)}`
+        if (pendingA != null)
+            return `${timestampPretty(pendingA)} – ${t('ab-loop-set-b')}`
+        return t('ab-loop-set-a')
+    }
+
+    if (readyStatus !== UserReadyState.Ready && readyStatus !== UserReadyState.NotReady)
+        return <></>
+    return (
+        <div title={title()}>
+            <Clickable className="p-2 flex items-center gap-x-1" onClick={abLoopClicked}>
+                <Repeat className={`h-7 ${ctx.abLoop == null && pendingA == null ? 'opacity-50' : ''}`}/>
+                {pendingA != null && <p className="text-sm">A</p>}
+                {ctx.abLoop != null && <p className="text-sm">A-B</p>}
+            </Clickable>
+        </div>
+    )
+}
\ No newline at end of file
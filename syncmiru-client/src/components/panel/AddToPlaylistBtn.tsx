@@ -8,6 +8,7 @@ import {useTranslation} from "react-i18next";
 import {Menu, MenuButton} from "@szhsin/react-menu";
 import AddVideoFromFileSrv from "@components/panel/AddVideoFromFileSrv.tsx";
 import AddUrlAddress from "@components/panel/AddUrlAddress.tsx";
+import UploadVideo from "@components/panel/UploadVideo.tsx";
 
 export default function AddToPlaylistBtn(): ReactElement {
     const {t} = useTranslation()
@@ -47,6 +48,7 @@ export default function AddToPlaylistBtn(): ReactElement {
                 <hr className="my-2"/>
                 <AddVideoFromFileSrv/>
                 <AddUrlAddress/>
+                <UploadVideo/>
             </div>
         </Menu>
     )
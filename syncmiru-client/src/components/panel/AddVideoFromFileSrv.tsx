@@ -4,6 +4,7 @@ import {MenuItem} from "@szhsin/react-menu";
 import {useTranslation} from "react-i18next";
 import {ModalWHeader} from "@components/widgets/Modal.tsx";
 import FilePicker from "@components/panel/FilePicker.tsx";
+import FileSearch from "@components/panel/FileSearch.tsx";
 import {BtnPrimary} from "@components/widgets/Button.tsx";
 import {FileKind} from "@models/file.ts";
 import {useMainContext} from "@hooks/useMainContext.ts";
@@ -41,6 +42,23 @@ export default function AddVideoFromFileSrv(): ReactElement {
             })
     }
 
+    function addDirectory(source: string, path: string, recursive: boolean) {
+        setShowModal(false)
+        setPlaylistLoading(true)
+        socket!.emitWithAck("add_directory", {file_srv: source, path: path, recursive: recursive})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err) {
+                    showPersistentErrorAlert(t('playlist-add-directory-error'))
+                }
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('playlist-add-directory-error'))
+            })
+            .finally(() => {
+                setPlaylistLoading(false)
+            })
+    }
+
     return (
         <>
             <MenuItem
@@ -58,10 +76,16 @@ export default function AddVideoFromFileSrv(): ReactElement {
                 setOpen={setShowModal}
                 content={
                     <div className="flex flex-col">
+                        <FileSearch
+                            filesPicked={filesPicked}
+                            setFilesPicked={setFilesPicked}
+                        />
+                        <hr className="my-4"/>
                         <FilePicker
                             filesPicked={filesPicked}
                             setFilesPicked={setFilesPicked}
                             fileKind={FileKind.Video}
+                            onAddDirectory={addDirectory}
                         />
                         <BtnPrimary
                             className="mt-4"
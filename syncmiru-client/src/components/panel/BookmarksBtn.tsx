@@ -0,0 +1,153 @@
+import {ReactElement, useState} from "react";
+import {useTranslation} from "react-i18next";
+import Joi from "joi";
+import {useForm} from "react-hook-form";
+import {joiResolver} from "@hookform/resolvers/joi";
+import {invoke} from "@tauri-apps/api/core";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {Clickable, DeleteBtn, BtnPrimary} from "@components/widgets/Button.tsx";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {Input} from "@components/widgets/Input.tsx";
+import BookmarkSvg from "@components/svg/Bookmark.tsx";
+import {UserReadyState} from "@components/widgets/ReadyState.tsx";
+import {Bookmark, PlaylistEntryId} from "@models/playlist.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import {timestampPretty} from "src/utils/mpv.ts";
+
+export default function BookmarksBtn(): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const [open, setOpen] = useState<boolean>(false)
+    const readyStatus = ctx.uid2ready.get(ctx.uid)
+
+    const formSchema = Joi.object({
+        label: Joi
+            .string()
+            .trim()
+            .min(1)
+            .max(64)
+            .required()
+            .messages({"string.empty": t('bookmarks-label-empty'), "string.max": t('bookmarks-label-too-long')})
+    })
+
+    const {
+        register,
+        handleSubmit,
+        reset,
+        formState: {errors}
+    } = useForm<FormFields>({resolver: joiResolver(formSchema)});
+
+    function bookmarksClicked() {
+        reset({label: ''})
+        setOpen(true)
+    }
+
+    function addBookmark(data: FormFields) {
+        invoke<number>('mpv_get_timestamp', {})
+            .then((time: number) => ctx.socket!.emitWithAck('add_bookmark', {
+                playlist_entry_id: ctx.activeVideoId as PlaylistEntryId,
+                timestamp: time,
+                label: data.label
+            }))
+            .then((ack: SocketIoAck<number>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('bookmarks-add-error'))
+                else
+                    reset({label: ''})
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('bookmarks-add-error'))
+            })
+    }
+
+    function deleteBookmark(bookmark: Bookmark) {
+        ctx.socket!.emitWithAck('delete_bookmark', {
+            playlist_entry_id: ctx.activeVideoId as PlaylistEntryId,
+            bookmark_id: bookmark.id
+        })
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('bookmarks-delete-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('bookmarks-delete-error'))
+            })
+    }
+
+    function jumpToBookmark(bookmark: Bookmark) {
+        setOpen(false)
+        invoke('mpv_seek', {timestamp: bookmark.timestamp})
+            .then(() => ctx.socket!.emitWithAck('mpv_seek', bookmark.timestamp))
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('mpv-seek-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('mpv-seek-error'))
+            })
+    }
+
+    function authorName(bookmark: Bookmark) {
+        if (bookmark.uid == null)
+            return ''
+        return ctx.users.get(bookmark.uid)?.displayname ?? ''
+    }
+
+    if (readyStatus !== UserReadyState.Ready && readyStatus !== UserReadyState.NotReady)
+        return <></>
+    return (
+        <>
+            <div title={t('bookmarks-title')}>
+                <Clickable className="p-2 flex items-center gap-x-1" onClick={bookmarksClicked}>
+                    <BookmarkSvg className="h-7"/>
+                    {ctx.bookmarks.length > 0 && <p className="text-sm">{ctx.bookmarks.length}</p>}
+                </Clickable>
+            </div>
+            <ModalWHeader
+                title={t('bookmarks-title')}
+                open={open}
+                setOpen={setOpen}
+                content={
+                    <div className="flex flex-col">
+                        {ctx.bookmarks.length === 0
+                            ? <p className="mb-4">{t('bookmarks-empty')}</p>
+                            : <div className="flex flex-col max-h-80 overflow-y-auto mb-4">
+                                {ctx.bookmarks.map((bookmark) => (
+                                    <div key={bookmark.id} className="flex items-center gap-x-3 p-1">
+                                        <Clickable className="p-1 font-mono" onClick={() => jumpToBookmark(bookmark)}>
+                                            {timestampPretty(bookmark.timestamp)}
+                                        </Clickable>
+                                        <p className="flex-1 break-words break-all">{bookmark.label}</p>
+                                        <p className="text-sm opacity-70">{authorName(bookmark)}</p>
+                                        {bookmark.uid === ctx.uid
+                                            ? <DeleteBtn className="w-8" onClick={() => deleteBookmark(bookmark)}/>
+                                            : <div className="w-8"></div>}
+                                    </div>
+                                ))}
+                            </div>}
+                        <hr className="-ml-6 -mr-6 mb-4"/>
+                        <form onSubmit={handleSubmit(addBookmark)} noValidate>
+                            <div className="flex gap-x-3">
+                                <Input
+                                    className="flex-1"
+                                    placeholder={t('bookmarks-label-placeholder')}
+                                    maxLength={64}
+                                    {...register('label')}
+                                />
+                                <BtnPrimary type="submit">{t('bookmarks-add-btn')}</BtnPrimary>
+                            </div>
+                            {errors.label
+                                ? <p className="text-danger font-semibold">{errors.label.message}</p>
+                                : <p className="text-danger invisible font-semibold">L</p>}
+                        </form>
+                    </div>
+                }
+            />
+        </>
+    )
+}
+
+interface FormFields {
+    label: string
+}
\ No newline at end of file
@@ -8,6 +8,12 @@ import MpvWindowBtn from "@components/panel/MpvWindowBtn.tsx";
 import UploadMyMpvState from "@components/panel/UploadMyMpvState.tsx";
 import MpvReloadBtn from "@components/panel/MpvReloadBtn.tsx";
 import SpeedLabel from "@components/panel/SpeedLabel.tsx";
+import UploadSubtitleBtn from "@components/panel/UploadSubtitleBtn.tsx";
+import AbLoopBtn from "@components/panel/AbLoopBtn.tsx";
+import BookmarksBtn from "@components/panel/BookmarksBtn.tsx";
+import CommentBtn from "@components/panel/CommentBtn.tsx";
+import ReactionBtn from "@components/panel/ReactionBtn.tsx";
+import PollBtn from "@components/panel/PollBtn.tsx";
 
 export default function ButtonPanel(): ReactElement {
     return (
@@ -15,8 +21,14 @@ export default function ButtonPanel(): ReactElement {
             <AddToPlaylistBtn/>
             <AudioSyncBtn/>
             <SubSyncBtn/>
+            <UploadSubtitleBtn/>
             <UploadMyMpvState/>
             <MpvReloadBtn/>
+            <AbLoopBtn/>
+            <BookmarksBtn/>
+            <CommentBtn/>
+            <ReactionBtn/>
+            <PollBtn/>
             <div className="flex-1"></div>
             <SpeedLabel/>
             <MpvWindowBtn/>
@@ -0,0 +1,108 @@
+import {ReactElement, useState} from "react";
+import {useTranslation} from "react-i18next";
+import Joi from "joi";
+import {useForm} from "react-hook-form";
+import {joiResolver} from "@hookform/resolvers/joi";
+import {invoke} from "@tauri-apps/api/core";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {BtnPrimary, BtnSecondary, Clickable} from "@components/widgets/Button.tsx";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {Input} from "@components/widgets/Input.tsx";
+import CommentSvg from "@components/svg/Comment.tsx";
+import {UserReadyState} from "@components/widgets/ReadyState.tsx";
+import {PlaylistEntryId} from "@models/playlist.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import {timestampPretty} from "src/utils/mpv.ts";
+
+export default function CommentBtn(): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const [open, setOpen] = useState<boolean>(false)
+    const [timestamp, setTimestamp] = useState<number>(0)
+    const readyStatus = ctx.uid2ready.get(ctx.uid)
+
+    const formSchema = Joi.object({
+        content: Joi
+            .string()
+            .trim()
+            .min(1)
+            .max(200)
+            .required()
+            .messages({"string.empty": t('comment-empty'), "string.max": t('comment-too-long')})
+    })
+
+    const {
+        register,
+        handleSubmit,
+        reset,
+        formState: {errors}
+    } = useForm<FormFields>({resolver: joiResolver(formSchema)});
+
+    function commentClicked() {
+        // the comment is pinned to the moment the user started writing it
+        invoke<number>('mpv_get_timestamp', {})
+            .then((time: number) => {
+                setTimestamp(time)
+                reset({content: ''})
+                setOpen(true)
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('comment-add-error'))
+            })
+    }
+
+    function addComment(data: FormFields) {
+        setOpen(false)
+        ctx.socket!.emitWithAck('add_comment', {
+            playlist_entry_id: ctx.activeVideoId as PlaylistEntryId,
+            timestamp: timestamp,
+            content: data.content
+        })
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('comment-add-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('comment-add-error'))
+            })
+    }
+
+    if (readyStatus !== UserReadyState.Ready && readyStatus !== UserReadyState.NotReady)
+        return <></>
+    return (
+        <>
+            <div title={t('comment-title')}>
+                <Clickable className="p-2" onClick={commentClicked}>
+                    <CommentSvg className="h-7"/>
+                </Clickable>
+            </div>
+            <ModalWHeader
+                title={`${t('comment-title')} ${timestampPretty(timestamp)}`}
+                open={open}
+                setOpen={setOpen}
+                content={
+                    <form onSubmit={handleSubmit(addComment)} noValidate>
+                        <Input
+                            placeholder={t('comment-placeholder')}
+                            maxLength={200}
+                            {...register('content')}
+                        />
+                        {errors.content
+                            ? <p className="text-danger font-semibold">{errors.content.message}</p>
+                            : <p className="text-danger invisible font-semibold">L</p>}
+                        <hr className="-ml-6 -mr-6 mt-4 mb-4"/>
+                        <div className="flex gap-3">
+                            <BtnPrimary type="submit">{t('comment-add-btn')}</BtnPrimary>
+                            <BtnSecondary onClick={() => setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                        </div>
+                    </form>
+                }
+            />
+        </>
+    )
+}
+
+interface FormFields {
+    content: string
+}
\ No newline at end of file
@@ -1,14 +1,13 @@
 import React, {Dispatch, MouseEvent, ReactElement, SetStateAction, useEffect, useState} from "react";
 import {useTranslation} from "react-i18next";
 import {useMainContext} from "@hooks/useMainContext.ts";
-import {FileInfoClient, FileInfoSrv, FileKind, FileType} from "@models/file.ts";
+import {Episode, FileInfoClient, FileKind, FilesPageSrv, FileSortBy, FileType} from "@models/file.ts";
 import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
-import {SearchInput} from "@components/widgets/Input.tsx";
-import {Btn, BtnPrimary, DeleteBtn, ParentFolderBtn} from "@components/widgets/Button.tsx";
-import {TableColumn} from "react-data-table-component";
+import {Checkbox, SearchInput} from "@components/widgets/Input.tsx";
+import Label from "@components/widgets/Label.tsx";
+import {Btn, BtnPrimary, BtnSecondary, DeleteBtn, ParentFolderBtn} from "@components/widgets/Button.tsx";
+import {SortOrder, TableColumn} from "react-data-table-component";
 import DataTableThemeAware from "@components/widgets/DataTableThemeAware.tsx";
-import {Language} from "@models/config.tsx";
-import {useLanguage} from "@hooks/useLanguage.ts";
 import Folder from "@components/svg/Folder.tsx";
 import DateTimeLocalPretty from "@components/widgets/DateTimeLocalPretty.tsx";
 import {bytesPretty} from "src/utils/pretty.ts";
@@ -23,7 +22,6 @@ import {MultiValue, SingleValue} from "react-select";
 
 export default function FilePicker(p: Props): ReactElement {
     const {t} = useTranslation()
-    const lang = useLanguage()
     const {
         source2url,
         socket
@@ -36,6 +34,11 @@ export default function FilePicker(p: Props): ReactElement {
     const [filesError, setFilesError] = useState<boolean>(false)
     const [noSourceAvailable, setNoSourceAvailable] = useState<boolean>(false)
     const [search, setSearch] = useState<string>("")
+    const [recursive, setRecursive] = useState<boolean>(true)
+    const [sortBy, setSortBy] = useState<FileSortBy>(FileSortBy.Name)
+    const [sortDesc, setSortDesc] = useState<boolean>(false)
+    const [nextCursor, setNextCursor] = useState<string | null>(null)
+    const [moreLoading, setMoreLoading] = useState<boolean>(false)
 
     const columns: TableColumn<FileInfoClient>[] = [
         {
@@ -43,7 +46,6 @@ export default function FilePicker(p: Props): ReactElement {
             width: '16rem',
             name: t('file-picker-file-name'),
             sortable: true,
-            sortFunction: (a, b) => a.name.localeCompare(b.name),
             cell: row => {
                 return (
                     <div
@@ -65,36 +67,28 @@ export default function FilePicker(p: Props): ReactElement {
                                 />
                         }
                         <p data-tag="allowRowEvents">{row.name}</p>
+                        {row.episode != null &&
+                            <p
+                                data-tag="allowRowEvents"
+                                className="text-xs rounded bg-gray-200 dark:bg-gray-600 px-1"
+                            >{prettyEpisode(row.episode)}</p>
+                        }
                     </div>
                 )
             }
         },
         {
+            id: 'mtime',
             name: t('file-picker-mtime'),
             sortable: true,
-            sortFunction: (a, b) => a.mtime.getTime() - b.mtime.getTime(),
             cell: row => {
                 return <DateTimeLocalPretty data-tag="allowRowEvents" datetime={row.mtime}/>
             }
         },
         {
+            id: 'size',
             name: t('file-picker-size'),
             sortable: true,
-            sortFunction: (a, b) => {
-                if (a.size != null) {
-                    if (b.size != null) {
-                        return a.size - b.size
-                    } else {
-                        return -1
-                    }
-                } else {
-                    if (b.size != null) {
-                        return 1
-                    } else {
-                        return 0
-                    }
-                }
-            },
             cell: row => {
                 return (
                     <p data-tag="allowRowEvents">{row.size != null ? bytesPretty(row.size) : '-'}</p>
@@ -143,23 +137,41 @@ export default function FilePicker(p: Props): ReactElement {
         }
         setFilesError(false)
         setNoSourceAvailable(false)
-        fetchFiles()
-    }, [selectedSource, currentPath]);
+        const timeout = setTimeout(() => fetchFiles(), search === '' ? 0 : 300)
+        return () => clearTimeout(timeout)
+    }, [selectedSource, currentPath, sortBy, sortDesc, search]);
 
-    function fetchFiles() {
-        setFilesLoading(true)
-        socket!.emitWithAck('get_files', {file_srv: selectedSource?.value, path: currentPath, file_kind: p.fileKind})
-            .then((ack: SocketIoAck<Array<FileInfoSrv>>) => {
+    function fetchFiles(cursor: string | null = null) {
+        if (cursor == null)
+            setFilesLoading(true)
+        else
+            setMoreLoading(true)
+
+        socket!.emitWithAck('get_files', {
+            file_srv: selectedSource?.value,
+            path: currentPath,
+            file_kind: p.fileKind,
+            sort_by: sortBy,
+            sort_desc: sortDesc,
+            filter: search !== '' ? search : null,
+            cursor: cursor,
+            limit: FILES_PAGE_LIMIT
+        })
+            .then((ack: SocketIoAck<FilesPageSrv>) => {
                 if (ack.status === SocketIoAckType.Err) {
                     setFilesError(true)
                 } else {
-                    const payload = ack.payload as Array<FileInfoSrv>
+                    const payload = ack.payload as FilesPageSrv
                     const m: Array<FileInfoClient> = []
-                    for (const file of payload) {
+                    for (const file of payload.files) {
                         const {mtime, ...rest} = file
                         m.push({mtime: new Date(mtime), ...rest})
                     }
-                    setFiles(m)
+                    if (cursor == null)
+                        setFiles(m)
+                    else
+                        setFiles((p) => [...p, ...m])
+                    setNextCursor(payload.next_cursor)
                     setFilesError(false)
                 }
 
@@ -169,16 +181,29 @@ export default function FilePicker(p: Props): ReactElement {
             })
             .finally(() => {
                 setFilesLoading(false)
+                setMoreLoading(false)
             })
     }
 
+    function sortChanged(column: TableColumn<FileInfoClient>, direction: SortOrder) {
+        if (column.id === 'mtime')
+            setSortBy(FileSortBy.Mtime)
+        else if (column.id === 'size')
+            setSortBy(FileSortBy.Size)
+        else
+            setSortBy(FileSortBy.Name)
+        setSortDesc(direction === 'desc')
+    }
+
     function selectedSourceChanged(e: SingleValue<SourceSelect> | MultiValue<SourceSelect>) {
         setSelectedSource(e as SingleValue<SourceSelect>)
         setCurrentPath('/')
+        setSearch('')
     }
 
     function fileClicked(row: FileInfoClient, e: MouseEvent<Element>) {
         if (row.file_type === FileType.Directory) {
+            setSearch('')
             setCurrentPath((p) => {
                 if (p.endsWith("/"))
                     return p + row.name
@@ -188,6 +213,7 @@ export default function FilePicker(p: Props): ReactElement {
     }
 
     function parentFolderClicked() {
+        setSearch('')
         setCurrentPath((p) => {
             const arr = p.split('/')
             arr.pop()
@@ -220,6 +246,13 @@ export default function FilePicker(p: Props): ReactElement {
         return selectedSource?.value + ':' + currentPath + '/' + fileName
     }
 
+    function addDirectoryClicked() {
+        if (selectedSource == null || p.onAddDirectory == null)
+            return
+
+        p.onAddDirectory(selectedSource.value, currentPath, recursive)
+    }
+
     function orderChanged(e: OnChangeMeta) {
         const newFilesPicked = arrayMove(p.filesPicked, e.oldIndex, e.newIndex)
         p.setFilesPicked(newFilesPicked)
@@ -268,7 +301,6 @@ export default function FilePicker(p: Props): ReactElement {
                                         className="w-40 min-w-40"
                                         value={search}
                                         setValue={setSearch}
-                                        disabled={filesLoading}
                                     />
                                 </div>
                                 {filesLoading
@@ -286,24 +318,19 @@ export default function FilePicker(p: Props): ReactElement {
                                             highlightOnHover={true}
                                             pointerOnHover={true}
                                             defaultSortFieldId="name"
+                                            sortServer={true}
+                                            onSort={sortChanged}
                                             columns={columns}
-                                            data={
-                                                files.filter(item => {
-                                                    const s = search.toLowerCase()
-                                                    let mtimePretty = item.mtime.toLocaleString("en-US")
-                                                    if (lang === Language.Czech)
-                                                        mtimePretty = item.mtime.toLocaleString("cs-CZ")
-
-                                                    if (mtimePretty.includes(s))
-                                                        return item
-                                                    if (item.name.toLowerCase().includes(s))
-                                                        return item
-                                                    if (item?.size?.toString().includes(s))
-                                                        return item
-
-                                                })
-                                            }
+                                            data={files}
                                         />
+                                        {nextCursor != null &&
+                                            <div className="flex justify-center mt-2">
+                                                <BtnSecondary
+                                                    onClick={() => fetchFiles(nextCursor)}
+                                                    disabled={moreLoading}
+                                                >{t('file-picker-load-more-btn')}</BtnSecondary>
+                                            </div>
+                                        }
                                     </div>
                                 }
                             </>
@@ -311,6 +338,26 @@ export default function FilePicker(p: Props): ReactElement {
                     </>
                 }
             </div>
+            {p.onAddDirectory != null && !noSourceAvailable && !filesError &&
+                <div className="flex items-center gap-x-2">
+                    <div className="flex items-center gap-x-1.5">
+                        <div>
+                            <Checkbox
+                                id="addDirectoryRecursive"
+                                className="hover:cursor-pointer"
+                                checked={recursive}
+                                onChange={(e) => setRecursive(e.target.checked)}
+                            />
+                        </div>
+                        <Label className="mt-1 hover:cursor-pointer" htmlFor="addDirectoryRecursive">{t('file-picker-recursive-label')}</Label>
+                    </div>
+                    <div className="flex-1"></div>
+                    <BtnSecondary
+                        onClick={addDirectoryClicked}
+                        disabled={filesLoading}
+                    >{t('file-picker-add-directory-btn')}</BtnSecondary>
+                </div>
+            }
             <hr/>
             <div className="flex flex-col max-h-[30dvh]">
                 <p>{t('file-picker-summary')}</p>
@@ -361,13 +408,23 @@ export default function FilePicker(p: Props): ReactElement {
     )
 }
 
+const FILES_PAGE_LIMIT = 200
+
 interface Props {
     filesPicked: Array<string>,
     setFilesPicked: Dispatch<SetStateAction<Array<string>>>,
-    fileKind: FileKind
+    fileKind: FileKind,
+    onAddDirectory?: (source: string, path: string, recursive: boolean) => void
 }
 
 interface SourceSelect {
     label: string,
     value: string
+}
+
+function prettyEpisode(e: Episode): string {
+    const episode = e.episode.toString().padStart(2, '0')
+    if (e.season == null)
+        return `E${episode}`
+    return `S${e.season.toString().padStart(2, '0')}E${episode}`
 }
\ No newline at end of file
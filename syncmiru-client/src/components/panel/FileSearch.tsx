@@ -0,0 +1,107 @@
+import React, {Dispatch, ReactElement, SetStateAction, useEffect, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {FoundFileSrv} from "@models/file.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {SearchInput} from "@components/widgets/Input.tsx";
+import {Btn, DeleteBtn} from "@components/widgets/Button.tsx";
+import Loading from "@components/Loading.tsx";
+import Plus from "@components/svg/Plus.tsx";
+import VideoFile from "@components/svg/VideoFile.tsx";
+
+export default function FileSearch(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const {socket} = useMainContext()
+    const [search, setSearch] = useState<string>("")
+    const [found, setFound] = useState<Array<FoundFileSrv>>([])
+    const [searching, setSearching] = useState<boolean>(false)
+    const [searchError, setSearchError] = useState<boolean>(false)
+
+    useEffect(() => {
+        if (search === '') {
+            setFound([])
+            setSearchError(false)
+            return
+        }
+        const timeout = setTimeout(() => {
+            setSearching(true)
+            socket!.emitWithAck('search_files', {query: search})
+                .then((ack: SocketIoAck<Array<FoundFileSrv>>) => {
+                    if (ack.status === SocketIoAckType.Err) {
+                        setSearchError(true)
+                    } else {
+                        setFound(ack.payload as Array<FoundFileSrv>)
+                        setSearchError(false)
+                    }
+                })
+                .catch(() => {
+                    setSearchError(true)
+                })
+                .finally(() => {
+                    setSearching(false)
+                })
+        }, 300)
+        return () => clearTimeout(timeout)
+    }, [search]);
+
+    function addFileClicked(f: FoundFileSrv) {
+        p.setFilesPicked((p) => [...p, f.full_path])
+    }
+
+    function deleteFileClicked(f: FoundFileSrv) {
+        p.setFilesPicked((p) => p.filter(x => x !== f.full_path))
+    }
+
+    return (
+        <div className="flex flex-col gap-y-2">
+            <div className="flex items-center gap-x-2">
+                <p>{t('file-search-text')}</p>
+                <div className="flex-1"></div>
+                <SearchInput
+                    className="w-64 min-w-64"
+                    value={search}
+                    setValue={setSearch}
+                />
+            </div>
+            {search !== '' &&
+                <div className="flex flex-col max-h-[20dvh] overflow-y-auto">
+                    {searching
+                        ? <div className="flex justify-center"><Loading/></div>
+                        : searchError
+                            ? <p className="self-center p-1 text-sm font-semibold">{t('file-search-error')}</p>
+                            : found.length === 0
+                                ? <p className="self-center p-1 text-sm font-semibold">{t('file-search-nothing-found')}</p>
+                                : found.map(f => {
+                                    return (
+                                        <div
+                                            key={f.full_path}
+                                            className="flex items-center gap-x-2 p-1 hover:bg-gray-100 dark:hover:bg-gray-700 rounded">
+                                            <VideoFile className="min-w-6 w-6"/>
+                                            <p className="text-xs break-all">{f.full_path}</p>
+                                            <div className="flex-1"></div>
+                                            {p.filesPicked.includes(f.full_path)
+                                                ? <DeleteBtn
+                                                    className="min-w-8 w-8"
+                                                    onClick={() => deleteFileClicked(f)}
+                                                />
+                                                : <Btn
+                                                    className="p-1 rounded hover:bg-gray-300 dark:hover:bg-gray-500"
+                                                    onClick={() => addFileClicked(f)}
+                                                >
+                                                    <Plus className="w-6"/>
+                                                </Btn>
+                                            }
+                                        </div>
+                                    )
+                                })
+                    }
+                </div>
+            }
+        </div>
+    )
+}
+
+interface Props {
+    filesPicked: Array<string>,
+    setFilesPicked: Dispatch<SetStateAction<Array<string>>>
+}
@@ -3,7 +3,8 @@ import {Clickable} from "@components/widgets/Button.tsx";
 import Reload from "@components/svg/Reload.tsx";
 import {useMainContext} from "@hooks/useMainContext.ts";
 import {UserReadyState} from "@components/widgets/ReadyState.tsx";
-import {PlaylistEntry, PlaylistEntryId, PlaylistEntryUrl, PlaylistEntryVideo} from "@models/playlist.ts";
+import {PlayingAccess, PlaylistEntry, PlaylistEntryId, PlaylistEntryUrl, PlaylistEntryVideo} from "@models/playlist.ts";
+import {loadFromSourceData} from "src/utils/playlist.ts";
 import {invoke} from "@tauri-apps/api/core";
 import {UserLoadedInfo} from "@models/mpv.ts";
 import {showPersistentErrorAlert} from "src/utils/alert.ts";
@@ -28,10 +29,10 @@ export default function MpvReloadBtn(): ReactElement {
                 }
                 else {
                     if(entry instanceof PlaylistEntryVideo) {
-                        const jwt = ctx.jwts.get(id) as string
+                        const access = ctx.jwts.get(id) as PlayingAccess
                         const video = entry as PlaylistEntryVideo
                         const source = ctx.source2url.get(video.source) as string
-                        const data = {source_url: source, jwt: jwt, playback_speed: ctx.joinedRoomSettings.playback_speed}
+                        const data = loadFromSourceData(access, source, ctx.joinedRoomSettings.playback_speed, video.clip)
 
                         ctx.setUid2ready((p) => {
                             const m: Map<UserId, UserReadyState> = new Map<UserId, UserReadyState>()
@@ -54,7 +55,7 @@ export default function MpvReloadBtn(): ReactElement {
                     }
                     else if(entry instanceof PlaylistEntryUrl) {
                         const video = entry as PlaylistEntryUrl
-                        const data = {url: video.url, playback_speed: ctx.joinedRoomSettings.playback_speed}
+                        const data = {url: video.url, playback_speed: ctx.joinedRoomSettings.playback_speed, start: video.clip.start ?? 0}
 
                         ctx.setUid2ready((p) => {
                             const m: Map<UserId, UserReadyState> = new Map<UserId, UserReadyState>()
@@ -0,0 +1,409 @@
+import {ReactElement, useEffect, useState} from "react";
+import {useTranslation} from "react-i18next";
+import Joi from "joi";
+import {Controller, useFieldArray, useForm} from "react-hook-form";
+import {joiResolver} from "@hookform/resolvers/joi";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {BtnPrimary, BtnSecondary, Clickable, DeleteBtn} from "@components/widgets/Button.tsx";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {Checkbox, Input} from "@components/widgets/Input.tsx";
+import Label from "@components/widgets/Label.tsx";
+import Help from "@components/widgets/Help.tsx";
+import SelectLangAware from "@components/widgets/SelectLangAware.tsx";
+import PollSvg from "@components/svg/Poll.tsx";
+import {RoomConnectionState} from "@models/context.ts";
+import {PlaylistEntryId} from "@models/playlist.ts";
+import {Poll, PollId, PollOption, PollState, PollTallies} from "@models/poll.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert, showTemporalErrorAlertForModal} from "src/utils/alert.ts";
+
+const POLL_OPTIONS_MAX = 10
+
+export default function PollBtn(): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const [open, setOpen] = useState<boolean>(false)
+    const [poll, setPoll] = useState<Poll | null>(null)
+    const [votes, setVotes] = useState<Array<number>>([])
+    const [creating, setCreating] = useState<boolean>(false)
+    const [now, setNow] = useState<number>(Date.now())
+
+    const connectedToRoom = ctx.currentRid != null && ctx.roomConnection === RoomConnectionState.Established
+
+    const formSchema = Joi.object({
+        question: Joi
+            .string()
+            .trim()
+            .min(1)
+            .max(200)
+            .required()
+            .messages({"string.empty": t('poll-question-empty'), "string.max": t('poll-question-too-long')}),
+        options: Joi
+            .array()
+            .items(Joi.object({
+                text: Joi.string().trim().allow('').max(100).messages({"string.max": t('poll-option-too-long')}),
+                entry: Joi.any()
+            }))
+            .min(2)
+            .max(POLL_OPTIONS_MAX),
+        multiChoice: Joi.boolean(),
+        activateWinner: Joi.boolean(),
+        duration: Joi
+            .string()
+            .allow('')
+            .pattern(/^\d+$/)
+            .custom((v: string, h) => {
+                const minutes = parseInt(v)
+                if (minutes < 1 || minutes > 60)
+                    return h.message({custom: t('poll-duration-invalid')})
+                return v
+            })
+            .messages({"string.pattern.base": t('poll-duration-invalid')})
+    })
+
+    const {
+        register,
+        handleSubmit,
+        reset,
+        control,
+        setValue,
+        getValues,
+        setError,
+        formState: {errors}
+    } = useForm<FormFields>({resolver: joiResolver(formSchema)});
+    const {fields, append, remove} = useFieldArray({control, name: 'options'})
+
+    useEffect(() => {
+        if (ctx.socket !== undefined && connectedToRoom) {
+            ctx.socket.on('poll', onPoll)
+            ctx.socket.on('poll_tallies', onPollTallies)
+            ctx.socket.on('poll_closed', onPollClosed)
+            ctx.socket.emitWithAck('get_poll', {})
+                .then((ack: SocketIoAck<PollState | null>) => {
+                    if (ack.status === SocketIoAckType.Err) {
+                        showPersistentErrorAlert(t('poll-received-error'))
+                        return
+                    }
+                    const state = ack.payload as PollState | null
+                    setPoll(state != null ? state.poll : null)
+                    setVotes(state != null ? state.votes : [])
+                })
+                .catch(() => {
+                    showPersistentErrorAlert(t('poll-received-error'))
+                })
+        }
+        else {
+            setPoll(null)
+            setVotes([])
+        }
+        return () => {
+            if (ctx.socket !== undefined) {
+                ctx.socket.off('poll', onPoll)
+                ctx.socket.off('poll_tallies', onPollTallies)
+                ctx.socket.off('poll_closed', onPollClosed)
+            }
+        }
+    }, [ctx.socket, connectedToRoom]);
+
+    useEffect(() => {
+        if (!open || poll == null || poll.closed || poll.closes_at == null)
+            return
+
+        const interval = setInterval(() => setNow(Date.now()), 1000)
+        return () => clearInterval(interval)
+    }, [open, poll]);
+
+    function onPoll(payload: Poll) {
+        setPoll(payload)
+        setVotes([])
+        setCreating(false)
+    }
+
+    function onPollTallies(payload: PollTallies) {
+        setPoll((p) => {
+            if (p == null || p.id !== payload.id)
+                return p
+            return {...p, tallies: payload.tallies}
+        })
+    }
+
+    function onPollClosed(payload: Poll) {
+        setPoll((p) => {
+            if (p == null || p.id !== payload.id)
+                return p
+            return payload
+        })
+    }
+
+    function pollClicked() {
+        setCreating(poll == null)
+        if (poll == null)
+            resetForm()
+        setOpen(true)
+    }
+
+    function resetForm() {
+        reset({
+            question: '',
+            options: [{text: '', entry: null}, {text: '', entry: null}],
+            multiChoice: false,
+            activateWinner: false,
+            duration: ''
+        })
+    }
+
+    function newPollClicked() {
+        resetForm()
+        setCreating(true)
+    }
+
+    function entryLabel(entryId: PlaylistEntryId): string {
+        return ctx.playlist.get(entryId)?.pretty() ?? ''
+    }
+
+    function entryChanged(index: number, entry: EntrySelect | null) {
+        if (entry != null && getValues(`options.${index}.text`).trim() === '')
+            setValue(`options.${index}.text`, entry.label.slice(0, 100))
+    }
+
+    function createPoll(data: FormFields) {
+        const options: Array<PollOption> = data.options.map((option) => {
+            return {
+                text: option.text.trim() !== '' ? option.text.trim() : (option.entry?.label ?? '').slice(0, 100),
+                playlist_entry_id: option.entry?.value ?? null
+            }
+        })
+        if (options.some((option) => option.text === '')) {
+            setError('options', {message: t('poll-option-empty')})
+            return
+        }
+
+        ctx.socket!.emitWithAck('create_poll', {
+            question: data.question,
+            options: options,
+            multi_choice: data.multiChoice,
+            activate_winner: data.activateWinner,
+            duration: data.duration !== '' ? parseInt(data.duration) * 60 : null
+        })
+            .then((ack: SocketIoAck<PollId>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showTemporalErrorAlertForModal(t('poll-create-error'))
+                else
+                    setCreating(false)
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('poll-create-error'))
+            })
+    }
+
+    function optionClicked(index: number) {
+        if (poll == null || poll.closed)
+            return
+
+        let newVotes: Array<number>
+        if (votes.includes(index))
+            newVotes = votes.filter((x) => x !== index)
+        else if (poll.multi_choice)
+            newVotes = [...votes, index]
+        else
+            newVotes = [index]
+
+        ctx.socket!.emitWithAck('vote_poll', {id: poll.id, choices: newVotes})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showTemporalErrorAlertForModal(t('poll-vote-error'))
+                else
+                    setVotes(newVotes)
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('poll-vote-error'))
+            })
+    }
+
+    function closePoll() {
+        if (poll == null)
+            return
+
+        ctx.socket!.emitWithAck('close_poll', {id: poll.id})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showTemporalErrorAlertForModal(t('poll-close-error'))
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('poll-close-error'))
+            })
+    }
+
+    function remainingPretty(closesAt: string): string {
+        const remaining = Math.max(0, Math.ceil((new Date(closesAt).getTime() - now) / 1000))
+        const minutes = Math.floor(remaining / 60)
+        const seconds = remaining % 60
+        return `${minutes}:${seconds.toString().padStart(2, '0')}`
+    }
+
+    function pollView(p: Poll): ReactElement {
+        const total = p.tallies.reduce((acc, x) => acc + x, 0)
+        return (
+            <div className="flex flex-col">
+                <p className="font-semibold break-words mb-1">{p.question}</p>
+                <p className="text-sm opacity-70 mb-4">
+                    {t('poll-created-by', {displayname: ctx.users.get(p.uid)?.displayname ?? ''})}
+                    {p.multi_choice && ` · ${t('poll-multi-choice-label')}`}
+                    {!p.closed && p.closes_at != null && ` · ${t('poll-closes-in', {remaining: remainingPretty(p.closes_at)})}`}
+                </p>
+                <div className="flex flex-col gap-y-2 max-h-80 overflow-y-auto mb-4">
+                    {p.options.map((option, i) => {
+                        const percent = total > 0 ? Math.round(p.tallies[i] / total * 100) : 0
+                        return (
+                            <Clickable
+                                key={i}
+                                className={`flex flex-col p-2 border rounded ${votes.includes(i) ? 'border-primary' : ''} ${p.winner === i ? 'font-semibold' : ''}`}
+                                onClick={() => optionClicked(i)}
+                                disabled={p.closed}
+                            >
+                                <div className="flex gap-x-3 items-center">
+                                    <p className="flex-1 text-left break-words break-all">{option.text}</p>
+                                    {option.playlist_entry_id != null && !ctx.playlist.has(option.playlist_entry_id) &&
+                                        <p className="text-sm opacity-70">{t('poll-option-entry-removed')}</p>}
+                                    <p className="text-sm">{p.tallies[i]}</p>
+                                </div>
+                                <div className="h-1.5 w-full bg-gray-200 dark:bg-gray-600 rounded mt-1">
+                                    <div className="h-1.5 bg-primary rounded" style={{width: `${percent}%`}}></div>
+                                </div>
+                            </Clickable>
+                        )
+                    })}
+                </div>
+                {p.closed && <p className="mb-4">
+                    {p.winner != null
+                        ? t('poll-winner', {option: p.options[p.winner].text})
+                        : t('poll-no-winner')}
+                </p>}
+                <hr className="-ml-6 -mr-6 mb-4"/>
+                <div className="flex gap-3">
+                    {!p.closed && p.uid === ctx.uid &&
+                        <BtnPrimary onClick={closePoll}>{t('poll-close-btn')}</BtnPrimary>}
+                    {p.closed &&
+                        <BtnPrimary onClick={newPollClicked}>{t('poll-new-btn')}</BtnPrimary>}
+                    <BtnSecondary onClick={() => setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                </div>
+            </div>
+        )
+    }
+
+    function createForm(): ReactElement {
+        const entryOptions = ctx.playlistOrder.map((id) => {
+            return {label: entryLabel(id), value: id} as EntrySelect
+        })
+        return (
+            <form onSubmit={handleSubmit(createPoll)} noValidate>
+                <Label htmlFor="question">{t('poll-question-label')}</Label>
+                <Input id="question" maxLength={200} {...register('question')}/>
+                {errors.question
+                    ? <p className="text-danger font-semibold">{errors.question.message}</p>
+                    : <p className="text-danger invisible font-semibold">L</p>}
+
+                <div className="flex justify-between">
+                    <Label>{t('poll-options-label')}</Label>
+                    <Help tooltipId="poll-options-help" className="w-4" content={t('poll-options-help')}/>
+                </div>
+                <div className="flex flex-col gap-y-2 max-h-72 overflow-y-auto">
+                    {fields.map((field, i) => (
+                        <div key={field.id} className="flex gap-x-2 items-center">
+                            <Input className="flex-1" maxLength={100} {...register(`options.${i}.text`)}/>
+                            <Controller
+                                control={control}
+                                name={`options.${i}.entry`}
+                                render={({field: {value, onChange}}) => (
+                                    <SelectLangAware
+                                        getOptionLabel={(entry: EntrySelect) => entry.label}
+                                        getOptionValue={(entry: EntrySelect) => entry.value.toString()}
+                                        value={value}
+                                        classNamePrefix="my-react-select"
+                                        className="w-48 my-react-select-container"
+                                        onChange={(entry) => {
+                                            onChange(entry)
+                                            entryChanged(i, entry as EntrySelect | null)
+                                        }}
+                                        options={entryOptions}
+                                        isClearable={true}
+                                    />
+                                )}
+                            />
+                            <DeleteBtn type="button" className="w-8" onClick={() => remove(i)} disabled={fields.length <= 2}/>
+                        </div>
+                    ))}
+                </div>
+                {errors.options
+                    ? <p className="text-danger font-semibold">{errors.options.message ?? t('poll-option-too-long')}</p>
+                    : <p className="text-danger invisible font-semibold">L</p>}
+                {fields.length < POLL_OPTIONS_MAX &&
+                    <BtnSecondary type="button" onClick={() => append({text: '', entry: null})}>
+                        {t('poll-add-option-btn')}
+                    </BtnSecondary>}
+
+                <div className="flex items-center mt-4">
+                    <p className="w-64">{t('poll-multi-choice-label')}</p>
+                    <div className="flex-1"></div>
+                    <Checkbox className="hover:cursor-pointer" {...register('multiChoice')}/>
+                </div>
+                <div className="flex items-center mt-2">
+                    <div className="w-64 flex items-center gap-x-1">
+                        <p>{t('poll-activate-winner-label')}</p>
+                        <Help tooltipId="poll-activate-winner-help" className="w-4" content={t('poll-activate-winner-help')}/>
+                    </div>
+                    <div className="flex-1"></div>
+                    <Checkbox className="hover:cursor-pointer" {...register('activateWinner')}/>
+                </div>
+                <div className="flex items-center mt-2">
+                    <Label htmlFor="duration" className="w-64">{t('poll-duration-label')}</Label>
+                    <div className="flex-1"></div>
+                    <Input id="duration" className="w-24" placeholder="-" {...register('duration')}/>
+                </div>
+                {errors.duration
+                    ? <p className="text-danger font-semibold">{errors.duration.message}</p>
+                    : <p className="text-danger invisible font-semibold">L</p>}
+
+                <hr className="-ml-6 -mr-6 mt-2 mb-4"/>
+                <div className="flex gap-3">
+                    <BtnPrimary type="submit">{t('poll-create-btn')}</BtnPrimary>
+                    {poll != null
+                        ? <BtnSecondary type="button" onClick={() => setCreating(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                        : <BtnSecondary type="button" onClick={() => setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>}
+                </div>
+            </form>
+        )
+    }
+
+    if (!connectedToRoom)
+        return <></>
+    return (
+        <>
+            <div title={t('poll-title')}>
+                <Clickable className="p-2 flex items-center gap-x-1" onClick={pollClicked}>
+                    <PollSvg className={`h-7 ${poll != null && !poll.closed ? '' : 'opacity-50'}`}/>
+                    {poll != null && !poll.closed && votes.length === 0 && <div className="w-2 h-2 rounded-full bg-primary"></div>}
+                </Clickable>
+            </div>
+            <ModalWHeader
+                title={t('poll-title')}
+                open={open}
+                setOpen={setOpen}
+                content={creating || poll == null ? createForm() : pollView(poll)}
+            />
+        </>
+    )
+}
+
+interface EntrySelect {
+    label: string,
+    value: PlaylistEntryId
+}
+
+interface FormFields {
+    question: string,
+    options: Array<{text: string, entry: EntrySelect | null}>,
+    multiChoice: boolean,
+    activateWinner: boolean,
+    duration: string
+}
\ No newline at end of file
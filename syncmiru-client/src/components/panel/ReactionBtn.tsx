@@ -0,0 +1,43 @@
+import {ReactElement, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {Clickable} from "@components/widgets/Button.tsx";
+import Smile from "@components/svg/Smile.tsx";
+import {RoomConnectionState} from "@models/context.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showTemporalErrorAlertForModal} from "src/utils/alert.ts";
+import {REACTIONS} from "src/utils/constants.ts";
+
+export default function ReactionBtn(): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const [open, setOpen] = useState<boolean>(false)
+
+    const connectedToRoom = ctx.currentRid != null && ctx.roomConnection === RoomConnectionState.Established
+
+    function react(reaction: string) {
+        ctx.socket!.emitWithAck('react', {reaction: reaction})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showTemporalErrorAlertForModal(t('reaction-error'))
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('reaction-error'))
+            })
+    }
+
+    if (!connectedToRoom)
+        return <></>
+    return (
+        <div className="flex items-center" title={t('reaction-title')}>
+            <Clickable className="p-2" onClick={() => setOpen((p) => !p)}>
+                <Smile className={`h-7 ${open ? '' : 'opacity-50'}`}/>
+            </Clickable>
+            {open && REACTIONS.map((reaction) => (
+                <Clickable key={reaction} className="p-1 text-xl" onClick={() => react(reaction)}>
+                    {reaction}
+                </Clickable>
+            ))}
+        </div>
+    )
+}
\ No newline at end of file
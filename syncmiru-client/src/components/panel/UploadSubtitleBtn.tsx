@@ -0,0 +1,61 @@
+import {ChangeEvent, ReactElement, useRef, useState} from "react";
+import {Clickable} from "@components/widgets/Button.tsx";
+import SubFile from "@components/svg/SubFile.tsx";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {useTranslation} from "react-i18next";
+import {RoomConnectionState} from "@models/context.ts";
+import {PlaylistEntryId} from "@models/playlist.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import {SUBTITLE_UPLOAD_MAX_SIZE, uploadSubtitle} from "src/utils/playlist.ts";
+
+export default function UploadSubtitleBtn(): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const subFileRef = useRef<HTMLInputElement>(null)
+    const [uploading, setUploading] = useState<boolean>(false)
+
+    const connectedToRoom = ctx.currentRid != null && ctx.roomConnection === RoomConnectionState.Established
+
+    function uploadSubtitleClicked() {
+        if (subFileRef.current == null || uploading)
+            return;
+
+        subFileRef.current.value = ''
+        subFileRef.current.click()
+    }
+
+    function subFileChanged(e: ChangeEvent<HTMLInputElement>) {
+        if (e.target.files == null || e.target.files.length === 0)
+            return;
+
+        const subFile = e.target.files[0]
+        if (subFile.size > SUBTITLE_UPLOAD_MAX_SIZE) {
+            showPersistentErrorAlert(t('subtitle-upload-max-size-error'))
+            return;
+        }
+
+        setUploading(true)
+        uploadSubtitle(ctx, ctx.activeVideoId as PlaylistEntryId, subFile)
+            .catch(() => {
+                showPersistentErrorAlert(t('subtitle-upload-error'))
+            })
+            .finally(() => {
+                setUploading(false)
+            })
+    }
+
+    if (!connectedToRoom || ctx.activeVideoId == null)
+        return <div></div>
+    return (
+        <Clickable className="p-2" onClick={uploadSubtitleClicked} disabled={uploading}>
+            <input
+                ref={subFileRef}
+                type="file"
+                className="hidden"
+                accept=".srt,.ass,.ssa,.vtt"
+                onChange={subFileChanged}
+            />
+            <SubFile className="h-7"/>
+        </Clickable>
+    )
+}
\ No newline at end of file
@@ -0,0 +1,175 @@
+import React, {ChangeEvent, ReactElement, useRef, useState} from "react";
+import {MenuItem} from "@szhsin/react-menu";
+import {useTranslation} from "react-i18next";
+import UploadCloud from "@components/svg/UploadCloud.tsx";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {BtnPrimary, BtnSecondary, DeleteBtn} from "@components/widgets/Button.tsx";
+import Loading from "@components/Loading.tsx";
+import {useHomeServer} from "@hooks/useHomeServer.ts";
+import {useJwt} from "@hooks/useJwt.tsx";
+import {useHwidHash} from "@hooks/useHwidHash.ts";
+import {UploadAuth, UploadError, UploadStatus} from "@models/upload.ts";
+import {createUpload, deleteUpload, getUploads, sendUploadChunks} from "src/utils/upload.ts";
+import {bytesPretty} from "src/utils/pretty.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+
+export default function UploadVideo(): ReactElement {
+    const {t} = useTranslation()
+    const homeSrv = useHomeServer()
+    const jwt = useJwt()
+    const hwidHash = useHwidHash()
+    const auth: UploadAuth = {homeSrv: homeSrv, jwt: jwt, hwidHash: hwidHash}
+    const videoFileRef = useRef<HTMLInputElement>(null)
+    const [showModal, setShowModal] = useState<boolean>(false);
+    const [loading, setLoading] = useState<boolean>(false);
+    const [uploads, setUploads] = useState<Array<UploadStatus>>([]);
+    const [uploading, setUploading] = useState<boolean>(false);
+    const [resumed, setResumed] = useState<UploadStatus | null>(null);
+
+    function uploadClicked() {
+        setShowModal(true)
+        setLoading(true)
+        getUploads(auth)
+            .then((uploads: Array<UploadStatus>) => {
+                setUploads(uploads)
+            })
+            .catch(() => {
+                setShowModal(false)
+                showPersistentErrorAlert(t('upload-list-error'))
+            })
+            .finally(() => {
+                setLoading(false)
+            })
+    }
+
+    function pickFile(upload: UploadStatus | null) {
+        if (videoFileRef.current == null || uploading)
+            return;
+
+        setResumed(upload)
+        videoFileRef.current.value = ''
+        videoFileRef.current.click()
+    }
+
+    function videoFileChanged(e: ChangeEvent<HTMLInputElement>) {
+        if (e.target.files == null || e.target.files.length === 0)
+            return;
+
+        const videoFile = e.target.files[0]
+        if (resumed != null && (resumed.name !== videoFile.name || resumed.size !== videoFile.size)) {
+            showPersistentErrorAlert(t('upload-resume-mismatch-error'))
+            return;
+        }
+
+        setUploading(true)
+        const uploadPromise = resumed != null ? Promise.resolve(resumed) : createUpload(auth, videoFile)
+        uploadPromise
+            .then((upload: UploadStatus) => {
+                setUploadStatus(upload)
+                return sendUploadChunks(auth, upload, videoFile, setUploadStatus)
+            })
+            .catch((e) => {
+                showPersistentErrorAlert(uploadErrorMsg(e))
+            })
+            .finally(() => {
+                setUploading(false)
+            })
+    }
+
+    function setUploadStatus(upload: UploadStatus) {
+        setUploads((p) => {
+            if (p.some(x => x.id === upload.id))
+                return p.map(x => x.id === upload.id ? upload : x)
+            return [...p, upload]
+        })
+    }
+
+    function uploadErrorMsg(e: unknown): string {
+        if (!(e instanceof UploadError))
+            return t('upload-error')
+        if (e.desc === 'name')
+            return t('upload-name-conflict-error')
+        if (e.desc === 'user_quota' || e.desc === 'global_quota')
+            return t('upload-quota-error')
+        if (e.desc === 'extension')
+            return t('upload-extension-error')
+        return t('upload-error')
+    }
+
+    function deleteClicked(upload: UploadStatus) {
+        setLoading(true)
+        deleteUpload(auth, upload.id)
+            .then(() => {
+                setUploads((p) => p.filter(x => x.id !== upload.id))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('upload-delete-error'))
+            })
+            .finally(() => {
+                setLoading(false)
+            })
+    }
+
+    return (
+        <>
+            <MenuItem
+                className="w-[13.6rem]"
+                onClick={uploadClicked}
+            >
+                <div className="flex gap-x-3">
+                    <UploadCloud className="h-6 w-6"/>
+                    <p>{t('add-to-playlist-upload')}</p>
+                </div>
+            </MenuItem>
+            <ModalWHeader
+                title={t('modal-upload-title')}
+                open={showModal}
+                setOpen={setShowModal}
+                content={
+                    <div className="flex flex-col gap-y-4">
+                        <input
+                            ref={videoFileRef}
+                            type="file"
+                            className="hidden"
+                            onChange={videoFileChanged}
+                        />
+                        <p>{t('modal-upload-text')}</p>
+                        {loading
+                            ? <Loading/>
+                            : <div className="flex flex-col gap-y-2">
+                                {uploads.length === 0 && <p className="text-sm">{t('modal-upload-empty')}</p>}
+                                {uploads.map(upload => (
+                                    <div key={upload.id} className="flex items-center gap-x-3">
+                                        <div className="flex flex-col flex-1 min-w-0">
+                                            <p className="break-words">{upload.name}</p>
+                                            <p className="text-xs">
+                                                {upload.finished
+                                                    ? bytesPretty(upload.size)
+                                                    : `${bytesPretty(upload.offset)} / ${bytesPretty(upload.size)}`}
+                                            </p>
+                                        </div>
+                                        {!upload.finished && <BtnSecondary
+                                            className="text-sm"
+                                            disabled={uploading}
+                                            onClick={() => pickFile(upload)}
+                                        >{t('modal-upload-resume')}</BtnSecondary>}
+                                        <DeleteBtn
+                                            className="w-10"
+                                            disabled={uploading}
+                                            onClick={() => deleteClicked(upload)}
+                                        />
+                                    </div>
+                                ))}
+                            </div>
+                        }
+                        <BtnPrimary
+                            className="mt-4"
+                            disabled={loading || uploading}
+                            onClick={() => pickFile(null)}
+                        >{uploading ? t('modal-upload-uploading') : t('modal-upload-btn')}</BtnPrimary>
+                    </div>
+                }
+            />
+        </>
+    )
+}
\ No newline at end of file
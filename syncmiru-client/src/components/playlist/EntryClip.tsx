@@ -0,0 +1,137 @@
+import {ReactElement, useState} from "react";
+import {useTranslation} from "react-i18next";
+import Joi from "joi";
+import {useForm} from "react-hook-form";
+import {joiResolver} from "@hookform/resolvers/joi";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {Clip, PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import Label from "@components/widgets/Label.tsx";
+import {Input} from "@components/widgets/Input.tsx";
+import Help from "@components/widgets/Help.tsx";
+import {BtnPrimary, BtnSecondary} from "@components/widgets/Button.tsx";
+import Edit from "@components/svg/Edit.tsx";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import {timestampParse, timestampPretty} from "src/utils/mpv.ts";
+
+export default function EntryClip(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const ctx = useMainContext()
+    const [open, setOpen] = useState<boolean>(false)
+
+    const timestampSchema = Joi
+        .string()
+        .allow('')
+        .custom((v: string, h) => {
+            if (timestampParse(v) == null)
+                return h.message({custom: t('playlist-clip-invalid-format')})
+            return v
+        })
+    const formSchema = Joi.object({
+        clipStart: timestampSchema,
+        clipEnd: timestampSchema
+    })
+
+    const {
+        register,
+        handleSubmit,
+        reset,
+        setError,
+        formState: {errors}
+    } = useForm<FormFields>({resolver: joiResolver(formSchema)});
+
+    function editClicked() {
+        reset({
+            clipStart: p.entry.clip.start != null ? timestampPretty(p.entry.clip.start) : '',
+            clipEnd: p.entry.clip.end != null ? timestampPretty(p.entry.clip.end) : ''
+        })
+        setOpen(true)
+    }
+
+    function setClip(data: FormFields) {
+        const clip: Clip = {
+            start: data.clipStart !== '' ? timestampParse(data.clipStart) : null,
+            end: data.clipEnd !== '' ? timestampParse(data.clipEnd) : null
+        }
+        if (clip.start != null && clip.end != null && clip.end <= clip.start) {
+            setError('clipEnd', {message: t('playlist-clip-end-before-start')})
+            return
+        }
+
+        setOpen(false)
+        ctx.socket!.emitWithAck('set_playlist_entry_clip', {playlist_entry_id: p.entryId, clip: clip})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('playlist-clip-change-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('playlist-clip-change-error'))
+            })
+    }
+
+    return (
+        <>
+            <div
+                role="button"
+                className='flex items-center rounded hover:bg-gray-300 p-1.5 dark:hover:bg-gray-500 invisible group-hover:visible min-h-8 h-8 min-w-8 w-8'
+                onClick={(e) => {
+                    e.stopPropagation();
+                    editClicked()
+                }}
+                onMouseDown={(e) => e.stopPropagation()}
+                onMouseUp={(e) => e.stopPropagation()}
+            >
+                <Edit className="w-full h-full"/>
+            </div>
+            <ModalWHeader
+                title={t('playlist-clip-title')}
+                open={open}
+                setOpen={setOpen}
+                content={
+                    <form onSubmit={handleSubmit(setClip)} noValidate>
+                        <p className="text-sm break-words break-all mb-4">{p.entry.pretty()}</p>
+                        <div className="flex gap-x-4">
+                            <div className="flex flex-col flex-1">
+                                <div className="flex justify-between">
+                                    <Label htmlFor="clipStart">{t('playlist-clip-start-label')}</Label>
+                                    <Help
+                                        tooltipId="clipStart-help"
+                                        className="w-4"
+                                        content={t('playlist-clip-help')}
+                                    />
+                                </div>
+                                <Input id="clipStart" placeholder="00:00" {...register('clipStart')}/>
+                                {errors.clipStart
+                                    ? <p className="text-danger font-semibold">{errors.clipStart.message}</p>
+                                    : <p className="text-danger invisible font-semibold">L</p>}
+                            </div>
+                            <div className="flex flex-col flex-1">
+                                <Label htmlFor="clipEnd">{t('playlist-clip-end-label')}</Label>
+                                <Input id="clipEnd" {...register('clipEnd')}/>
+                                {errors.clipEnd
+                                    ? <p className="text-danger font-semibold">{errors.clipEnd.message}</p>
+                                    : <p className="text-danger invisible font-semibold">L</p>}
+                            </div>
+                        </div>
+                        <hr className="-ml-6 -mr-6 mt-4 mb-4"/>
+                        <div className="flex gap-3">
+                            <BtnPrimary type="submit">{t('modal-change-action-btn')}</BtnPrimary>
+                            <BtnSecondary onClick={() => setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                        </div>
+                    </form>
+                }
+            />
+        </>
+    )
+}
+
+interface Props {
+    entryId: PlaylistEntryId,
+    entry: PlaylistEntry
+}
+
+interface FormFields {
+    clipStart: string,
+    clipEnd: string
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+import {ReactElement, useEffect, useState} from "react";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {PlaylistEntry, PlaylistEntryId, PlaylistEntryVideo, PosterBin} from "@models/playlist.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {arrayBufferToBase64} from "src/utils/encoding.ts";
+import VideoFile from "@components/svg/VideoFile.tsx";
+
+export default function EntryPoster(p: Props): ReactElement {
+    const ctx = useMainContext()
+    const [posterBase64, setPosterBase64] = useState<string>('')
+
+    const posterPath = p.entry instanceof PlaylistEntryVideo ? p.entry.metadata?.poster : null
+
+    useEffect(() => {
+        if (posterPath == null || ctx.socket == null)
+            return
+
+        ctx.socket.emitWithAck('get_entry_poster', {playlist_entry_id: p.entryId})
+            .then((ack: SocketIoAck<PosterBin>) => {
+                if (ack.status === SocketIoAckType.Ok)
+                    setPosterBase64(arrayBufferToBase64((ack.payload as PosterBin).data))
+            })
+            .catch(() => {})
+    }, [p.entryId, posterPath]);
+
+    if (posterBase64 === '')
+        return <VideoFile className="min-w-6 w-6"/>
+
+    return <img src={`data:image/jpeg;base64, ${posterBase64}`} alt="Poster" className="min-w-6 w-6 rounded-sm"/>
+}
+
+interface Props {
+    entryId: PlaylistEntryId,
+    entry: PlaylistEntry
+}
\ No newline at end of file
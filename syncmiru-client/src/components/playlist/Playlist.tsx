@@ -8,6 +8,9 @@ import {
     PlaylistEntryId,
     PlaylistEntryUrl,
     PlaylistEntryVideo,
+    PlaylistEntryMedia,
+    PlaylistEntryMetadata,
+    PlaylistEntryClip,
 } from "@models/playlist.ts";
 import {arrayMove, List, OnChangeMeta, RenderListParams} from "react-movable";
 import VideoFile from "@components/svg/VideoFile.tsx";
@@ -23,7 +26,9 @@ import {UserId} from "@models/user.ts";
 import {UserReadyState} from "@components/widgets/ReadyState.tsx";
 import {UserAudioSubtitles} from "@models/mpv.ts";
 import {hideMpvReadyMessages, MpvMsgMood} from "src/utils/mpv.ts";
-import {changeActiveVideo} from "src/utils/playlist.ts";
+import {changeActiveVideo, prettyClip, prettyMediaInfo} from "src/utils/playlist.ts";
+import EntryPoster from "@components/playlist/EntryPoster.tsx";
+import EntryClip from "@components/playlist/EntryClip.tsx";
 
 export default function Playlist(): ReactElement {
     const ctx = useMainContext()
@@ -42,12 +47,18 @@ export default function Playlist(): ReactElement {
             ctx.socket.on('add_video_files', onAddVideoFiles)
             ctx.socket.on('add_urls', onAddUrls)
             ctx.socket.on('playlist_order', onPlaylistOrder)
+            ctx.socket.on('playlist_entry_media', onPlaylistEntryMedia)
+            ctx.socket.on('playlist_entry_metadata', onPlaylistEntryMetadata)
+            ctx.socket.on('playlist_entry_clip', onPlaylistEntryClip)
         }
         return () => {
             if(ctx.socket !== undefined) {
                 ctx.socket.off('add_video_files', onAddVideoFiles)
                 ctx.socket.off('add_urls', onAddUrls)
                 ctx.socket.off('playlist_order', onPlaylistOrder)
+                ctx.socket.off('playlist_entry_media', onPlaylistEntryMedia)
+                ctx.socket.off('playlist_entry_metadata', onPlaylistEntryMetadata)
+                ctx.socket.off('playlist_entry_clip', onPlaylistEntryClip)
             }
         }
     }, [ctx.socket]);
@@ -78,6 +89,8 @@ export default function Playlist(): ReactElement {
         if(ctx.currentRid == null) {
             ctx.setPlaylist(new Map<PlaylistEntryId, PlaylistEntry>())
             ctx.setPlaylistOrder([])
+            ctx.setAbLoop(null)
+            ctx.setBookmarks([])
         }
     }, [ctx.currentRid, ctx.roomConnection]);
 
@@ -101,7 +114,7 @@ export default function Playlist(): ReactElement {
         const m: Map<PlaylistEntryId, PlaylistEntry> = new Map<PlaylistEntryId, PlaylistEntry>()
         for (const idStr in r.entries) {
             const value = r.entries[idStr]
-            m.set(parseInt(idStr), new PlaylistEntryVideo(value.source, value.path))
+            m.set(parseInt(idStr), new PlaylistEntryVideo(value.source, value.path, value.metadata, value.media, value.clip))
         }
 
         ctx.setPlaylist((p) => {
@@ -128,7 +141,7 @@ export default function Playlist(): ReactElement {
         const m: Map<PlaylistEntryId, PlaylistEntry> = new Map<PlaylistEntryId, PlaylistEntry>()
         for (const idStr in r.entries) {
             const value = r.entries[idStr]
-            m.set(parseInt(idStr), new PlaylistEntryUrl(value.url))
+            m.set(parseInt(idStr), new PlaylistEntryUrl(value.url, value.clip))
         }
 
         ctx.setPlaylist((p) => {
@@ -150,6 +163,54 @@ export default function Playlist(): ReactElement {
         }
     }
 
+    function onPlaylistEntryMedia(entryMedia: PlaylistEntryMedia) {
+        ctx.setPlaylist((p) => {
+            const entry = p.get(entryMedia.playlist_entry_id)
+            if (!(entry instanceof PlaylistEntryVideo))
+                return p
+
+            const m = new Map<PlaylistEntryId, PlaylistEntry>(p)
+            m.set(
+                entryMedia.playlist_entry_id,
+                new PlaylistEntryVideo(entry.source, entry.path, entry.metadata, entryMedia.media, entry.clip)
+            )
+            return m
+        })
+    }
+
+    function onPlaylistEntryMetadata(entryMetadata: PlaylistEntryMetadata) {
+        ctx.setPlaylist((p) => {
+            const entry = p.get(entryMetadata.playlist_entry_id)
+            if (!(entry instanceof PlaylistEntryVideo))
+                return p
+
+            const m = new Map<PlaylistEntryId, PlaylistEntry>(p)
+            m.set(
+                entryMetadata.playlist_entry_id,
+                new PlaylistEntryVideo(entry.source, entry.path, entryMetadata.metadata, entry.media, entry.clip)
+            )
+            return m
+        })
+    }
+
+    function onPlaylistEntryClip(entryClip: PlaylistEntryClip) {
+        ctx.setPlaylist((p) => {
+            const entry = p.get(entryClip.playlist_entry_id)
+            if (entry == null)
+                return p
+
+            const m = new Map<PlaylistEntryId, PlaylistEntry>(p)
+            if (entry instanceof PlaylistEntryVideo)
+                m.set(
+                    entryClip.playlist_entry_id,
+                    new PlaylistEntryVideo(entry.source, entry.path, entry.metadata, entry.media, entryClip.clip)
+                )
+            else if (entry instanceof PlaylistEntryUrl)
+                m.set(entryClip.playlist_entry_id, new PlaylistEntryUrl(entry.url, entryClip.clip))
+            return m
+        })
+    }
+
     function onPlaylistOrder(changePlaylistOrder: ChangePlaylistOrder) {
         ctx.setPlaylistOrder(changePlaylistOrder.order)
 
@@ -216,6 +277,8 @@ export default function Playlist(): ReactElement {
     }
 
     function onChangeActiveVideo(entryId: PlaylistEntryId) {
+        ctx.setAbLoop(null)
+        ctx.setBookmarks([])
         ctx.setUid2ready((p) => {
             const m: Map<UserId, UserReadyState> = new Map<UserId, UserReadyState>()
             for (const [id, value] of p) {
@@ -343,9 +406,23 @@ export default function Playlist(): ReactElement {
                                         onMouseDown={(e) => onPlaylistEntryMouseDown(e, playlistEntryId)}
                                         onMouseUp={(e) => onPlaylistEntryMouseUp(e, playlistEntryId)}
                                         className="flex items-center mb-0.5 gap-x-2 p-2 hover:bg-gray-100 dark:hover:bg-gray-700 hover:cursor-pointer rounded group">
-                                        <VideoFile className="min-w-6 w-6"/>
-                                        <p className={`text-sm break-words break-all ${ctx.activeVideoId === playlistEntryId ? 'font-bold' : ''}`}>{renderTxt}</p>
+                                        <EntryPoster entryId={playlistEntryId} entry={entry}/>
+                                        <p
+                                            className={`text-sm break-words break-all ${ctx.activeVideoId === playlistEntryId ? 'font-bold' : ''}`}
+                                            title={entry instanceof PlaylistEntryVideo ? entry.metadata?.plot ?? undefined : undefined}
+                                        >{renderTxt}</p>
+                                        {entry instanceof PlaylistEntryVideo && entry.media != null &&
+                                            <p className="text-xs text-gray-500 dark:text-gray-400 whitespace-nowrap">
+                                                {prettyMediaInfo(entry.media, t)}
+                                            </p>
+                                        }
+                                        {(entry.clip.start != null || entry.clip.end != null) &&
+                                            <p className="text-xs text-gray-500 dark:text-gray-400 whitespace-nowrap">
+                                                {prettyClip(entry.clip)}
+                                            </p>
+                                        }
                                         <div className="flex-1"></div>
+                                        <EntryClip entryId={playlistEntryId} entry={entry}/>
                                         {entry instanceof PlaylistEntryUrl &&
                                             <div
                                                 role="button"
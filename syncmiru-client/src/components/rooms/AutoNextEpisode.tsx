@@ -0,0 +1,79 @@
+import {ReactElement, useEffect, useState} from "react";
+import {useTranslation} from "react-i18next";
+import Help from "@components/widgets/Help.tsx";
+import {Checkbox} from "@components/widgets/Input.tsx";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+
+export default function AutoNextEpisode(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const {socket} = useMainContext()
+    const [autoNextEpisode, setAutoNextEpisode] = useState<boolean>(false)
+
+    useEffect(() => {
+        if (socket !== undefined) {
+            socket.on('room_auto_next_episode', onAutoNextEpisode)
+            socket.emitWithAck('get_room_auto_next_episode', {id: p.rid})
+                .then((ack: SocketIoAck<boolean>) => {
+                    if(ack.status === SocketIoAckType.Err)
+                        showPersistentErrorAlert(t('auto-next-episode-received-error'))
+                    else
+                        setAutoNextEpisode(ack.payload as boolean)
+                })
+                .catch(() => {
+                    showPersistentErrorAlert(t('auto-next-episode-received-error'))
+                })
+                .finally(() => {
+                    p.setLoading(false)
+                })
+        }
+        return () => {
+            if(socket !== undefined) {
+                socket.off('room_auto_next_episode', onAutoNextEpisode)
+            }
+        }
+    }, [socket]);
+
+    function onAutoNextEpisode(payload: {id: number, auto_next_episode: boolean}) {
+        if (payload.id === p.rid)
+            setAutoNextEpisode(payload.auto_next_episode)
+    }
+
+    function autoNextEpisodeChanged(checked: boolean) {
+        p.setLoading(true)
+        socket!.emitWithAck('set_room_auto_next_episode', {id: p.rid, auto_next_episode: checked})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('auto-next-episode-change-error'))
+                else
+                    setAutoNextEpisode(checked)
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('auto-next-episode-change-error'))
+            })
+            .finally(() => {
+                p.setLoading(false)
+            })
+    }
+
+    return (
+        <div className="flex items-center">
+            <div className="w-64 flex items-center gap-x-1">
+                <p>{t('room-auto-next-episode-title')}</p>
+                <Help className="w-4" tooltipId="auto-next-episode-help" content={t('room-auto-next-episode-help')}/>
+            </div>
+            <div className="flex-1"></div>
+            <Checkbox
+                className="hover:cursor-pointer"
+                checked={autoNextEpisode}
+                onChange={(e) => autoNextEpisodeChanged(e.target.checked)}
+            />
+        </div>
+    )
+}
+
+interface Props {
+    setLoading: (b: boolean) => void
+    rid: number
+}
\ No newline at end of file
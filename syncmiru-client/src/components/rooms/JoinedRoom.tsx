@@ -1,14 +1,16 @@
-import {ReactElement} from "react";
+import {ReactElement, useState} from "react";
 import DoorOut from "@components/svg/DoorOut.tsx";
 import {Clickable} from "@components/widgets/Button.tsx";
 import {useMainContext} from "@hooks/useMainContext.ts";
 import {useTranslation} from "react-i18next";
 import {RoomConnectionState} from "@models/context.ts";
 import {disconnectFromRoom} from "src/utils/room.ts";
+import RoomTopic from "@components/rooms/RoomTopic.tsx";
 
 export default function JoinedRoom(): ReactElement {
     const ctx = useMainContext()
     const {t} = useTranslation()
+    const [topicOpen, setTopicOpen] = useState<boolean>(false)
 
     if(ctx.currentRid == null)
         return <></>
@@ -32,6 +34,9 @@ export default function JoinedRoom(): ReactElement {
             <div className="flex flex-col">
                 <p className="text-xs">{connectionMsg}</p>
                 <p className="truncate w-44">{room.name}</p>
+                <Clickable className="text-xs text-left truncate w-44 opacity-70" onClick={() => setTopicOpen(true)}>
+                    {room.topic ?? t('room-topic-set-btn')}
+                </Clickable>
             </div>
             <Clickable
                 className="p-2"
@@ -40,6 +45,7 @@ export default function JoinedRoom(): ReactElement {
             >
                 <DoorOut className="w-7"/>
             </Clickable>
+            <RoomTopic rid={ctx.currentRid} open={topicOpen} setOpen={setTopicOpen}/>
         </div>
     )
 }
\ No newline at end of file
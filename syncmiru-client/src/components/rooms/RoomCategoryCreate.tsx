@@ -0,0 +1,43 @@
+import {ReactElement, useState} from "react";
+import {MenuItem} from "@szhsin/react-menu";
+import {useTranslation} from "react-i18next";
+import Folder from "@components/svg/Folder.tsx";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import RoomCategoryName from "@components/rooms/RoomCategoryName.tsx";
+
+export default function RoomCategoryCreate(): ReactElement {
+    const {t} = useTranslation()
+    const {socket} = useMainContext()
+    const [open, setOpen] = useState<boolean>(false)
+
+    function createRoomCategory(name: string) {
+        socket!.emitWithAck("create_room_category", {name: name})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('modal-create-room-category-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('modal-create-room-category-error'))
+            })
+    }
+
+    return (
+        <>
+            <MenuItem onClick={() => setOpen(true)}>
+                <div className="flex gap-x-3">
+                    <Folder className="h-6 w-6"/>
+                    <p>{t('srv-info-create-room-category')}</p>
+                </div>
+            </MenuItem>
+            <RoomCategoryName
+                title={t('modal-create-room-category-title')}
+                actionLabel={t('modal-create-action-btn')}
+                open={open}
+                setOpen={setOpen}
+                onSubmit={createRoomCategory}
+            />
+        </>
+    )
+}
\ No newline at end of file
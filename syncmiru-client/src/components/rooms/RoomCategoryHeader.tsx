@@ -0,0 +1,89 @@
+import {ReactElement, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {ModalDelete} from "@components/widgets/Modal.tsx";
+import Edit from "@components/svg/Edit.tsx";
+import Delete from "@components/svg/Delete.tsx";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import {RoomCategory} from "@models/room.ts";
+import RoomCategoryName from "@components/rooms/RoomCategoryName.tsx";
+
+export default function RoomCategoryHeader(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const {socket} = useMainContext()
+    const [renameOpen, setRenameOpen] = useState<boolean>(false)
+    const [deleteOpen, setDeleteOpen] = useState<boolean>(false)
+
+    function renameRoomCategory(name: string) {
+        socket!.emitWithAck("set_room_category_name", {id: p.category.id, name: name})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('modal-room-category-name-change-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('modal-room-category-name-change-error'))
+            })
+    }
+
+    function deleteRoomCategory() {
+        socket!.emitWithAck("delete_room_category", {id: p.category.id})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err)
+                    showPersistentErrorAlert(t('modal-room-category-delete-error'))
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('modal-room-category-delete-error'))
+            })
+    }
+
+    return (
+        <>
+            <div
+                data-movable-handle={true}
+                className="flex items-center gap-x-1 px-2 pt-3 pb-1 group hover:cursor-pointer"
+            >
+                <p className="text-xs font-semibold uppercase break-words w-[10rem] text-left">{p.category.name}</p>
+                <div className="flex-1"></div>
+                <div
+                    role="button"
+                    className="rounded hover:bg-gray-300 p-1 dark:hover:bg-gray-500 invisible group-hover:visible min-w-5 w-5"
+                    onClick={() => setRenameOpen(true)}
+                    onMouseDown={(e) => e.stopPropagation()}
+                    onMouseUp={(e) => e.stopPropagation()}
+                >
+                    <Edit/>
+                </div>
+                <div
+                    role="button"
+                    className="rounded hover:bg-gray-300 p-1 dark:hover:bg-gray-500 invisible group-hover:visible min-w-5 w-5"
+                    onClick={() => setDeleteOpen(true)}
+                    onMouseDown={(e) => e.stopPropagation()}
+                    onMouseUp={(e) => e.stopPropagation()}
+                >
+                    <Delete/>
+                </div>
+            </div>
+            <RoomCategoryName
+                title={t('modal-change-room-category-name-title')}
+                actionLabel={t('modal-change-action-btn')}
+                name={p.category.name}
+                open={renameOpen}
+                setOpen={setRenameOpen}
+                onSubmit={renameRoomCategory}
+            />
+            <ModalDelete
+                onDeleteConfirmed={deleteRoomCategory}
+                content={
+                    <p>{t('modal-room-category-delete-text')} "{p.category.name}"?</p>
+                }
+                open={deleteOpen}
+                setOpen={setDeleteOpen}
+            />
+        </>
+    )
+}
+
+interface Props {
+    category: RoomCategory
+}
\ No newline at end of file
@@ -0,0 +1,83 @@
+import {ReactElement, useEffect} from "react";
+import {useTranslation} from "react-i18next";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {BtnPrimary, BtnSecondary} from "@components/widgets/Button.tsx";
+import {Input} from "@components/widgets/Input.tsx";
+import Label from "@components/widgets/Label.tsx";
+import Joi from "joi";
+import {useForm} from "react-hook-form";
+import {joiResolver} from "@hookform/resolvers/joi";
+import {roomCategoryNameValidate} from "src/form/validators.ts";
+
+export default function RoomCategoryName(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const formSchema = Joi.object({
+        name: Joi
+            .string()
+            .required()
+            .messages({"string.empty": t('required-field-error')})
+            .custom((v: string, h) => {
+                if(!roomCategoryNameValidate(v))
+                    return h.message({custom: t('title-invalid-format')})
+                if(v === p.name)
+                    return h.message({custom: t('modal-room-category-name-no-change-error')})
+                return v
+            })
+    })
+
+    const {
+        register,
+        handleSubmit,
+        reset,
+        formState: {errors}
+    } = useForm<FormFields>({resolver: joiResolver(formSchema)});
+
+    useEffect(() => {
+        if (p.open)
+            reset({name: p.name ?? ''})
+    }, [p.open]);
+
+    function onSubmit(data: FormFields) {
+        p.setOpen(false)
+        p.onSubmit(data.name)
+    }
+
+    return (
+        <ModalWHeader
+            title={p.title}
+            open={p.open}
+            setOpen={p.setOpen}
+            content={
+                <form onSubmit={handleSubmit(onSubmit)} noValidate>
+                    <Label htmlFor="roomCategoryName">{t('modal-room-category-name-label')}</Label>
+                    <Input
+                        id="roomCategoryName"
+                        maxLength={32}
+                        {...register('name')}
+                    />
+                    {errors.name
+                        ? <p className="text-danger font-semibold">{errors.name.message}</p>
+                        : <p className="text-danger invisible font-semibold">L</p>}
+                    <hr className="-ml-6 -mr-6 mt-4 mb-4"/>
+                    <div className="flex gap-3">
+                        <BtnPrimary type="submit">{p.actionLabel}</BtnPrimary>
+                        <BtnSecondary type="button" onClick={() => p.setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                    </div>
+                </form>
+            }
+        />
+    )
+}
+
+interface Props {
+    title: string,
+    actionLabel: string,
+    name?: string,
+    open: boolean,
+    setOpen: (b: boolean) => void,
+    onSubmit: (name: string) => void
+}
+
+interface FormFields {
+    name: string
+}
\ No newline at end of file
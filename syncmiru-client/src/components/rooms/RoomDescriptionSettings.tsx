@@ -0,0 +1,92 @@
+import {ReactElement, useEffect, useState} from "react";
+import {useHistoryState} from "wouter/use-browser-location";
+import {RoomSettingsHistoryState} from "@models/historyState.ts";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {BtnPrimary, BtnSecondary, EditBtn} from "@components/widgets/Button.tsx";
+import {useTranslation} from "react-i18next";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import Label from "@components/widgets/Label.tsx";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {TextAreaModalFix} from "@components/widgets/TextAreaModalFix.tsx";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+
+const ROOM_DESCRIPTION_MAX = 500
+
+export default function RoomDescriptionSettings(p: Props): ReactElement {
+    const {rid} = useHistoryState<RoomSettingsHistoryState>()
+    const {t} = useTranslation()
+    const {rooms, socket} = useMainContext()
+    const [open, setOpen] = useState<boolean>(false)
+    const [description, setDescription] = useState<string>('')
+
+    const room = rooms.get(rid)
+
+    useEffect(() => {
+        p.setLoading(false)
+    }, [rid, rooms]);
+
+    function editClicked() {
+        setDescription(room?.description ?? '')
+        setOpen(true)
+    }
+
+    function changeDescription() {
+        const value = description.trim()
+        p.setLoading(true)
+        setOpen(false)
+        socket!
+            .emitWithAck("set_room_description", {id: rid, description: value.length > 0 ? value : null})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err) {
+                    showPersistentErrorAlert(t('modal-room-description-change-error'))
+                }
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('modal-room-description-change-error'))
+            })
+            .finally(() => p.setLoading(false))
+    }
+
+    return (
+        <>
+            <div className="flex items-center">
+                <p className="w-56 self-start">{t('room-settings-description-label')}</p>
+                <p className="max-w-96 whitespace-pre-wrap break-words">
+                    {room?.description != null ? room.description : t('room-settings-description-empty')}
+                </p>
+                <div className="flex-1"></div>
+                <EditBtn className="w-10" onClick={editClicked}/>
+            </div>
+            <ModalWHeader
+                title={t('modal-change-room-description-title')}
+                open={open}
+                setOpen={setOpen}
+                content={
+                    <div className="flex flex-col">
+                        <Label htmlFor="roomDescription">{t('modal-room-description-label')}</Label>
+                        <TextAreaModalFix
+                            className="p-1.5 w-full h-32 border dark:bg-darkbg"
+                            value={description}
+                            onChange={(e) => setDescription(e.target.value)}
+                        />
+                        <p className={`text-xs self-end ${description.length > ROOM_DESCRIPTION_MAX ? 'text-danger' : ''}`}>
+                            {description.length}/{ROOM_DESCRIPTION_MAX}
+                        </p>
+                        <hr className="-ml-6 -mr-6 mt-4 mb-4"/>
+                        <div className="flex gap-3">
+                            <BtnPrimary
+                                disabled={description.length > ROOM_DESCRIPTION_MAX}
+                                onClick={changeDescription}
+                            >{t('modal-change-action-btn')}</BtnPrimary>
+                            <BtnSecondary onClick={() => setOpen(false)}>{t('modal-keep-btn')}</BtnSecondary>
+                        </div>
+                    </div>
+                }
+            />
+        </>
+    )
+}
+
+interface Props {
+    setLoading: (b: boolean) => void
+}
\ No newline at end of file
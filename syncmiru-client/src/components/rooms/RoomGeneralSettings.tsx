@@ -6,15 +6,19 @@ import {useLocation} from "wouter";
 import {useTranslation} from "react-i18next";
 import RoomNameSettings from "@components/rooms/RoomNameSettings.tsx";
 import DeleteRoom from "@components/rooms/DeleteRoom.tsx";
+import RoomDescriptionSettings from "@components/rooms/RoomDescriptionSettings.tsx";
+import RoomIconSettings from "@components/rooms/RoomIconSettings.tsx";
 
 export default function RoomGeneralSettings(): ReactElement {
     const [_, navigate] = useLocation()
     const {t} = useTranslation()
     const [roomNameLoading, setRoomNameLoading] = useState<boolean>(true)
+    const [roomDescriptionLoading, setRoomDescriptionLoading] = useState<boolean>(true)
+    const [roomIconLoading, setRoomIconLoading] = useState<boolean>(true)
     const [deleteRoomLoading, setDeleteRoomLoading] = useState<boolean>(true)
 
     function showContent() {
-        return !roomNameLoading && !deleteRoomLoading
+        return !roomNameLoading && !roomDescriptionLoading && !roomIconLoading && !deleteRoomLoading
     }
 
     return (
@@ -30,8 +34,10 @@ export default function RoomGeneralSettings(): ReactElement {
                     <div className="flex-1"></div>
                     <CloseBtn onClick={() => navigateToMain(navigate)}></CloseBtn>
                 </div>
-                <div className="flex flex-col m-8">
+                <div className="flex flex-col m-8 gap-y-6">
                     <RoomNameSettings setLoading={(b) => setRoomNameLoading(b)}/>
+                    <RoomIconSettings setLoading={(b) => setRoomIconLoading(b)}/>
+                    <RoomDescriptionSettings setLoading={(b) => setRoomDescriptionLoading(b)}/>
                 </div>
                 <hr/>
                 <div className="ml-8 mr-8 mt-8">
@@ -0,0 +1,231 @@
+import {BtnPrimary, BtnSecondary, Clickable, EditBtn, ZoomResetBtn} from "@components/widgets/Button.tsx";
+import React, {ChangeEvent, ReactElement, useEffect, useRef, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import Upload from "@components/svg/Upload.tsx";
+import Delete from "@components/svg/Delete.tsx";
+import ExifReader from 'exifreader';
+import {showPersistentErrorAlert, showTemporalErrorAlertForModal} from "src/utils/alert.ts";
+import Pic from "@components/svg/Pic.tsx";
+import Slider from "rc-slider";
+import 'rc-slider/assets/index.css';
+import AvatarEditor from 'react-avatar-editor'
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {useHistoryState} from "wouter/use-browser-location";
+import {RoomSettingsHistoryState} from "@models/historyState.ts";
+import Play from "@components/svg/Play.tsx";
+
+export default function RoomIconSettings(p: Props): ReactElement {
+    const {rid} = useHistoryState<RoomSettingsHistoryState>()
+    const {rooms, socket} = useMainContext()
+    const [icon, setIcon] = useState<string>('')
+    const {t} = useTranslation()
+    const [iconActionModalOpen, setIconActionModalOpen] = useState<boolean>(false)
+    const [picEditorModalOpen, setPicEditorModalOpen] = useState<boolean>(false)
+    const iconFileRef = useRef<HTMLInputElement>(null)
+    const [iconFile, setIconFile] = useState<File | string>('')
+    const [iconEditorScale, setIconEditorScale] = useState<number>(1.0)
+    const iconEditorRef = useRef<AvatarEditor>(null)
+
+    useEffect(() => {
+        const room = rooms.get(rid)
+        if(room !== undefined)
+            setIcon(room.icon)
+
+        p.setLoading(false)
+    }, [rid, rooms]);
+
+    function editClicked() {
+        setIconActionModalOpen(true)
+    }
+
+    function deleteIconClicked() {
+        p.setLoading(true)
+        socket!.emitWithAck('delete_room_icon', {id: rid})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err) {
+                    showPersistentErrorAlert(t('modal-room-icon-delete-error'))
+                }
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('modal-room-icon-delete-error'))
+            })
+            .finally(() => {
+                p.setLoading(false)
+                setIconActionModalOpen(false)
+            })
+    }
+
+    function uploadNewIconClicked() {
+        if (iconFileRef.current == null)
+            return;
+
+        iconFileRef.current.value = ''
+        iconFileRef.current.click()
+    }
+
+    function reset() {
+        setIconEditorScale(1.0)
+    }
+
+    async function iconFileChanged(e: ChangeEvent<HTMLInputElement>) {
+        if (e.target.files == null || e.target.files.length === 0)
+            return;
+
+        reset()
+        const picFile = e.target.files[0]
+        const picBuffer = await picFile.arrayBuffer()
+        if(picBuffer.byteLength > 5*1024*1024) {
+            showTemporalErrorAlertForModal(t('modal-room-icon-max-size-error'))
+            return;
+        }
+
+        const tags = await ExifReader.load(picBuffer);
+        if (
+            tags["Image Height"]?.value == undefined
+            || tags["Image Height"]?.value < 128
+            || tags["Image Width"]?.value == undefined
+            || tags["Image Width"]?.value < 128
+        ) {
+            showTemporalErrorAlertForModal(t('modal-room-icon-res-error'))
+            return;
+        }
+
+        setIconFile(picFile)
+        setIconActionModalOpen(false)
+        setPicEditorModalOpen(true)
+    }
+
+    async function setIconClicked() {
+        const canvas: HTMLCanvasElement = scaleTo128(iconEditorRef.current!.getImageScaledToCanvas())
+        const blob = await getCanvasBlob(canvas) as Blob
+        const imgBin = Array.from(new Uint8Array(await blob.arrayBuffer()))
+
+        setPicEditorModalOpen(false)
+        p.setLoading(true)
+        socket!.emitWithAck("set_room_icon", {id: rid, data: imgBin})
+            .then((ack: SocketIoAck<null>) => {
+                if(ack.status === SocketIoAckType.Err) {
+                    showPersistentErrorAlert(t('modal-room-icon-change-error'))
+                }
+            })
+            .catch(() => {
+                showPersistentErrorAlert(t('modal-room-icon-change-error'))
+            })
+            .finally(() => {
+                p.setLoading(false)
+            })
+    }
+
+    function scaleTo128(canvas: HTMLCanvasElement): HTMLCanvasElement {
+        const canvas128 = document.createElement('canvas');
+        canvas128.width = 128;
+        canvas128.height = 128;
+        const ctx = canvas128.getContext('2d') as CanvasRenderingContext2D;
+        ctx.drawImage(canvas, 0, 0, 128, 128);
+        return canvas128
+    }
+
+    function getCanvasBlob(canvas: HTMLCanvasElement): Promise<Blob | null> {
+        return new Promise(function(resolve, reject) {
+            canvas.toBlob(function(blob) {
+                resolve(blob)
+            })
+        })
+    }
+
+    return (
+        <>
+            <div className="flex items-center">
+                <p className="w-56">{t('room-settings-icon-label')}</p>
+                {icon !== ''
+                    ? <img src={`data:image/png;base64, ${icon}`} alt="Icon" className="rounded w-14"/>
+                    : <Play className="w-14"/>}
+                <div className="flex-1"></div>
+                <EditBtn className="w-10" onClick={editClicked}/>
+            </div>
+            <ModalWHeader
+                title={t('modal-room-icon-action-title')}
+                open={iconActionModalOpen}
+                setOpen={setIconActionModalOpen}
+                content={
+                    <div className="flex justify-center">
+                        <Clickable className="w-44 h-32 border" onClick={uploadNewIconClicked}>
+                            <div className="flex flex-col items-center">
+                                <input
+                                    ref={iconFileRef}
+                                    type="file"
+                                    className="hidden"
+                                    accept=".jpg,.jpeg,.png"
+                                    onChange={iconFileChanged}
+                                />
+                                <Upload className="w-8"/>
+                                <p className="mt-2">{t('modal-avatar-change-btn')}</p>
+                            </div>
+                        </Clickable>
+                        <div className="w-4"></div>
+                        <Clickable className="w-44 h-32 border" onClick={deleteIconClicked}>
+                            <div className="flex flex-col items-center">
+                                <Delete className="w-8"/>
+                                <p className="mt-2">{t('modal-room-icon-delete-btn')}</p>
+                            </div>
+                        </Clickable>
+                    </div>
+                }
+            />
+            <ModalWHeader
+                title={t('modal-room-icon-edit-title')}
+                open={picEditorModalOpen}
+                setOpen={setPicEditorModalOpen}
+                content={
+                    <>
+                        <div className="flex flex-col items-center justify-center gap-y-4">
+                            <AvatarEditor
+                                ref={iconEditorRef}
+                                image={iconFile}
+                                width={256}
+                                height={256}
+                                border={50}
+                                borderRadius={16}
+                                color={[0, 0, 0, 0.8]}
+                                scale={iconEditorScale}
+                                rotate={0}
+                            />
+                            <div className="relative">
+                                <div className="flex items-center gap-x-4 w-96">
+                                    <Pic className="w-4"/>
+                                    <Slider
+                                        min={0.5}
+                                        max={5}
+                                        step={0.001}
+                                        defaultValue={1.0}
+                                        value={iconEditorScale}
+                                        onChange={(v) => setIconEditorScale(v as number)}
+                                    />
+                                    <Pic className="w-8"/>
+                                    <ZoomResetBtn
+                                        className="w-10 absolute right-0 -mr-24"
+                                        onClick={() => setIconEditorScale(1.0)}
+                                    />
+                                </div>
+                            </div>
+                        </div>
+                        <hr className="-ml-6 -mr-6 mt-4 mb-4"/>
+                        <div className="flex gap-3">
+                            <BtnPrimary
+                                onClick={setIconClicked}
+                            >{t('modal-change-action-btn')}</BtnPrimary>
+                            <BtnSecondary
+                                onClick={() => setPicEditorModalOpen(false)}>{t('modal-keep-btn')}</BtnSecondary>
+                        </div>
+                    </>
+                }
+            />
+        </>
+    )
+}
+
+interface Props {
+    setLoading: (b: boolean) => void
+}
\ No newline at end of file
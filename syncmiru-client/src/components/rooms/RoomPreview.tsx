@@ -0,0 +1,43 @@
+import {ReactElement, useEffect, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {RoomPreviewClient} from "@models/room.ts";
+import {PlayingState} from "@models/mpv.ts";
+import {timestampPretty} from "src/utils/mpv.ts";
+
+export default function RoomPreview(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const [now, setNow] = useState<number>(Date.now())
+    const watching = p.preview.watching
+    const playing = watching != null && watching.playing_state === PlayingState.Play
+
+    useEffect(() => {
+        if (!playing)
+            return
+
+        const interval = setInterval(() => setNow(Date.now()), 1000)
+        return () => clearInterval(interval)
+    }, [playing]);
+
+    if (watching == null)
+        return <></>
+
+    let position = watching.position
+    if (position != null && playing)
+        position += Math.max(0, now - p.preview.received_at) / 1000
+
+    return (
+        <div className={`flex items-center gap-x-1 text-xs opacity-70 ${p.className || ''}`}>
+            <p className="truncate">
+                {playing
+                    ? t('room-preview-playing', {title: watching.title})
+                    : t('room-preview-paused', {title: watching.title})}
+            </p>
+            {position != null && <p className="ml-auto">{timestampPretty(position)}</p>}
+        </div>
+    )
+}
+
+interface Props {
+    preview: RoomPreviewClient,
+    className?: string
+}
\ No newline at end of file
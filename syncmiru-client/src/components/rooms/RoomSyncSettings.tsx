@@ -11,6 +11,7 @@ import DesyncTolerance from "@components/rooms/DesyncTolerance.tsx";
 import MinorDesyncPlaybackSlow from "@components/rooms/MinorDesyncPlaybackSlow.tsx";
 import MajorDesyncMin from "@components/rooms/MajorDesyncMin.tsx";
 import {InfoBanner} from "@components/widgets/Banner.tsx";
+import AutoNextEpisode from "@components/rooms/AutoNextEpisode.tsx";
 
 export default function RoomSyncSettings(): ReactElement {
     const [_, navigate] = useLocation()
@@ -19,10 +20,11 @@ export default function RoomSyncSettings(): ReactElement {
     const [desyncToleranceLoading, setDesyncToleranceLoading] = useState<boolean>(true)
     const [minorDesyncPlaybackSlowLoading, setMinorDesyncPlaybackSlowLoading] = useState<boolean>(true)
     const [majorDesyncMinLoading, setMajorDesyncMinLoading] = useState<boolean>(true)
+    const [autoNextEpisodeLoading, setAutoNextEpisodeLoading] = useState<boolean>(true)
     const {t} = useTranslation()
 
     function showContent() {
-        return !playbackSpeedLoading && !desyncToleranceLoading && !minorDesyncPlaybackSlowLoading && !majorDesyncMinLoading
+        return !playbackSpeedLoading && !desyncToleranceLoading && !minorDesyncPlaybackSlowLoading && !majorDesyncMinLoading && !autoNextEpisodeLoading
     }
 
     return (
@@ -51,6 +53,10 @@ export default function RoomSyncSettings(): ReactElement {
                             setLoading={(b) => setPlaybackSpeedLoading(b)}
                             rid={rid}
                         />
+                        <AutoNextEpisode
+                            setLoading={(b) => setAutoNextEpisodeLoading(b)}
+                            rid={rid}
+                        />
                     </div>
                 </div>
                 <div className="m-8">
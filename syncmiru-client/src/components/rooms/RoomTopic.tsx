@@ -0,0 +1,103 @@
+import {ReactElement, useEffect} from "react";
+import {useTranslation} from "react-i18next";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {BtnPrimary, BtnSecondary} from "@components/widgets/Button.tsx";
+import {Input} from "@components/widgets/Input.tsx";
+import Label from "@components/widgets/Label.tsx";
+import Joi from "joi";
+import {useForm} from "react-hook-form";
+import {joiResolver} from "@hookform/resolvers/joi";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showTemporalErrorAlertForModal} from "src/utils/alert.ts";
+import {RoomId} from "@models/room.ts";
+
+export default function RoomTopic(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const {socket, rooms} = useMainContext()
+    const topic = rooms.get(p.rid)?.topic ?? null
+
+    const formSchema = Joi.object({
+        topic: Joi
+            .string()
+            .allow('')
+            .max(128)
+            .pattern(/^[^\r\n]*$/)
+            .messages({
+                "string.max": t('room-topic-invalid'),
+                "string.pattern.base": t('room-topic-invalid')
+            })
+    })
+
+    const {
+        register,
+        handleSubmit,
+        reset,
+        formState: {errors}
+    } = useForm<FormFields>({resolver: joiResolver(formSchema)});
+
+    useEffect(() => {
+        if (p.open)
+            reset({topic: topic ?? ''})
+    }, [p.open]);
+
+    function setTopic(value: string | null) {
+        socket!.emitWithAck('set_room_topic', {id: p.rid, topic: value})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err) {
+                    showTemporalErrorAlertForModal(t('room-topic-set-error'))
+                    return
+                }
+                p.setOpen(false)
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('room-topic-set-error'))
+            })
+    }
+
+    function onSubmit(data: FormFields) {
+        const value = data.topic.trim()
+        setTopic(value.length > 0 ? value : null)
+    }
+
+    return (
+        <ModalWHeader
+            title={t('room-topic-title')}
+            open={p.open}
+            setOpen={p.setOpen}
+            content={
+                <form onSubmit={handleSubmit(onSubmit)} noValidate>
+                    <Label htmlFor="topic">{t('room-topic-label')}</Label>
+                    <Input
+                        id="topic"
+                        maxLength={128}
+                        {...register('topic')}
+                    />
+                    {errors.topic
+                        ? <p className="text-danger font-semibold">{errors.topic.message}</p>
+                        : <p className="text-danger invisible font-semibold">L</p>}
+                    <hr className="-ml-6 -mr-6 mt-4 mb-4"/>
+                    <div className="flex gap-3">
+                        <BtnPrimary type="submit">{t('modal-change-action-btn')}</BtnPrimary>
+                        <BtnSecondary type="button" onClick={() => p.setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                        <div className="flex-1"></div>
+                        {topic != null &&
+                            <BtnSecondary type="button" onClick={() => setTopic(null)}>
+                                {t('room-topic-clear-btn')}
+                            </BtnSecondary>}
+                    </div>
+                </form>
+            }
+        />
+    )
+}
+
+interface Props {
+    rid: RoomId,
+    open: boolean,
+    setOpen: (b: boolean) => void
+}
+
+interface FormFields {
+    topic: string
+}
\ No newline at end of file
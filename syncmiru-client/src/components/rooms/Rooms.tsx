@@ -3,12 +3,21 @@ import {useMainContext} from "@hooks/useMainContext.ts";
 import Loading from "@components/Loading.tsx";
 import {
     JoinedRoomInfoSrv,
+    RoomCategory,
+    RoomCategoryId,
+    RoomCategoryNameChange,
+    RoomDescription,
+    RoomIconChange,
     RoomId,
+    RoomLayout,
     RoomMap,
     RoomNameChange,
+    RoomPreview as RoomPreviewSrv,
+    RoomPreviewClient,
     RoomSettingsClient,
     RoomSrv,
     RoomsWOrder,
+    RoomTopic,
     RoomValue
 } from "@models/room.ts";
 import {showPersistentErrorAlert} from "src/utils/alert.ts";
@@ -17,7 +26,7 @@ import Settings from "@components/svg/Settings.tsx";
 import {useTranslation} from "react-i18next";
 import {useLocation} from "wouter";
 import {RoomSettingsHistoryState} from "@models/historyState.ts";
-import {arrayMove, List, OnChangeMeta, RenderListParams} from "react-movable";
+import {List, OnChangeMeta, RenderListParams} from "react-movable";
 import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
 import {navigateToLoginFormMain} from "src/utils/navigate.ts";
 import {
@@ -54,15 +63,21 @@ import SubtitlesCrossed from "@components/svg/SubtitlesCrossed.tsx";
 import {MpvMsgMood, showMpvReadyMessages} from "src/utils/mpv.ts";
 import {UserAudioSubtitles, UserChangeAudioSync, UserChangeSubSync} from "@models/mpv.ts";
 import {changeActiveVideo} from "src/utils/playlist.ts";
+import RoomPreview from "@components/rooms/RoomPreview.tsx";
+import {arrayBufferToBase64} from "src/utils/encoding.ts";
+import {moveRoomLayoutItem, roomLayoutItems, roomLayoutOf} from "src/utils/room.ts";
+import RoomCategoryHeader from "@components/rooms/RoomCategoryHeader.tsx";
 
 export default function Rooms(): ReactElement {
     const ctx = useMainContext()
     const {t} = useTranslation()
     const [_, navigate] = useLocation()
     const [roomsOrder, setRoomsOrder] = useState<Array<RoomId>>([])
+    const [categories, setCategories] = useState<Array<RoomCategory>>([])
     const [mousePos, setMousePos] = useState<[number, number]>([0, 0])
     const [roomsFetching, setRoomsFetching] = useState<boolean>(true)
     const [roomUsersFetching, setRoomUsersFetching] = useState<boolean>(true)
+    const [roomPreviews, setRoomPreviews] = useState<Map<RoomId, RoomPreviewClient>>(new Map<RoomId, RoomPreviewClient>())
 
     const usersRef = useRef(ctx.users)
 
@@ -73,16 +88,25 @@ export default function Rooms(): ReactElement {
 
             ctx.socket.on('rooms', onRooms)
             ctx.socket.on('room_name_change', onRoomNameChange)
+            ctx.socket.on('room_description', onRoomDescription)
+            ctx.socket.on('room_topic', onRoomTopic)
+            ctx.socket.on('room_icon', onRoomIcon)
             ctx.socket.on('room_order', onRoomOrder)
+            ctx.socket.on('room_categories', onRoomCategories)
+            ctx.socket.on('room_category_name_change', onRoomCategoryNameChange)
+            ctx.socket.on('del_room_categories', onDeleteRoomCategories)
             ctx.socket.on('room_user_ping', onRoomUserPing)
             ctx.socket.on('user_ready_state_change', onUserReadyStateChange)
             ctx.socket.on('change_audio_sync', onChangeAudioSync)
             ctx.socket.on('change_sub_sync', onChangeSubSync)
+            ctx.socket.on('room_previews', onRoomPreviews)
 
             ctx.socket.emitWithAck("get_rooms")
                 .then((roomsWOrder: RoomsWOrder) => {
                     addRoomsFromSrv(roomsWOrder.rooms)
                     setRoomsOrder(roomsWOrder.room_order)
+                    setCategories(roomsWOrder.categories)
+                    onRoomPreviews(roomsWOrder.previews)
                 })
                 .catch(() => {
                     navigateToLoginFormMain(navigate)
@@ -112,11 +136,18 @@ export default function Rooms(): ReactElement {
             if (ctx.socket !== undefined) {
                 ctx.socket.off('rooms', onRooms)
                 ctx.socket.off('room_name_change', onRoomNameChange)
+                ctx.socket.off('room_description', onRoomDescription)
+                ctx.socket.off('room_topic', onRoomTopic)
+                ctx.socket.off('room_icon', onRoomIcon)
                 ctx.socket.off('room_order', onRoomOrder)
+                ctx.socket.off('room_categories', onRoomCategories)
+                ctx.socket.off('room_category_name_change', onRoomCategoryNameChange)
+                ctx.socket.off('del_room_categories', onDeleteRoomCategories)
                 ctx.socket.off('room_user_ping', onRoomUserPing)
                 ctx.socket.off('user_ready_state_change', onUserReadyStateChange)
                 ctx.socket.off('change_audio_sync', onChangeAudioSync)
                 ctx.socket.off('change_sub_sync', onChangeSubSync)
+                ctx.socket.off('room_previews', onRoomPreviews)
             }
         }
     }, [ctx.socket]);
@@ -177,12 +208,33 @@ export default function Rooms(): ReactElement {
             for (const roomNameChange of roomNameChanges) {
                 const roomValue = m.get(roomNameChange.rid)
                 if (roomValue != null)
-                    m.set(roomNameChange.rid, {name: roomNameChange.room_name})
+                    m.set(roomNameChange.rid, {...roomValue, name: roomNameChange.room_name})
             }
             return m
         })
     }
 
+    function updateRoom(rid: RoomId, change: Partial<RoomValue>) {
+        ctx.setRooms((p) => {
+            const roomValue = p.get(rid)
+            if (roomValue == null)
+                return p
+            return new Map<RoomId, RoomValue>([...p]).set(rid, {...roomValue, ...change})
+        })
+    }
+
+    function onRoomDescription(roomDescription: RoomDescription) {
+        updateRoom(roomDescription.id, {description: roomDescription.description})
+    }
+
+    function onRoomTopic(roomTopic: RoomTopic) {
+        updateRoom(roomTopic.id, {topic: roomTopic.topic})
+    }
+
+    function onRoomIcon(roomIcon: RoomIconChange) {
+        updateRoom(roomIcon.id, {icon: roomIcon.icon != null ? arrayBufferToBase64(roomIcon.icon) : ''})
+    }
+
     function forceDisconnectFromRoomOnFetchFailure() {
         roomDisconnectChangeState()
         showPersistentErrorAlert(t('room-join-failed'))
@@ -226,17 +278,64 @@ export default function Rooms(): ReactElement {
         setRoomsOrder((p) => {
             return p.filter(x => !roomIdsToDelete.includes(x))
         })
+        setCategories((p) => {
+            return p.map((x) => ({...x, room_order: x.room_order.filter(rid => !roomIdsToDelete.includes(rid))}))
+        })
+        setRoomPreviews((p) => {
+            const m = new Map<RoomId, RoomPreviewClient>(p)
+            for (const rid of roomIdsToDelete)
+                m.delete(rid)
+            return m
+        })
+    }
+
+    function onRoomPreviews(previews: Array<RoomPreviewSrv>) {
+        const receivedAt = Date.now()
+        setRoomPreviews((p) => {
+            const m = new Map<RoomId, RoomPreviewClient>(p)
+            for (const preview of previews)
+                m.set(preview.rid, {...preview, received_at: receivedAt})
+            return m
+        })
+    }
+
+    function onRoomOrder(layout: RoomLayout) {
+        applyRoomLayout(layout)
+    }
+
+    function applyRoomLayout(layout: RoomLayout) {
+        setRoomsOrder(layout.room_order)
+        setCategories((p) => layout.categories.map((x) => ({
+            ...x,
+            name: p.find(c => c.id === x.id)?.name ?? ''
+        })))
+    }
+
+    function onRoomCategories(newCategories: Array<RoomCategory>) {
+        setCategories((p) => [
+            ...p.filter(x => !newCategories.some(c => c.id === x.id)),
+            ...newCategories
+        ])
     }
 
-    function onRoomOrder(roomOrder: Array<RoomId>) {
-        setRoomsOrder(roomOrder)
+    function onRoomCategoryNameChange(change: RoomCategoryNameChange) {
+        setCategories((p) => p.map((x) => x.id === change.id ? {...x, name: change.name} : x))
+    }
+
+    function onDeleteRoomCategories(categoryIdsToDelete: Array<RoomCategoryId>) {
+        setCategories((p) => p.filter(x => !categoryIdsToDelete.includes(x.id)))
     }
 
     function addRoomsFromSrv(rooms: Array<RoomSrv>) {
         ctx.setRooms((p) => {
             const m: RoomMap = new Map<RoomId, RoomValue>()
             for (const room of rooms)
-                m.set(room.id, {name: room.name})
+                m.set(room.id, {
+                    name: room.name,
+                    description: room.description,
+                    topic: room.topic,
+                    icon: room.icon != null ? arrayBufferToBase64(room.icon) : ''
+                })
 
             return new Map<RoomId, RoomValue>([...p, ...m])
         })
@@ -600,11 +699,11 @@ export default function Rooms(): ReactElement {
                                                         const type = playlistSrv[idStr].type
                                                         if(type === PlaylistEntryType.Video) {
                                                             const valueSrv = playlistSrv[idStr] as PlaylistEntryVideoSrv
-                                                            p.set(id, new PlaylistEntryVideo(valueSrv.source, valueSrv.path))
+                                                            p.set(id, new PlaylistEntryVideo(valueSrv.source, valueSrv.path, valueSrv.metadata, valueSrv.media, valueSrv.clip))
                                                         }
                                                         else if(type === PlaylistEntryType.Url) {
                                                             const value = playlistSrv[idStr] as PlaylistEntryUrlSrv
-                                                            p.set(id, new PlaylistEntryUrl(value.url))
+                                                            p.set(id, new PlaylistEntryUrl(value.url, value.clip))
                                                         }
                                                     }
                                                     ctx.setPlaylist(p)
@@ -659,21 +758,21 @@ export default function Rooms(): ReactElement {
     }
 
     function orderChanged(e: OnChangeMeta) {
-        let oldOrder: Array<RoomId>
-        const newOrder = arrayMove(roomsOrder, e.oldIndex, e.newIndex)
-        setRoomsOrder((p) => {
-            oldOrder = p
-            return newOrder
-        })
-        ctx.socket!.emitWithAck("set_room_order", {room_order: newOrder})
+        const oldRoomsOrder = roomsOrder
+        const oldCategories = categories
+        const newLayout = moveRoomLayoutItem(roomLayoutOf(roomsOrder, categories), e.oldIndex, e.newIndex)
+        applyRoomLayout(newLayout)
+        ctx.socket!.emitWithAck("set_room_order", newLayout)
             .then((ack: SocketIoAck<null>) => {
                 if (ack.status === SocketIoAckType.Err) {
-                    setRoomsOrder(oldOrder)
+                    setRoomsOrder(oldRoomsOrder)
+                    setCategories(oldCategories)
                     showPersistentErrorAlert(t('room-order-change-error'))
                 }
             })
             .catch(() => {
-                setRoomsOrder(oldOrder)
+                setRoomsOrder(oldRoomsOrder)
+                setCategories(oldCategories)
                 showPersistentErrorAlert(t('room-order-change-error'))
             })
     }
@@ -716,7 +815,7 @@ export default function Rooms(): ReactElement {
                 </div>}
             <List
                 onChange={orderChanged}
-                values={roomsOrder}
+                values={roomLayoutItems(roomLayoutOf(roomsOrder, categories))}
                 renderList={({children, props}: RenderListParams) => {
                     return (
                         <ul
@@ -725,11 +824,30 @@ export default function Rooms(): ReactElement {
                         >{children}</ul>
                     )
                 }}
-                renderItem={({value: rid, props}) => {
+                renderItem={({value: item, props}) => {
                     const {key, ...restProps} = props
+                    if (item.category) {
+                        const category = categories.find(x => x.id === item.id)
+                        if (category == null)
+                            return <></>
+                        return (
+                            <li
+                                key={key}
+                                {...restProps}
+                                style={{
+                                    ...props.style,
+                                    listStyleType: 'none'
+                                }}
+                            >
+                                <RoomCategoryHeader category={category}/>
+                            </li>
+                        )
+                    }
+                    const rid = item.id
                     const roomUids = ctx.roomUsers.get(rid)
                     const hasUsers = roomUids != null && roomUids.size > 0
                     const roomValue = ctx.rooms.get(rid)
+                    const roomPreview = roomPreviews.get(rid)
                     if (roomValue == null)
                         return <></>
                     return (
@@ -744,12 +862,18 @@ export default function Rooms(): ReactElement {
                             <div className="flex flex-col">
                                 <div
                                     data-movable-handle={true}
+                                    title={roomValue.description ?? undefined}
                                     className='flex p-2 mb-0.5 items-center gap-x-2 w-full group break-words rounded hover:bg-gray-100 dark:hover:bg-gray-700 hover:cursor-pointer'
                                     onMouseDown={(e) => onRoomMouseDown(e, rid)}
                                     onMouseUp={(e) => onRoomMouseUp(e, rid)}
                                 >
-                                    <Play className="min-w-5 w-5"/>
-                                    <p className="w-[8.0rem] text-left">{roomValue.name}</p>
+                                    {roomValue.icon !== ''
+                                        ? <img src={`data:image/png;base64, ${roomValue.icon}`} alt="Icon" className="rounded min-w-5 w-5"/>
+                                        : <Play className="min-w-5 w-5"/>}
+                                    <div className="flex flex-col w-[8.0rem] text-left">
+                                        <p>{roomValue.name}</p>
+                                        {roomValue.topic != null && <p className="text-xs opacity-70 truncate">{roomValue.topic}</p>}
+                                    </div>
                                     <div className="flex-1"></div>
                                     <div
                                         role="button"
@@ -764,6 +888,7 @@ export default function Rooms(): ReactElement {
                                         <Settings/>
                                     </div>
                                 </div>
+                                {roomPreview != null && <RoomPreview className="pl-9 pr-2 -mt-1.5 mb-1" preview={roomPreview}/>}
                                 {hasUsers && <div className="flex flex-col gap-y-0.5 mb-4">
                                     {Array.from(roomUids)?.map((uid) => {
                                         const user = ctx.users.get(uid)
@@ -779,6 +904,7 @@ export default function Rooms(): ReactElement {
                                                     key={uid}
                                                     id={uid}
                                                     visible={uid === ctx.roomUidClicked}
+                                                    presence={ctx.uid2presence.get(uid)}
                                                     content={
                                                         <Clickable
                                                             className={`w-full py-1.5 ${uid === ctx.roomUidClicked ? 'bg-gray-100 dark:bg-gray-700' : ''}`}>
@@ -6,6 +6,7 @@ import Wrench from "@components/svg/Wrench.tsx";
 import {useLocation} from "wouter";
 import {useTranslation} from "react-i18next";
 import RoomCreate from "@components/rooms/RoomCreate.tsx";
+import RoomCategoryCreate from "@components/rooms/RoomCategoryCreate.tsx";
 
 export default function SrvInfo({homeSrv}: Props): ReactElement {
     const [_, navigate] = useLocation()
@@ -34,6 +35,7 @@ export default function SrvInfo({homeSrv}: Props): ReactElement {
                 </div>
             </MenuItem>
             <RoomCreate/>
+            <RoomCategoryCreate/>
         </Menu>
     )
 }
@@ -0,0 +1,27 @@
+import {ReactElement} from "react";
+
+// Source: https://www.svgrepo.com/svg/511059/bookmark
+// License: MIT
+
+export default function Bookmark({className}: Props): ReactElement {
+    return (
+        <svg
+            xmlns="http://www.w3.org/2000/svg"
+            viewBox="0 0 24 24"
+            fill="none"
+            className={className || ''}
+        >
+            <path
+                d="M5 4a1 1 0 011-1h12a1 1 0 011 1v17l-7-4-7 4V4z"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+            />
+        </svg>
+    )
+}
+
+interface Props {
+    className?: string
+}
\ No newline at end of file
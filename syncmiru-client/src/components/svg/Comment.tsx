@@ -0,0 +1,27 @@
+import {ReactElement} from "react";
+
+// Source: https://www.svgrepo.com/svg/511017/comment-alt
+// License: MIT
+
+export default function Comment({className}: Props): ReactElement {
+    return (
+        <svg
+            xmlns="http://www.w3.org/2000/svg"
+            viewBox="0 0 24 24"
+            fill="none"
+            className={className || ''}
+        >
+            <path
+                d="M4 5a1 1 0 011-1h14a1 1 0 011 1v11a1 1 0 01-1 1h-7l-5 4v-4H5a1 1 0 01-1-1V5zM8 9h8M8 13h5"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+            />
+        </svg>
+    )
+}
+
+interface Props {
+    className?: string
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+import {ReactElement} from "react";
+
+// Source: https://www.svgrepo.com/svg/511150/chart-bar-vertical-01
+// License: MIT
+
+export default function Poll({className}: Props): ReactElement {
+    return (
+        <svg
+            xmlns="http://www.w3.org/2000/svg"
+            viewBox="0 0 24 24"
+            fill="none"
+            className={className || ''}
+        >
+            <path
+                d="M5 21V13M12 21V5M19 21V9M3 21h18"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+            />
+        </svg>
+    )
+}
+
+interface Props {
+    className?: string
+}
\ No newline at end of file
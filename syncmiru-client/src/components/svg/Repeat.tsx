@@ -0,0 +1,27 @@
+import {ReactElement} from "react";
+
+// Source: https://www.svgrepo.com/svg/511093/repeat
+// License: MIT
+
+export default function Repeat({className}: Props): ReactElement {
+    return (
+        <svg
+            xmlns="http://www.w3.org/2000/svg"
+            viewBox="0 0 24 24"
+            fill="none"
+            className={className || ''}
+        >
+            <path
+                d="M17 2l4 4-4 4M3 11v-1a4 4 0 014-4h14M7 22l-4-4 4-4M21 13v1a4 4 0 01-4 4H3"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+            />
+        </svg>
+    )
+}
+
+interface Props {
+    className?: string
+}
\ No newline at end of file
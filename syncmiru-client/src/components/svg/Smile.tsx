@@ -0,0 +1,27 @@
+import {ReactElement} from "react";
+
+// Source: https://www.svgrepo.com/svg/511135/smile
+// License: MIT
+
+export default function Smile({className}: Props): ReactElement {
+    return (
+        <svg
+            xmlns="http://www.w3.org/2000/svg"
+            viewBox="0 0 24 24"
+            fill="none"
+            className={className || ''}
+        >
+            <path
+                d="M12 21a9 9 0 100-18 9 9 0 000 18zM8.5 14.5s1.2 1.5 3.5 1.5 3.5-1.5 3.5-1.5M9 9.5h.01M15 9.5h.01"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+            />
+        </svg>
+    )
+}
+
+interface Props {
+    className?: string
+}
\ No newline at end of file
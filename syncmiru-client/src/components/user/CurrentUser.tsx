@@ -1,4 +1,4 @@
-import React, {ReactElement} from "react";
+import React, {ReactElement, useState} from "react";
 import {Clickable} from "@components/widgets/Button.tsx";
 import Settings from "@components/svg/Settings.tsx";
 import Loading from "@components/Loading.tsx";
@@ -6,10 +6,14 @@ import {useMainContext} from "@hooks/useMainContext.ts";
 import {useLocation} from "wouter";
 import {UserValueClient} from "@models/user.ts";
 import Avatar from "@components/widgets/Avatar.tsx";
+import CustomStatus from "@components/user/CustomStatus.tsx";
+import {useTranslation} from "react-i18next";
 
 export default function CurrentUser(): ReactElement {
     const [_, navigate] = useLocation()
-    const {uid, users} = useMainContext()
+    const {t} = useTranslation()
+    const {uid, users, uid2presence} = useMainContext()
+    const [customStatusOpen, setCustomStatusOpen] = useState<boolean>(false)
 
     function userSettingsClicked() {
         navigate('/main/user-settings/account')
@@ -19,16 +23,19 @@ export default function CurrentUser(): ReactElement {
         const user = users.get(uid) as UserValueClient
         return (
             <div className="flex justify-between items-center border-t p-2 h-16">
-                <div className="flex items-center">
+                <Clickable className="flex items-center text-left" onClick={() => setCustomStatusOpen(true)}>
                     <Avatar className="min-w-12 w-12 mr-3" picBase64={user.avatar}/>
                     <div className="flex flex-col items-start justify-center">
                         <p className="break-words max-w-[7.2rem]">{user.displayname}</p>
-                        <p className="text-xs -mt-1">{user.username}</p>
+                        <p className="text-xs -mt-1 truncate max-w-[7.2rem]">
+                            {uid2presence.get(uid)?.custom_status ?? t('presence-custom-status-set-btn')}
+                        </p>
                     </div>
-                </div>
+                </Clickable>
                 <Clickable className="p-3" onClick={userSettingsClicked}>
                     <Settings className="h-6"/>
                 </Clickable>
+                <CustomStatus open={customStatusOpen} setOpen={setCustomStatusOpen}/>
             </div>
         )
     }
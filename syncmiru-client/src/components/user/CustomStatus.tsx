@@ -0,0 +1,101 @@
+import {ReactElement, useEffect} from "react";
+import {useTranslation} from "react-i18next";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {BtnPrimary, BtnSecondary} from "@components/widgets/Button.tsx";
+import {Input} from "@components/widgets/Input.tsx";
+import Label from "@components/widgets/Label.tsx";
+import Joi from "joi";
+import {useForm} from "react-hook-form";
+import {joiResolver} from "@hookform/resolvers/joi";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showTemporalErrorAlertForModal} from "src/utils/alert.ts";
+
+export default function CustomStatus(p: Props): ReactElement {
+    const {t} = useTranslation()
+    const {socket, uid, uid2presence} = useMainContext()
+    const customStatus = uid2presence.get(uid)?.custom_status ?? null
+
+    const formSchema = Joi.object({
+        customStatus: Joi
+            .string()
+            .allow('')
+            .max(64)
+            .pattern(/^[^\r\n]*$/)
+            .messages({
+                "string.max": t('presence-custom-status-invalid'),
+                "string.pattern.base": t('presence-custom-status-invalid')
+            })
+    })
+
+    const {
+        register,
+        handleSubmit,
+        reset,
+        formState: {errors}
+    } = useForm<FormFields>({resolver: joiResolver(formSchema)});
+
+    useEffect(() => {
+        if (p.open)
+            reset({customStatus: customStatus ?? ''})
+    }, [p.open]);
+
+    function setCustomStatus(value: string | null) {
+        socket!.emitWithAck('set_custom_status', {custom_status: value})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err) {
+                    showTemporalErrorAlertForModal(t('presence-custom-status-set-error'))
+                    return
+                }
+                p.setOpen(false)
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('presence-custom-status-set-error'))
+            })
+    }
+
+    function onSubmit(data: FormFields) {
+        const value = data.customStatus.trim()
+        setCustomStatus(value.length > 0 ? value : null)
+    }
+
+    return (
+        <ModalWHeader
+            title={t('presence-custom-status-title')}
+            open={p.open}
+            setOpen={p.setOpen}
+            content={
+                <form onSubmit={handleSubmit(onSubmit)} noValidate>
+                    <Label htmlFor="customStatus">{t('presence-custom-status-label')}</Label>
+                    <Input
+                        id="customStatus"
+                        maxLength={64}
+                        {...register('customStatus')}
+                    />
+                    {errors.customStatus
+                        ? <p className="text-danger font-semibold">{errors.customStatus.message}</p>
+                        : <p className="text-danger invisible font-semibold">L</p>}
+                    <hr className="-ml-6 -mr-6 mt-4 mb-4"/>
+                    <div className="flex gap-3">
+                        <BtnPrimary type="submit">{t('modal-change-action-btn')}</BtnPrimary>
+                        <BtnSecondary type="button" onClick={() => p.setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                        <div className="flex-1"></div>
+                        {customStatus != null &&
+                            <BtnSecondary type="button" onClick={() => setCustomStatus(null)}>
+                                {t('presence-custom-status-clear-btn')}
+                            </BtnSecondary>}
+                    </div>
+                </form>
+            }
+        />
+    )
+}
+
+interface Props {
+    open: boolean,
+    setOpen: (b: boolean) => void
+}
+
+interface FormFields {
+    customStatus: string
+}
\ No newline at end of file
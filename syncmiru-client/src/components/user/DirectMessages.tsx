@@ -0,0 +1,181 @@
+import {ReactElement, useEffect, useRef, useState} from "react";
+import {useTranslation} from "react-i18next";
+import {useMainContext} from "@hooks/useMainContext.ts";
+import {ModalWHeader} from "@components/widgets/Modal.tsx";
+import {BtnDanger, BtnPrimary, BtnSecondary, BtnTextPrimary} from "@components/widgets/Button.tsx";
+import {TextAreaModalFix} from "@components/widgets/TextAreaModalFix.tsx";
+import DateTimeLocalPretty from "@components/widgets/DateTimeLocalPretty.tsx";
+import Check from "@components/svg/Check.tsx";
+import {DirectMessage, DirectMessagesRead} from "@models/directMessage.ts";
+import {UserId} from "@models/user.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showTemporalErrorAlertForModal} from "src/utils/alert.ts";
+
+const DIRECT_MESSAGES_PAGE_SIZE = 50
+
+export default function DirectMessages(p: Props): ReactElement {
+    const ctx = useMainContext()
+    const {t} = useTranslation()
+    const [messages, setMessages] = useState<Array<DirectMessage>>([])
+    const [hasOlder, setHasOlder] = useState<boolean>(false)
+    const [content, setContent] = useState<string>('')
+    const bottomRef = useRef<HTMLDivElement>(null)
+
+    const user = ctx.users.get(p.uid)
+    const contentValid = content.trim().length > 0 && content.length <= 1000
+
+    useEffect(() => {
+        if (!p.open || ctx.socket === undefined)
+            return
+
+        setMessages([])
+        setContent('')
+        loadMessages(null)
+        readMessages()
+    }, [p.open, p.uid]);
+
+    useEffect(() => {
+        if (!p.open || ctx.socket === undefined)
+            return
+
+        ctx.socket.on('direct_message', onDirectMessage)
+        ctx.socket.on('direct_messages_read', onDirectMessagesRead)
+        return () => {
+            ctx.socket!.off('direct_message', onDirectMessage)
+            ctx.socket!.off('direct_messages_read', onDirectMessagesRead)
+        }
+    }, [p.open, p.uid]);
+
+    useEffect(() => {
+        bottomRef.current?.scrollIntoView()
+    }, [messages.length > 0 ? messages[messages.length - 1].id : null]);
+
+    function loadMessages(beforeId: number | null) {
+        ctx.socket!.emitWithAck('get_direct_messages', {uid: p.uid, before_id: beforeId})
+            .then((ack: SocketIoAck<Array<DirectMessage>>) => {
+                if (ack.status === SocketIoAckType.Err) {
+                    showTemporalErrorAlertForModal(t('dm-received-error'))
+                    return
+                }
+                const page = ack.payload as Array<DirectMessage>
+                setHasOlder(page.length === DIRECT_MESSAGES_PAGE_SIZE)
+                setMessages((m) => [...page.reverse(), ...m])
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('dm-received-error'))
+            })
+    }
+
+    function readMessages() {
+        ctx.socket!.emitWithAck('read_direct_messages', {id: p.uid})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Ok)
+                    p.onRead(p.uid)
+            })
+            .catch(() => {})
+    }
+
+    function onDirectMessage(message: DirectMessage) {
+        if (message.sender !== p.uid)
+            return
+
+        setMessages((m) => [...m, message])
+        readMessages()
+    }
+
+    function onDirectMessagesRead(payload: DirectMessagesRead) {
+        if (payload.uid !== p.uid)
+            return
+
+        setMessages((m) => m.map((x) => x.sender === ctx.uid ? {...x, read: true} : x))
+    }
+
+    function sendMessage() {
+        ctx.socket!.emitWithAck('send_direct_message', {uid: p.uid, content: content})
+            .then((ack: SocketIoAck<DirectMessage>) => {
+                if (ack.status === SocketIoAckType.Err) {
+                    showTemporalErrorAlertForModal(t('dm-send-error'))
+                    return
+                }
+                setMessages((m) => [...m, ack.payload as DirectMessage])
+                setContent('')
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('dm-send-error'))
+            })
+    }
+
+    function blockClicked() {
+        const event = p.blocked ? 'unblock_user' : 'block_user'
+        ctx.socket!.emitWithAck(event, {id: p.uid})
+            .then((ack: SocketIoAck<null>) => {
+                if (ack.status === SocketIoAckType.Err)
+                    showTemporalErrorAlertForModal(t('dm-block-error'))
+                else
+                    p.setBlocked(p.uid, !p.blocked)
+            })
+            .catch(() => {
+                showTemporalErrorAlertForModal(t('dm-block-error'))
+            })
+    }
+
+    return (
+        <ModalWHeader
+            title={t('dm-title', {displayname: user?.displayname ?? ''})}
+            open={p.open}
+            setOpen={p.setOpen}
+            content={
+                <div className="flex flex-col">
+                    <div className="flex flex-col gap-y-2 h-[40dvh] overflow-y-auto mb-4">
+                        {hasOlder &&
+                            <BtnTextPrimary className="self-center" onClick={() => loadMessages(messages[0].id)}>
+                                {t('dm-load-older-btn')}
+                            </BtnTextPrimary>}
+                        {messages.length === 0 && <p className="self-center opacity-70">{t('dm-empty')}</p>}
+                        {messages.map((message) => {
+                            const mine = message.sender === ctx.uid
+                            return (
+                                <div
+                                    key={message.id}
+                                    className={`flex flex-col max-w-[80%] rounded p-2 ${mine ? 'self-end bg-indigo-100 dark:bg-indigo-900' : 'self-start bg-gray-100 dark:bg-gray-700'}`}
+                                >
+                                    <p className="whitespace-pre-wrap break-words">{message.content}</p>
+                                    <div className="flex items-center gap-x-1 self-end text-xs opacity-70">
+                                        <DateTimeLocalPretty datetime={new Date(message.created_at)}/>
+                                        {mine && message.read && <Check className="w-3"/>}
+                                    </div>
+                                </div>
+                            )
+                        })}
+                        <div ref={bottomRef}></div>
+                    </div>
+                    {p.blocked
+                        ? <p className="mb-4 text-center">{t('dm-blocked-text')}</p>
+                        : <TextAreaModalFix
+                            className="p-1.5 w-full h-24 border dark:bg-darkbg mb-4"
+                            value={content}
+                            onChange={(e) => setContent(e.target.value)}
+                        />}
+                    <div className="flex gap-3">
+                        {!p.blocked &&
+                            <BtnPrimary disabled={!contentValid} onClick={sendMessage}>{t('dm-send-btn')}</BtnPrimary>}
+                        <BtnSecondary onClick={() => p.setOpen(false)}>{t('modal-cancel-btn')}</BtnSecondary>
+                        <div className="flex-1"></div>
+                        {p.blocked
+                            ? <BtnSecondary onClick={blockClicked}>{t('dm-unblock-btn')}</BtnSecondary>
+                            : <BtnDanger onClick={blockClicked}>{t('dm-block-btn')}</BtnDanger>}
+                    </div>
+                </div>
+            }
+        />
+    )
+}
+
+interface Props {
+    uid: UserId,
+    open: boolean,
+    setOpen: (b: boolean) => void,
+    blocked: boolean,
+    setBlocked: (uid: UserId, blocked: boolean) => void,
+    onRead: (uid: UserId) => void
+}
\ No newline at end of file
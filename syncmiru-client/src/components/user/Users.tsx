@@ -1,9 +1,9 @@
-import React, {ReactElement, useEffect, useState} from "react";
+import React, {ReactElement, useEffect, useRef, useState} from "react";
 import {useMainContext} from "@hooks/useMainContext.ts";
 import {AvatarChange, DisplaynameChange, UserClient, UserId, UserMap, UserValueClient} from "@models/user.ts";
 import Avatar from "@components/widgets/Avatar.tsx";
 import 'src/rc-tooltip.css'
-import {Clickable} from "@components/widgets/Button.tsx";
+import {BtnSecondary, Clickable} from "@components/widgets/Button.tsx";
 import {SearchInput} from "@components/widgets/Input.tsx";
 import {useTranslation} from "react-i18next";
 import {navigateToLoginFormMain} from "src/utils/navigate.ts";
@@ -14,6 +14,13 @@ import {UserRoomMap} from "@models/roomUser.ts";
 import {RoomId} from "@models/room.ts";
 import {arrayBufferToBase64} from "src/utils/encoding.ts";
 import {createLocaleComparator} from "src/utils/sort.ts";
+import DirectMessages from "@components/user/DirectMessages.tsx";
+import {DirectMessage, UnreadCount} from "@models/directMessage.ts";
+import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
+import {showPersistentErrorAlert} from "src/utils/alert.ts";
+import {Presence, PresenceClient, PresenceStatus} from "@models/presence.ts";
+
+const IDLE_TIMEOUT_MS = 5 * 60 * 1000
 
 export default function Users(): ReactElement {
     const [_, navigate] = useLocation()
@@ -28,7 +35,10 @@ export default function Users(): ReactElement {
         setRoomUidClicked,
         usersClickedUid,
         setUsersClickedUid,
-        uid2audioSub
+        uid2audioSub,
+        activeVideoId,
+        uid2presence,
+        setUid2presence
     } = useMainContext()
     const [usersLoading, setUsersLoading] = useState<boolean>(true)
     const [onlineUsers, setOnlineUsers]
@@ -36,6 +46,12 @@ export default function Users(): ReactElement {
     const [offlineUsers, setOfflineUsers]
         = useState<Array<UserClient>>(new Array<UserClient>())
     const [onlineUids, setOnlineUids] = useState<Array<UserId>>(new Array<UserId>());
+    const [unreadCounts, setUnreadCounts] = useState<Map<UserId, number>>(new Map<UserId, number>())
+    const [blockedUids, setBlockedUids] = useState<Set<UserId>>(new Set<UserId>())
+    const [dmUid, setDmUid] = useState<UserId>(-1)
+    const [dmOpen, setDmOpen] = useState<boolean>(false)
+    const lastActivityRef = useRef<number>(Date.now())
+    const idleRef = useRef<boolean>(false)
 
     const [searchValue, setSearchValue] = useState<string>("")
     const searchValueLC = searchValue.toLowerCase()
@@ -70,6 +86,93 @@ export default function Users(): ReactElement {
         }
     }, [socket]);
 
+    useEffect(() => {
+        if (socket !== undefined) {
+            socket.emitWithAck('get_direct_message_unread_counts')
+                .then((ack: SocketIoAck<Array<UnreadCount>>) => {
+                    if (ack.status === SocketIoAckType.Err) {
+                        showPersistentErrorAlert(t('dm-unread-received-error'))
+                        return
+                    }
+                    const counts = ack.payload as Array<UnreadCount>
+                    setUnreadCounts(new Map<UserId, number>(counts.map((x) => [x.uid, x.count])))
+                })
+                .catch(() => {
+                    showPersistentErrorAlert(t('dm-unread-received-error'))
+                })
+            socket.emitWithAck('get_blocked_users')
+                .then((ack: SocketIoAck<Array<UserId>>) => {
+                    if (ack.status === SocketIoAckType.Err) {
+                        showPersistentErrorAlert(t('dm-blocked-received-error'))
+                        return
+                    }
+                    setBlockedUids(new Set<UserId>(ack.payload as Array<UserId>))
+                })
+                .catch(() => {
+                    showPersistentErrorAlert(t('dm-blocked-received-error'))
+                })
+        }
+    }, [socket]);
+
+    useEffect(() => {
+        if (socket !== undefined) {
+            socket.on('presence', onPresence)
+
+            socket.emitWithAck('get_presence')
+                .then((ack: SocketIoAck<Array<Presence>>) => {
+                    if (ack.status === SocketIoAckType.Err) {
+                        showPersistentErrorAlert(t('presence-received-error'))
+                        return
+                    }
+                    onPresence(ack.payload as Array<Presence>)
+                })
+                .catch(() => {
+                    showPersistentErrorAlert(t('presence-received-error'))
+                })
+        }
+        return () => {
+            if (socket !== undefined) {
+                socket.off('presence', onPresence)
+            }
+        }
+    }, [socket]);
+
+    useEffect(() => {
+        if (socket === undefined)
+            return
+
+        function activity() {
+            lastActivityRef.current = Date.now()
+            if (idleRef.current)
+                setIdle(false)
+        }
+
+        window.addEventListener('mousemove', activity)
+        window.addEventListener('keydown', activity)
+        const interval = setInterval(() => {
+            if (!idleRef.current
+                && activeVideoId == null
+                && Date.now() - lastActivityRef.current >= IDLE_TIMEOUT_MS)
+                setIdle(true)
+        }, 30 * 1000)
+        return () => {
+            window.removeEventListener('mousemove', activity)
+            window.removeEventListener('keydown', activity)
+            clearInterval(interval)
+        }
+    }, [socket, activeVideoId]);
+
+    useEffect(() => {
+        if (socket !== undefined) {
+            socket.on('direct_message', onDirectMessage)
+        }
+        return () => {
+            if (socket !== undefined) {
+                socket.off('direct_message', onDirectMessage)
+            }
+        }
+    }, [socket, dmOpen, dmUid]);
+
     useEffect(() => {
         if (socket !== undefined) {
             socket.on('displayname_change', onDisplaynameChange)
@@ -92,7 +195,7 @@ export default function Users(): ReactElement {
                 socket.off("del_users", onDelUsers);
             }
         }
-    }, [socket, usersClickedUid]);
+    }, [socket, usersClickedUid, dmUid]);
 
     useEffect(() => {
         if (socket !== undefined) {
@@ -131,6 +234,11 @@ export default function Users(): ReactElement {
 
     function onOffline(uid: UserId) {
         setOnlineUids((p) => p.filter(x => x !== uid))
+        setUid2presence((p) => {
+            const m = new Map<UserId, PresenceClient>(p)
+            m.delete(uid)
+            return m
+        })
 
         if (uid === roomUidClicked)
             setRoomUidClicked(-1)
@@ -149,6 +257,24 @@ export default function Users(): ReactElement {
         })
     }
 
+    function onPresence(presences: Array<Presence>) {
+        const receivedAt = Date.now()
+        setUid2presence((p) => {
+            const m = new Map<UserId, PresenceClient>(p)
+            for (const presence of presences)
+                m.set(presence.uid, {...presence, received_at: receivedAt})
+            return m
+        })
+    }
+
+    function setIdle(idle: boolean) {
+        idleRef.current = idle
+        socket!.emitWithAck('set_idle', idle)
+            .catch(() => {
+                idleRef.current = !idle
+            })
+    }
+
     function onDisplaynameChange(payload: DisplaynameChange) {
         let user = users.get(payload.uid)
         if (user === undefined)
@@ -174,6 +300,10 @@ export default function Users(): ReactElement {
     function onDelUsers(delUids: Array<UserId>) {
         if(delUids.includes(usersClickedUid))
             setUsersClickedUid(-1)
+        if(delUids.includes(dmUid)) {
+            setDmOpen(false)
+            setDmUid(-1)
+        }
 
         setUsers((p) => {
             let m: UserMap = new Map<UserId, UserValueClient>();
@@ -185,6 +315,68 @@ export default function Users(): ReactElement {
         })
     }
 
+    function onDirectMessage(message: DirectMessage) {
+        if (dmOpen && message.sender === dmUid)
+            return
+
+        setUnreadCounts((p) => new Map<UserId, number>(p).set(message.sender, (p.get(message.sender) ?? 0) + 1))
+    }
+
+    function directMessagesRead(readUid: UserId) {
+        setUnreadCounts((p) => {
+            const m = new Map<UserId, number>(p)
+            m.delete(readUid)
+            return m
+        })
+    }
+
+    function setBlocked(blockedUid: UserId, blocked: boolean) {
+        setBlockedUids((p) => {
+            const blockedSet = new Set<UserId>(p)
+            if (blocked)
+                blockedSet.add(blockedUid)
+            else
+                blockedSet.delete(blockedUid)
+            return blockedSet
+        })
+    }
+
+    function directMessageClicked(otherUid: UserId) {
+        setUsersClickedUid(-1)
+        setDmUid(otherUid)
+        setDmOpen(true)
+    }
+
+    function userActions(u: UserClient): ReactElement | undefined {
+        if (u.id === uid)
+            return undefined
+        return (
+            <BtnSecondary className="mt-2 w-full" onClick={() => directMessageClicked(u.id)}>
+                {t('dm-open-btn')}
+            </BtnSecondary>
+        )
+    }
+
+    function presenceLine(u: UserClient): ReactElement {
+        const presence = uid2presence.get(u.id)
+        if (presence == null)
+            return <></>
+        if (presence.watching != null)
+            return <p className="text-xs -mt-0.5 opacity-70 truncate max-w-[10.4rem]">
+                {t('presence-watching-short', {title: presence.watching.title})}
+            </p>
+        if (presence.custom_status != null)
+            return <p className="text-xs -mt-0.5 opacity-70 truncate max-w-[10.4rem]">{presence.custom_status}</p>
+        return <></>
+    }
+
+    function unreadBadge(u: UserClient): ReactElement {
+        const count = unreadCounts.get(u.id)
+        if (count == null || count === 0)
+            return <></>
+        return <p className="ml-auto rounded-full bg-primary text-white text-xs px-1.5">{count}</p>
+    }
+
     function tooltipVisibilityChanged(visible: boolean, idx: number) {
         if (!visible)
             setUsersClickedUid(-1)
@@ -216,14 +408,20 @@ export default function Users(): ReactElement {
                             <div className="flex items-center">
                                 <Clickable
                                     className={`p-1 pl-3 ml-1 mr-1 mtext-left flex items-center w-full text-left ${u.id === usersClickedUid ? 'bg-gray-100 dark:bg-gray-700' : ''}`}>
-                                    <Avatar className="min-w-10 w-10 mr-2"
+                                    <Avatar className={`min-w-10 w-10 mr-2 ${uid2presence.get(u.id)?.status === PresenceStatus.Idle ? 'opacity-50' : ''}`}
                                             picBase64={u.avatar}/>
-                                    <p className="break-words max-w-[10.4rem]">{u.displayname}</p>
+                                    <div className="flex flex-col">
+                                        <p className="break-words max-w-[10.4rem]">{u.displayname}</p>
+                                        {presenceLine(u)}
+                                    </div>
+                                    {unreadBadge(u)}
                                 </Clickable>
                             </div>
                         }
                         user={u}
                         audioSub={uid2audioSub.get(u.id)}
+                        presence={uid2presence.get(u.id)}
+                        actions={userActions(u)}
                         tooltipOnlineVisibilityChanged={tooltipVisibilityChanged}
                     />
                 )
@@ -244,15 +442,25 @@ export default function Users(): ReactElement {
                                     <Avatar className="min-w-10 w-10 mr-2"
                                             picBase64={u.avatar}/>
                                     <p className="break-words max-w-[10.4rem]">{u.displayname}</p>
+                                    {unreadBadge(u)}
                                 </Clickable>
                             </div>
                         }
                         user={u}
                         audioSub={undefined}
+                        actions={userActions(u)}
                         tooltipOnlineVisibilityChanged={tooltipVisibilityChanged}
                     />
                 )
             })}
+            {dmUid !== -1 && <DirectMessages
+                uid={dmUid}
+                open={dmOpen}
+                setOpen={setDmOpen}
+                blocked={blockedUids.has(dmUid)}
+                setBlocked={setBlocked}
+                onRead={directMessagesRead}
+            />}
         </div>
     )
 }
\ No newline at end of file
@@ -5,13 +5,51 @@ import Tooltip from "rc-tooltip";
 import {UserValueClient} from "@models/user.ts";
 import {UserAudioSubtitles} from "@models/mpv.ts";
 import LeftRightArrow from "@components/svg/LeftRightArrow.tsx";
+import {PresenceClient, PresenceStatus} from "@models/presence.ts";
+import {PlayingState} from "@models/mpv.ts";
+import {timestampPretty} from "src/utils/mpv.ts";
+import {useTranslation} from "react-i18next";
 
 export default function UserInfoTooltip(p: Props): ReactElement {
+    const {t} = useTranslation()
+
     function onVisibleChanged(visible: boolean) {
         if (p.tooltipOnlineVisibilityChanged != null)
             p.tooltipOnlineVisibilityChanged(visible, p.id)
     }
 
+    function watchingPosition(presence: PresenceClient): number | null {
+        const watching = presence.watching
+        if (watching == null || watching.position == null)
+            return null
+        if (watching.playing_state === PlayingState.Pause)
+            return watching.position
+        return watching.position + (Date.now() - presence.received_at) / 1000
+    }
+
+    function presenceInfo(presence: PresenceClient): ReactElement {
+        const position = watchingPosition(presence)
+        return (
+            <div className="mt-2 flex flex-col max-w-[12.3rem]">
+                {presence.status === PresenceStatus.Idle &&
+                    <p className="text-sm opacity-70">{t('presence-idle')}</p>}
+                {presence.custom_status != null &&
+                    <p className="text-sm break-words">{presence.custom_status}</p>}
+                {presence.watching != null &&
+                    <>
+                        <p className="text-sm">
+                            {presence.watching.playing_state === PlayingState.Play
+                                ? t('presence-watching')
+                                : t('presence-paused')}
+                        </p>
+                        <p className="text-sm font-semibold break-words">{presence.watching.title}</p>
+                        {position != null && <p className="text-sm">{timestampPretty(position)}</p>}
+                    </>
+                }
+            </div>
+        )
+    }
+
     return (
         <Tooltip onVisibleChange={onVisibleChanged}
                  placement="bottom"
@@ -40,6 +78,8 @@ export default function UserInfoTooltip(p: Props): ReactElement {
                                  </div>
                              </div>
                          }
+                         {p.presence !== undefined && presenceInfo(p.presence)}
+                         {p.actions}
                      </div>
                  }>
             {p.content}
@@ -54,4 +94,6 @@ interface Props {
     tooltipOnlineVisibilityChanged?: (e: boolean, id: number) => void,
     visible?: boolean
     audioSub: UserAudioSubtitles | undefined
+    actions?: ReactElement
+    presence?: PresenceClient
 }
\ No newline at end of file
@@ -56,4 +56,11 @@ export const roomNameValidate = (roomName: string): boolean => {
         && roomName.length <= 16
         && !/^\s$/.test(roomName[0])
         && !/^\s$/.test(roomName[roomName.length - 1])
+}
+
+export const roomCategoryNameValidate = (name: string): boolean => {
+    return name.length >= 1
+        && name.length <= 32
+        && !/^\s$/.test(name[0])
+        && !/^\s$/.test(name[name.length - 1])
 }
\ No newline at end of file
@@ -3,7 +3,7 @@ import {MainContextModel, RoomConnectionState} from "@models/context.ts";
 import {UserId, UserValueClient} from "src/models/user.ts";
 import {RoomId, RoomValue} from "@models/room.ts";
 import Decimal from "decimal.js";
-import {PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
+import {PlayingAccess, PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
 import {UserReadyState} from "@components/widgets/ReadyState.tsx";
 import {UserAudioSubtitles} from "@models/mpv.ts";
 
@@ -52,7 +52,7 @@ export const MainContext = createContext<MainContextModel>(
         setPlaylist: (v) => {},
         playlistOrder: new Array<PlaylistEntryId>(),
         setPlaylistOrder: (v) => {},
-        jwts: new Map<PlaylistEntryId, string>(),
+        jwts: new Map<PlaylistEntryId, PlayingAccess>(),
         setJwts: (v) => {},
         mpvRunning: false,
         setMpvRunning: (v) => {},
@@ -0,0 +1,6 @@
+import {UserId} from "@models/user.ts";
+
+export interface ChatMessage {
+    uid: UserId,
+    content: string
+}
\ No newline at end of file
@@ -3,10 +3,11 @@ import {UserId, UserMap} from "src/models/user.ts";
 import {RoomId, RoomMap, RoomSettingsClient} from "@models/room.ts";
 import {Dispatch, MutableRefObject, SetStateAction} from "react";
 import {UserRoomMap, UserRoomPingsClient} from "@models/roomUser.ts";
-import {PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
+import {Bookmark, PlayingAccess, PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
 import {UserReadyState} from "@components/widgets/ReadyState.tsx";
-import {UserAudioSubtitles} from "@models/mpv.ts";
+import {AbLoop, UserAudioSubtitles} from "@models/mpv.ts";
 import Decimal from "decimal.js";
+import {PresenceClient} from "@models/presence.ts";
 
 export interface MainContextModel {
     uid: number
@@ -49,8 +50,8 @@ export interface MainContextModel {
     setPlaylist: Dispatch<SetStateAction<Map<PlaylistEntryId, PlaylistEntry>>>
     playlistOrder: Array<PlaylistEntryId>
     setPlaylistOrder: Dispatch<SetStateAction<Array<PlaylistEntryId>>>
-    jwts: Map<PlaylistEntryId, string>,
-    setJwts: Dispatch<SetStateAction<Map<PlaylistEntryId, string>>>,
+    jwts: Map<PlaylistEntryId, PlayingAccess>,
+    setJwts: Dispatch<SetStateAction<Map<PlaylistEntryId, PlayingAccess>>>,
     mpvRunning: boolean,
     setMpvRunning: Dispatch<SetStateAction<boolean>>,
     modalShown: boolean,
@@ -67,7 +68,13 @@ export interface MainContextModel {
     setUid2audioSub: Dispatch<SetStateAction<Map<UserId, UserAudioSubtitles>>>,
     timestampTimerRef: MutableRefObject<number> | null,
     reportedPlaybackSpeed: Decimal | null,
-    setReportedPlaybackSpeed: Dispatch<SetStateAction<Decimal | null>>
+    setReportedPlaybackSpeed: Dispatch<SetStateAction<Decimal | null>>,
+    abLoop: AbLoop | null,
+    setAbLoop: Dispatch<SetStateAction<AbLoop | null>>,
+    bookmarks: Array<Bookmark>,
+    setBookmarks: Dispatch<SetStateAction<Array<Bookmark>>>,
+    uid2presence: Map<UserId, PresenceClient>,
+    setUid2presence: Dispatch<SetStateAction<Map<UserId, PresenceClient>>>
 }
 
 export enum RoomConnectionState {
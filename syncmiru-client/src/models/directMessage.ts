@@ -0,0 +1,19 @@
+import {UserId} from "@models/user.ts";
+
+export interface DirectMessage {
+    id: number,
+    sender: UserId,
+    recipient: UserId,
+    content: string,
+    created_at: string,
+    read: boolean
+}
+
+export interface UnreadCount {
+    uid: UserId,
+    count: number
+}
+
+export interface DirectMessagesRead {
+    uid: UserId
+}
\ No newline at end of file
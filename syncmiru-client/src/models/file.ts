@@ -1,7 +1,13 @@
 interface FileInfoCommon {
     name: string,
     file_type: FileType
-    size?: number
+    size?: number,
+    episode?: Episode | null
+}
+
+export interface Episode {
+    season: number | null,
+    episode: number
 }
 
 export interface FileInfoClient extends FileInfoCommon {
@@ -17,6 +23,26 @@ export enum FileType {
     Directory = 1
 }
 
+export interface FilesPageSrv {
+    files: Array<FileInfoSrv>,
+    next_cursor: string | null
+}
+
+export interface FoundFileSrv {
+    full_path: string,
+    source: string,
+    path: string,
+    name: string,
+    mtime: string,
+    size?: number
+}
+
+export enum FileSortBy {
+    Name = 0,
+    Mtime = 1,
+    Size = 2
+}
+
 export enum FileKind {
     Video = 0
 }
\ No newline at end of file
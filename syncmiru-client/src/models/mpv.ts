@@ -39,6 +39,16 @@ export interface UserSeek {
     timestamp: number
 }
 
+export interface AbLoop {
+    a: number,
+    b: number
+}
+
+export interface UserLoop {
+    uid: UserId,
+    ab_loop: AbLoop | null
+}
+
 export interface UserSpeedChangeSrv {
     uid: UserId,
     speed: string
@@ -96,4 +106,10 @@ export interface MpvState {
     playing_state: PlayingState
     playback_speed: string
     timestamp: number
+    ab_loop: AbLoop | null
+}
+
+export interface UserReaction {
+    uid: UserId,
+    reaction: string
 }
\ No newline at end of file
@@ -5,11 +5,62 @@ export type PlaylistEntryId = number
 export interface PlaylistEntryVideoSrv {
     source: string,
     path: string,
+    subtitles: Array<string>,
+    metadata: EntryMetadata | null,
+    media: MediaInfo | null,
+    clip: Clip,
     type: PlaylistEntryType
 }
 
+export interface Clip {
+    start: number | null,
+    end: number | null
+}
+
+export interface PlaylistEntryClip {
+    playlist_entry_id: PlaylistEntryId,
+    clip: Clip
+}
+
+export interface ClipEnded {
+    playlist_entry_id: PlaylistEntryId,
+    timestamp: number
+}
+
+export interface TrackInfo {
+    language: string | null,
+    name: string | null,
+    codec: string | null
+}
+
+export interface MediaInfo {
+    duration: number | null,
+    width: number | null,
+    height: number | null,
+    audio_tracks: Array<TrackInfo>,
+    subtitle_tracks: Array<TrackInfo>
+}
+
+export interface PlaylistEntryMedia {
+    playlist_entry_id: PlaylistEntryId,
+    media: MediaInfo
+}
+
+export interface PlaylistEntryMetadata {
+    playlist_entry_id: PlaylistEntryId,
+    metadata: EntryMetadata
+}
+
+export interface EntryMetadata {
+    title: string | null,
+    year: number | null,
+    plot: string | null,
+    poster: string | null
+}
+
 export interface PlaylistEntryUrlSrv {
     url: string,
+    clip: Clip,
     type: PlaylistEntryType,
 }
 
@@ -19,27 +70,82 @@ export enum PlaylistEntryType {
 }
 
 export abstract class PlaylistEntry {
+    public abstract clip: Clip
     public abstract pretty(): string
 }
 
 export class PlaylistEntryVideo implements PlaylistEntry {
     public type: PlaylistEntryType = PlaylistEntryType.Video;
-    constructor(public source: string, public path: string) {}
+    constructor(
+        public source: string,
+        public path: string,
+        public metadata: EntryMetadata | null = null,
+        public media: MediaInfo | null = null,
+        public clip: Clip = {start: null, end: null}
+    ) {}
 
     public pretty() {
+        if (this.metadata?.title != null) {
+            if (this.metadata.year != null)
+                return `${this.metadata.title} (${this.metadata.year})`
+            return this.metadata.title
+        }
         return `${this.source}:${this.path}`
     }
 }
 
 export class PlaylistEntryUrl implements PlaylistEntry {
     public type: PlaylistEntryType = PlaylistEntryType.Url;
-    constructor(public url: string) {}
+    constructor(public url: string, public clip: Clip = {start: null, end: null}) {}
 
     public pretty() {
         return this.url
     }
 }
 
+export enum PlayingAccessType {
+    Jwt = "jwt",
+    Presigned = "presigned"
+}
+
+export interface PlayingAccessJwt {
+    type: PlayingAccessType.Jwt,
+    jwt: string
+}
+
+export interface PlayingAccessPresigned {
+    type: PlayingAccessType.Presigned,
+    url: string
+}
+
+export type FileAccess = PlayingAccessJwt | PlayingAccessPresigned
+
+export type SubtitleAccess = FileAccess & {
+    name: string
+}
+
+export type PlayingAccess = FileAccess & {
+    subtitles: Array<SubtitleAccess>
+}
+
+export type SubtitleId = number
+
+export interface SubtitleInfo {
+    playlist_entry_id: PlaylistEntryId,
+    id: SubtitleId,
+    uid: UserId,
+    name: string
+}
+
+export interface PosterBin {
+    data: Array<number>
+}
+
+export interface SubtitleBin {
+    name: string,
+    data: Array<number>
+}
+
 export interface AddVideoFilesRespSrv {
     uid: UserId
     entries: Record<string, PlaylistEntryVideoSrv>
@@ -58,4 +164,39 @@ export interface DeletePlaylistEntry {
 export interface ChangePlaylistOrder {
     uid: UserId,
     order: Array<PlaylistEntryId>
+}
+
+export interface Bookmark {
+    id: number,
+    timestamp: number,
+    label: string,
+    uid: UserId | null
+}
+
+export interface BookmarkAdded {
+    playlist_entry_id: PlaylistEntryId,
+    bookmark: Bookmark
+}
+
+export interface BookmarkDeleted {
+    playlist_entry_id: PlaylistEntryId,
+    bookmark_id: number
+}
+
+export interface Comment {
+    id: number,
+    timestamp: number,
+    content: string,
+    uid: UserId | null
+}
+
+export interface CommentsWindow {
+    from: number,
+    to: number,
+    comments: Array<Comment>
+}
+
+export interface CommentAdded {
+    playlist_entry_id: PlaylistEntryId,
+    comment: Comment
 }
\ No newline at end of file
@@ -0,0 +1,32 @@
+import {UserId} from "@models/user.ts";
+import {PlaylistEntryId} from "@models/playlist.ts";
+
+export type PollId = number
+
+export interface PollOption {
+    text: string,
+    playlist_entry_id: PlaylistEntryId | null
+}
+
+export interface Poll {
+    id: PollId,
+    uid: UserId,
+    question: string,
+    options: Array<PollOption>,
+    multi_choice: boolean,
+    activate_winner: boolean,
+    closes_at: string | null,
+    closed: boolean,
+    tallies: Array<number>,
+    winner: number | null
+}
+
+export interface PollState {
+    poll: Poll,
+    votes: Array<number>
+}
+
+export interface PollTallies {
+    id: PollId,
+    tallies: Array<number>
+}
\ No newline at end of file
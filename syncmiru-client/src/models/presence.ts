@@ -0,0 +1,28 @@
+import {UserId} from "@models/user.ts";
+import {RoomId} from "@models/room.ts";
+import {PlaylistEntryId} from "@models/playlist.ts";
+import {PlayingState} from "@models/mpv.ts";
+
+export enum PresenceStatus {
+    Online = "Online",
+    Idle = "Idle"
+}
+
+export interface Watching {
+    rid: RoomId,
+    playlist_entry_id: PlaylistEntryId,
+    title: string,
+    position: number | null,
+    playing_state: PlayingState
+}
+
+export interface Presence {
+    uid: UserId,
+    status: PresenceStatus,
+    custom_status: string | null,
+    watching: Watching | null
+}
+
+export interface PresenceClient extends Presence {
+    received_at: number
+}
\ No newline at end of file
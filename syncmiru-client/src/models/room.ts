@@ -7,15 +7,23 @@ import {
 } from "@models/playlist.ts";
 import {UserId} from "@models/user.ts";
 import {UserAudioSubtitles} from "@models/mpv.ts";
+import {Watching} from "@models/presence.ts";
 
 export type RoomId = number
 
 export interface RoomValue {
     name: string,
+    description: string | null,
+    topic: string | null,
+    icon: string
 }
 
-export interface RoomSrv extends RoomValue {
-    id: RoomId
+export interface RoomSrv {
+    id: RoomId,
+    name: string,
+    description: string | null,
+    topic: string | null,
+    icon: ArrayBuffer | null
 }
 
 export type RoomMap = Map<RoomId, RoomValue>
@@ -25,6 +33,21 @@ export interface RoomNameChange {
     room_name: string
 }
 
+export interface RoomDescription {
+    id: RoomId,
+    description: string | null
+}
+
+export interface RoomTopic {
+    id: RoomId,
+    topic: string | null
+}
+
+export interface RoomIconChange {
+    id: RoomId,
+    icon: ArrayBuffer | null
+}
+
 export interface RoomPlaybackSpeed {
     id: RoomId,
     playback_speed: string
@@ -38,6 +61,44 @@ export interface RoomDesyncTolerance {
 export interface RoomsWOrder {
     rooms: Array<RoomSrv>
     room_order: Array<RoomId>
+    categories: Array<RoomCategory>
+    previews: Array<RoomPreview>
+}
+
+export type RoomCategoryId = number
+
+export interface RoomCategoryOrder {
+    id: RoomCategoryId,
+    room_order: Array<RoomId>
+}
+
+export interface RoomCategory extends RoomCategoryOrder {
+    name: string
+}
+
+export interface RoomCategoryNameChange {
+    id: RoomCategoryId,
+    name: string
+}
+
+export interface RoomLayout {
+    room_order: Array<RoomId>,
+    categories: Array<RoomCategoryOrder>
+}
+
+export interface RoomLayoutItem {
+    category: boolean,
+    id: RoomId | RoomCategoryId
+}
+
+export interface RoomPreview {
+    rid: RoomId,
+    members: Array<UserId>,
+    watching: Watching | null
+}
+
+export interface RoomPreviewClient extends RoomPreview {
+    received_at: number
 }
 
 export interface RoomSettingsSrv {
@@ -0,0 +1,24 @@
+export type UploadId = number
+
+export interface UploadStatus {
+    id: UploadId,
+    name: string,
+    size: number,
+    offset: number,
+    finished: boolean
+}
+
+export interface UploadAuth {
+    homeSrv: string,
+    jwt: string,
+    hwidHash: string
+}
+
+export class UploadError extends Error {
+    desc: string | null
+
+    constructor(desc: string | null) {
+        super(desc ?? 'upload error')
+        this.desc = desc
+    }
+}
\ No newline at end of file
@@ -1,2 +1,4 @@
 export const SOCKETIO_ACK_TIMEOUT_MS = 5000
-export const SYNCMIRU_VERSION = "1.0.0"
\ No newline at end of file
+export const SYNCMIRU_VERSION = "1.0.0"
+export const AB_LOOP_JUMP_TOLERANCE = 0.5
+export const REACTIONS = ["👍", "😂", "😮", "😢", "😡", "❤️", "🔥", "👏"]
@@ -55,6 +55,13 @@ export function timestampPretty(timestamp: number) {
 
 }
 
+export function timestampParse(s: string): number | null {
+    const parts = s.trim().split(':')
+    if (parts.length > 3 || !parts.every(x => /^\d+(\.\d+)?$/.test(x)))
+        return null
+    return parts.reduce((acc, x) => acc * 60 + parseFloat(x), 0)
+}
+
 export enum MpvMsgMood {
     Neutral = 0,
     Bad = 1,
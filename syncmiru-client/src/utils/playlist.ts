@@ -1,9 +1,24 @@
-import {PlaylistEntryId, PlaylistEntryVideo} from "@models/playlist.ts";
+import Decimal from "decimal.js";
+import {
+    Clip,
+    FileAccess,
+    MediaInfo,
+    PlayingAccess,
+    PlayingAccessType,
+    PlaylistEntryId,
+    PlaylistEntryVideo,
+    SubtitleAccess,
+    SubtitleBin,
+    SubtitleId,
+    TrackInfo
+} from "@models/playlist.ts";
+import {invoke} from "@tauri-apps/api/core";
 import {SocketIoAck, SocketIoAckType} from "@models/socketio.ts";
 import {showPersistentErrorAlert} from "src/utils/alert.ts";
 import {forceDisconnectFromRoom} from "src/utils/room.ts";
 import {MainContextModel} from "@models/context.ts";
 import {TFunction} from "i18next";
+import {timestampPretty} from "src/utils/mpv.ts";
 
 export function changeActiveVideo(ctx: MainContextModel, t: TFunction<"translation", undefined>, entryId: PlaylistEntryId) {
     ctx.setPlaylist((playlist) => {
@@ -14,18 +29,18 @@ export function changeActiveVideo(ctx: MainContextModel, t: TFunction<"translati
         if(entry instanceof PlaylistEntryVideo)
             reqIds.add(entryId)
 
-        const jwtsTmp: Map<PlaylistEntryId, string> = new Map<PlaylistEntryId, string>()
+        const jwtsTmp: Map<PlaylistEntryId, PlayingAccess> = new Map<PlaylistEntryId, PlayingAccess>()
         let promises = []
         for(const id of reqIds) {
             promises.push(ctx.socket!.emitWithAck("req_playing_jwt", {playlist_entry_id: id})
-                .then((ack: SocketIoAck<string>) => {
+                .then((ack: SocketIoAck<PlayingAccess>) => {
                     if(ack.status === SocketIoAckType.Err) {
                         showPersistentErrorAlert(t('playlist-entry-req-jwt-error'))
                         forceDisconnectFromRoom(ctx, t)
                         return
                     }
-                    const jwt = ack.payload as string
-                    jwtsTmp.set(entryId, jwt)
+                    const access = ack.payload as PlayingAccess
+                    jwtsTmp.set(entryId, access)
                 })
                 .catch(() => {
                     showPersistentErrorAlert(t('playlist-entry-req-jwt-error'))
@@ -41,4 +56,85 @@ export function changeActiveVideo(ctx: MainContextModel, t: TFunction<"translati
 
         return playlist
     })
+}
+
+function sourceData(access: FileAccess, sourceUrl: string) {
+    if (access.type === PlayingAccessType.Presigned)
+        return {source_url: access.url, jwt: null}
+    return {source_url: sourceUrl, jwt: access.jwt}
+}
+
+export function loadFromSourceData(access: FileAccess, sourceUrl: string, playbackSpeed: Decimal, clip: Clip) {
+    return {...sourceData(access, sourceUrl), playback_speed: playbackSpeed, start: clip.start ?? 0}
+}
+
+export function subAddData(sub: SubtitleAccess, sourceUrl: string) {
+    return {...sourceData(sub, sourceUrl), title: sub.name}
+}
+
+export function loadUploadedSubtitle(ctx: MainContextModel, entryId: PlaylistEntryId, subtitleId: SubtitleId): Promise<unknown> {
+    return ctx.socket!.emitWithAck('get_subtitle', {playlist_entry_id: entryId, subtitle_id: subtitleId})
+        .then((ack: SocketIoAck<SubtitleBin>) => {
+            if (ack.status === SocketIoAckType.Err)
+                throw new Error('subtitle download failed')
+
+            const sub = ack.payload as SubtitleBin
+            const text = new TextDecoder().decode(new Uint8Array(sub.data))
+            const data = {source_url: `memory://${text}`, jwt: null, title: sub.name}
+            return invoke('mpv_sub_add', {data: JSON.stringify(data)})
+        })
+}
+
+export async function uploadSubtitle(ctx: MainContextModel, entryId: PlaylistEntryId, file: File) {
+    const bin = new Uint8Array(await file.arrayBuffer())
+    const startAck: SocketIoAck<SubtitleId> = await ctx.socket!.emitWithAck(
+        'upload_subtitle_start',
+        {playlist_entry_id: entryId, name: file.name, size: bin.byteLength}
+    )
+    if (startAck.status === SocketIoAckType.Err)
+        throw new Error('subtitle upload rejected')
+
+    const uploadId = startAck.payload as SubtitleId
+    for (let offset = 0; offset < bin.byteLength; offset += SUBTITLE_UPLOAD_CHUNK_SIZE) {
+        const chunk = Array.from(bin.subarray(offset, offset + SUBTITLE_UPLOAD_CHUNK_SIZE))
+        const chunkAck: SocketIoAck<null> = await ctx.socket!.emitWithAck(
+            'upload_subtitle_chunk',
+            {upload_id: uploadId, data: chunk}
+        )
+        if (chunkAck.status === SocketIoAckType.Err)
+            throw new Error('subtitle upload failed')
+    }
+
+    const finishAck: SocketIoAck<null> = await ctx.socket!.emitWithAck('upload_subtitle_finish', {upload_id: uploadId})
+    if (finishAck.status === SocketIoAckType.Err)
+        throw new Error('subtitle upload failed')
+}
+
+export const SUBTITLE_UPLOAD_MAX_SIZE = 5 * 1024 * 1024
+const SUBTITLE_UPLOAD_CHUNK_SIZE = 256 * 1024
+
+export function prettyMediaInfo(media: MediaInfo, t: TFunction<"translation", undefined>): string {
+    const parts: Array<string> = []
+    if (media.duration != null) {
+        const total = Math.round(media.duration)
+        const h = Math.floor(total / 3600)
+        const m = Math.floor((total % 3600) / 60).toString().padStart(h > 0 ? 2 : 1, '0')
+        const s = (total % 60).toString().padStart(2, '0')
+        parts.push(h > 0 ? `${h}:${m}:${s}` : `${m}:${s}`)
+    }
+    if (media.height != null)
+        parts.push(`${media.height}p`)
+
+    const languages = (tracks: Array<TrackInfo>) => [...new Set(tracks.map(x => x.language ?? '?'))].join('/')
+    if (media.audio_tracks.length > 0)
+        parts.push(`${t('playlist-media-audio')} ${languages(media.audio_tracks)}`)
+    if (media.subtitle_tracks.length > 0)
+        parts.push(`${t('playlist-media-subtitles')} ${languages(media.subtitle_tracks)}`)
+    return parts.join(' · ')
+}
+
+export function prettyClip(clip: Clip): string {
+    const start = clip.start != null ? timestampPretty(clip.start) : ''
+    const end = clip.end != null ? timestampPretty(clip.end) : ''
+    return `${start}–${end}`
 }
\ No newline at end of file
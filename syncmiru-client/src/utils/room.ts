@@ -5,8 +5,9 @@ import {invoke} from "@tauri-apps/api/core";
 import {showPersistentErrorAlert} from "src/utils/alert.ts";
 import {TFunction} from "i18next";
 import {UserReadyState} from "@components/widgets/ReadyState.tsx";
-import {PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
+import {PlayingAccess, PlaylistEntry, PlaylistEntryId} from "@models/playlist.ts";
 import {UserAudioSubtitles} from "@models/mpv.ts";
+import {RoomCategory, RoomLayout, RoomLayoutItem} from "@models/room.ts";
 
 export function forceDisconnectFromRoom(ctx: MainContextModel, t: TFunction<"translation", undefined>) {
     ctx.setRoomConnection(RoomConnectionState.Disconnecting)
@@ -63,9 +64,61 @@ function clearValuesAfterDisconnect(ctx: MainContextModel) {
     ctx.setUidPing(new Map<UserId, number>())
     ctx.setPlaylist(new Map<PlaylistEntryId, PlaylistEntry>())
     ctx.setPlaylistOrder([])
-    ctx.setJwts(new Map<PlaylistEntryId, string>())
+    ctx.setJwts(new Map<PlaylistEntryId, PlayingAccess>())
     ctx.setUid2ready(new Map<UserId, UserReadyState>())
     ctx.setActiveVideoId(null)
     ctx.setMpvRunning(false)
     ctx.setUid2audioSub(new Map<UserId, UserAudioSubtitles>())
+}
+
+export function roomLayoutItems(layout: RoomLayout): Array<RoomLayoutItem> {
+    const items: Array<RoomLayoutItem> = layout.room_order.map((rid) => ({category: false, id: rid}))
+    for (const category of layout.categories) {
+        items.push({category: true, id: category.id})
+        for (const rid of category.room_order)
+            items.push({category: false, id: rid})
+    }
+    return items
+}
+
+function roomLayoutFromItems(items: Array<RoomLayoutItem>): RoomLayout {
+    const layout: RoomLayout = {room_order: [], categories: []}
+    for (const item of items) {
+        if (item.category)
+            layout.categories.push({id: item.id, room_order: []})
+        else if (layout.categories.length === 0)
+            layout.room_order.push(item.id)
+        else
+            layout.categories[layout.categories.length - 1].room_order.push(item.id)
+    }
+    return layout
+}
+
+/**
+ * Moving a room puts it under the nearest category header above it,
+ * moving a header moves the whole category together with its rooms.
+ */
+export function moveRoomLayoutItem(layout: RoomLayout, oldIndex: number, newIndex: number): RoomLayout {
+    const items = roomLayoutItems(layout)
+    const moved = items[oldIndex]
+    if (!moved.category) {
+        const [item] = items.splice(oldIndex, 1)
+        items.splice(newIndex, 0, item)
+        return roomLayoutFromItems(items)
+    }
+
+    const headers = items.filter(x => x.category)
+    const [header] = items.splice(oldIndex, 1)
+    items.splice(newIndex, 0, header)
+    const newCategoryIdx = items.filter(x => x.category).findIndex(x => x.id === moved.id)
+    const categories = layout.categories.filter(x => x.id !== moved.id)
+    categories.splice(newCategoryIdx, 0, layout.categories[headers.findIndex(x => x.id === moved.id)])
+    return {room_order: layout.room_order, categories: categories}
+}
+
+export function roomLayoutOf(roomOrder: Array<number>, categories: Array<RoomCategory>): RoomLayout {
+    return {
+        room_order: roomOrder,
+        categories: categories.map((x) => ({id: x.id, room_order: x.room_order}))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,59 @@
+import {UploadAuth, UploadError, UploadId, UploadStatus} from "@models/upload.ts";
+
+export const UPLOAD_CHUNK_SIZE = 1024 * 1024
+const UPLOAD_OFFSET_HEADER = 'upload-offset'
+
+export function getUploads(auth: UploadAuth): Promise<Array<UploadStatus>> {
+    return uploadReq(auth, '/uploads', {method: 'GET'})
+        .then((resp) => resp.json())
+}
+
+export function createUpload(auth: UploadAuth, file: File): Promise<UploadStatus> {
+    return uploadReq(auth, '/uploads', {
+        method: 'POST',
+        headers: {'Content-Type': 'application/json'},
+        body: JSON.stringify({name: file.name, size: file.size})
+    })
+        .then((resp) => resp.json())
+}
+
+export function deleteUpload(auth: UploadAuth, id: UploadId): Promise<void> {
+    return uploadReq(auth, `/uploads/${id}`, {method: 'DELETE'})
+        .then(() => {})
+}
+
+export async function sendUploadChunks(
+    auth: UploadAuth,
+    upload: UploadStatus,
+    file: File,
+    onProgress: (status: UploadStatus) => void
+): Promise<UploadStatus> {
+    let status = upload
+    while (!status.finished) {
+        const chunk = file.slice(status.offset, status.offset + UPLOAD_CHUNK_SIZE)
+        const resp = await uploadReq(auth, `/uploads/${upload.id}`, {
+            method: 'PATCH',
+            headers: {[UPLOAD_OFFSET_HEADER]: status.offset.toString()},
+            body: chunk
+        })
+        status = await resp.json()
+        onProgress(status)
+    }
+    return status
+}
+
+async function uploadReq(auth: UploadAuth, path: string, init: RequestInit): Promise<Response> {
+    const resp = await fetch(auth.homeSrv + path, {
+        ...init,
+        headers: {
+            ...init.headers,
+            'Authorization': `Bearer ${auth.jwt}`,
+            'hwid-hash': auth.hwidHash
+        }
+    })
+    if (!resp.ok) {
+        const err = await resp.json().catch(() => null)
+        throw new UploadError(err?.desc ?? null)
+    }
+    return resp
+}
\ No newline at end of file
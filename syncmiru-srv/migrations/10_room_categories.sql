@@ -0,0 +1,7 @@
+CREATE TABLE "room_category" (
+                           "id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                           "name" varchar(32) UNIQUE NOT NULL,
+                           "room_order" integer ARRAY NOT NULL DEFAULT '{}'
+);
+
+ALTER TABLE "settings" ADD COLUMN "category_order" integer ARRAY NOT NULL DEFAULT '{}';
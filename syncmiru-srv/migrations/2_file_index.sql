@@ -0,0 +1,16 @@
+-- creating the extension needs a superuser or, since PostgreSQL 13, a user with the CREATE privilege on the database,
+-- otherwise a superuser has to run CREATE EXTENSION pg_trgm; beforehand
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE TABLE "file_index" (
+                              "id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                              "source" varchar(255) NOT NULL,
+                              "path" text NOT NULL,
+                              "name" text NOT NULL,
+                              "mtime" timestamptz NOT NULL,
+                              "size" bigint,
+                              "indexed_at" timestamptz NOT NULL,
+                              UNIQUE ("source", "path")
+);
+
+CREATE INDEX "file_index_name_trgm_idx" ON "file_index" USING gin ("name" gin_trgm_ops);
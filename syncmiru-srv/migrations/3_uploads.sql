@@ -0,0 +1,12 @@
+CREATE TABLE "upload" (
+                          "id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                          "user_id" integer NOT NULL,
+                          "name" text UNIQUE NOT NULL,
+                          "size" bigint NOT NULL,
+                          "finished" boolean NOT NULL DEFAULT false,
+                          "created_at" timestamptz NOT NULL DEFAULT (now())
+);
+
+ALTER TABLE "upload" ADD FOREIGN KEY ("user_id") REFERENCES "users" ("id") on delete cascade;
+
+CREATE INDEX "upload_user_id_idx" ON "upload" ("user_id");
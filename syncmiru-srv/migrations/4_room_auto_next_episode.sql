@@ -0,0 +1 @@
+ALTER TABLE "room" ADD COLUMN "auto_next_episode" boolean NOT NULL DEFAULT false;
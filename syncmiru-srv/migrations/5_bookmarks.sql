@@ -0,0 +1,14 @@
+CREATE TABLE "bookmark" (
+                            "id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                            "room_id" integer NOT NULL,
+                            "media" text NOT NULL,
+                            "timestamp" double precision NOT NULL,
+                            "label" varchar(64) NOT NULL,
+                            "user_id" integer,
+                            "created_at" timestamptz NOT NULL DEFAULT (now())
+);
+
+ALTER TABLE "bookmark" ADD FOREIGN KEY ("room_id") REFERENCES "room" ("id") on delete cascade;
+ALTER TABLE "bookmark" ADD FOREIGN KEY ("user_id") REFERENCES "users" ("id") on delete set null;
+
+CREATE INDEX "bookmark_room_id_media_idx" ON "bookmark" ("room_id", "media");
@@ -0,0 +1,12 @@
+CREATE TABLE "comment" (
+                           "id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                           "media" text NOT NULL,
+                           "timestamp" double precision NOT NULL,
+                           "content" varchar(200) NOT NULL,
+                           "user_id" integer,
+                           "created_at" timestamptz NOT NULL DEFAULT (now())
+);
+
+ALTER TABLE "comment" ADD FOREIGN KEY ("user_id") REFERENCES "users" ("id") on delete set null;
+
+CREATE INDEX "comment_media_timestamp_idx" ON "comment" ("media", "timestamp");
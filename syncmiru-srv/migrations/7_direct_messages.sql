@@ -0,0 +1,24 @@
+CREATE TABLE "direct_message" (
+                           "id" INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                           "sender_id" integer NOT NULL,
+                           "recipient_id" integer NOT NULL,
+                           "content" varchar(1000) NOT NULL,
+                           "created_at" timestamptz NOT NULL DEFAULT (now()),
+                           "read_at" timestamptz
+);
+
+CREATE TABLE "user_block" (
+                           "user_id" integer NOT NULL,
+                           "blocked_id" integer NOT NULL,
+                           "created_at" timestamptz NOT NULL DEFAULT (now()),
+                           PRIMARY KEY ("user_id", "blocked_id")
+);
+
+ALTER TABLE "direct_message" ADD FOREIGN KEY ("sender_id") REFERENCES "users" ("id") on delete cascade;
+ALTER TABLE "direct_message" ADD FOREIGN KEY ("recipient_id") REFERENCES "users" ("id") on delete cascade;
+
+ALTER TABLE "user_block" ADD FOREIGN KEY ("user_id") REFERENCES "users" ("id") on delete cascade;
+ALTER TABLE "user_block" ADD FOREIGN KEY ("blocked_id") REFERENCES "users" ("id") on delete cascade;
+
+CREATE INDEX "direct_message_sender_id_recipient_id_idx" ON "direct_message" ("sender_id", "recipient_id");
+CREATE INDEX "direct_message_recipient_id_read_at_idx" ON "direct_message" ("recipient_id", "read_at");
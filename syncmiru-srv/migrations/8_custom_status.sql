@@ -0,0 +1 @@
+ALTER TABLE "users" ADD COLUMN "custom_status" varchar(64);
@@ -0,0 +1,3 @@
+ALTER TABLE "room" ADD COLUMN "description" varchar(500);
+ALTER TABLE "room" ADD COLUMN "topic" varchar(128);
+ALTER TABLE "room" ADD COLUMN "icon" bytea;
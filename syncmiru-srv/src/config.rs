@@ -3,14 +3,17 @@ use std::fs;
 use std::fs::File;
 use std::io::{Read};
 use std::path::{Path, PathBuf};
-use anyhow::{Context};
+use anyhow::{anyhow, Context};
 use indexmap::IndexMap;
 use josekit::jws::{JwsSigner, JwsVerifier};
 use lettre::transport::smtp::authentication::Credentials;
 use log::{debug, info, warn};
+use rusty_s3::{Bucket, Credentials as S3Credentials, UrlStyle};
+use url::Url;
 use yaml_rust2::{Yaml, YamlLoader};
 use crate::config::KeyAlg::{ES256, ES512, RS256, RS512};
 use crate::config::LogOutput::{StdErr, Stdout};
+use crate::constants;
 use crate::error::SyncmiruError;
 use crate::result::Result;
 
@@ -28,11 +31,20 @@ pub fn read(config_file: impl AsRef<Path> + Copy) -> Result<Config> {
     let db = DbConfig::from(&doc["db"])?;
     let email = EmailConf::from(&doc["email"])?;
     let login_jwt = LoginJwt::from(&doc["login_jwt"])?;
-    let sources = Source::from(&doc["sources"])?;
+    let mut sources = Source::from(&doc["sources"])?;
     let extensions = Extensions::from(&doc["extensions"])?;
+    let admins = Admins::from(&doc["admins"])?;
+    let indexer = IndexerConfig::from(&doc["indexer"])?;
+    let uploads = UploadsConfig::from(&doc["uploads"])?;
+    if let Some(u) = &uploads {
+        if sources.contains_key(&u.source_name) {
+            return Err(SyncmiruError::from(anyhow!("source_name inside uploads section collides with a configured source")))
+        }
+        sources.insert(u.source_name.clone(), u.source(&srv));
+    }
     info!("{} parsed", cf_print);
     Ok(
-        Config { srv, reg_pub, db, log, email, login_jwt, sources, extensions }
+        Config { srv, reg_pub, db, log, email, login_jwt, sources, extensions, admins, indexer, uploads }
     )
 }
 
@@ -45,7 +57,10 @@ pub struct Config {
     pub email: EmailConf,
     pub login_jwt: LoginJwt,
     pub sources: Sources,
-    pub extensions: Extensions
+    pub extensions: Extensions,
+    pub admins: Admins,
+    pub indexer: IndexerConfig,
+    pub uploads: Option<UploadsConfig>
 }
 
 #[derive(Debug, Clone)]
@@ -386,11 +401,9 @@ pub type Sources = IndexMap<String, Source>;
 
 #[derive(Debug, Clone)]
 pub struct Source {
-    pub list_root_url: String,
-    pub srv_jwt: String,
+    pub kind: SourceKind,
     pub client_url: String,
-    pub priv_pem: Vec<u8>,
-    pub alg: KeyAlg
+    pub listing_cache_ttl: u64
 }
 
 impl Source {
@@ -401,36 +414,26 @@ impl Source {
                 let name = k.as_str()
                     .context("invalid source name inside sources section")?
                     .to_string();
-                let list_root_url = v["list_root_url"]
-                    .as_str()
-                    .context("list_rool_url is missing inside source section")?
-                    .to_string();
-                let srv_jwt = v["srv_jwt"]
-                    .as_str()
-                    .context("srv_jwt is missing inside source section")?
-                    .to_string();
-                let client_url = v["client_url"]
-                    .as_str()
-                    .context("client_url is missing inside source section")?
-                    .to_string();
-                let alg_str = v["algorithm"]
-                    .as_str()
-                    .context("algorithm is missing inside source section")?
-                    .to_lowercase();
-                let alg = KeyAlg::from(alg_str.as_ref())
-                    .context("Invalid algorithm inside source section")?;
-                let priv_key_file = PathBuf::from(
-                    v["priv_key_file"]
+                let kind = SourceKind::from(v)?;
+                let client_url = match &kind {
+                    SourceKind::S3 { bucket, .. } => bucket.base_url().to_string(),
+                    _ => v["client_url"]
                         .as_str()
-                        .context("priv_key_file is missing inside source section")?
-                );
-                let priv_pem = parse_key(priv_key_file, KeyType::Private, alg)?;
+                        .context("client_url is missing inside source section")?
+                        .to_string()
+                };
+                let mut listing_cache_ttl = constants::LISTING_CACHE_TTL_DEFAULT;
+                let ttl_yaml = &v["listing_cache_ttl"];
+                if !ttl_yaml.is_badvalue() {
+                    listing_cache_ttl = ttl_yaml
+                        .as_i64()
+                        .filter(|x| *x >= 0)
+                        .context("invalid listing_cache_ttl inside source section")? as u64;
+                }
                 sources.insert(name, Source {
-                    list_root_url,
-                    srv_jwt,
+                    kind,
                     client_url,
-                    priv_pem,
-                    alg
+                    listing_cache_ttl
                 });
             }
         }
@@ -441,7 +444,138 @@ impl Source {
     }
 }
 
-impl JwtSigner for Source {
+#[derive(Debug, Clone)]
+pub enum SourceKind {
+    Autoindex { list_root_url: String, srv_jwt: Option<String>, format: ListingFormat, file_jwt: FileJwt },
+    WebDav { list_root_url: String, username: Option<String>, password: Option<String>, file_jwt: FileJwt },
+    S3 { bucket: Bucket, credentials: S3Credentials, presign_expiration: u64 },
+    Local { root: PathBuf, file_jwt: FileJwt }
+}
+
+impl SourceKind {
+    pub fn from(yaml: &Yaml) -> Result<Self> {
+        let kind_str = yaml["kind"]
+            .as_str()
+            .unwrap_or("autoindex")
+            .to_lowercase();
+        match kind_str.as_str() {
+            "autoindex" => {
+                let list_root_url = Self::parse_list_root_url(yaml)?;
+                let format_str = yaml["format"]
+                    .as_str()
+                    .unwrap_or("openresty");
+                let format = ListingFormat::from(format_str)
+                    .context("Invalid format inside source section")?;
+                let srv_jwt = yaml["srv_jwt"].as_str().map(|x| x.to_string());
+                if format == ListingFormat::Openresty && srv_jwt.is_none() {
+                    return Err(SyncmiruError::from(anyhow!("srv_jwt is missing inside source section")))
+                }
+                let file_jwt = FileJwt::from(yaml)?;
+                Ok(SourceKind::Autoindex { list_root_url, srv_jwt, format, file_jwt })
+            }
+            "webdav" => {
+                let list_root_url = Self::parse_list_root_url(yaml)?;
+                let username = yaml["username"].as_str().map(|x| x.to_string());
+                let password = yaml["password"].as_str().map(|x| x.to_string());
+                if password.is_some() && username.is_none() {
+                    return Err(SyncmiruError::from(anyhow!("password set without username inside source section")))
+                }
+                let file_jwt = FileJwt::from(yaml)?;
+                Ok(SourceKind::WebDav { list_root_url, username, password, file_jwt })
+            }
+            "s3" => {
+                let endpoint_str = yaml["endpoint"]
+                    .as_str()
+                    .context("endpoint is missing inside source section")?;
+                let endpoint = Url::parse(endpoint_str)
+                    .context("Invalid endpoint inside source section")?;
+                let bucket_name = yaml["bucket"]
+                    .as_str()
+                    .context("bucket is missing inside source section")?
+                    .to_string();
+                let region = yaml["region"]
+                    .as_str()
+                    .unwrap_or("us-east-1")
+                    .to_string();
+                let url_style = match yaml["path_style"].as_bool().unwrap_or(true) {
+                    true => UrlStyle::Path,
+                    false => UrlStyle::VirtualHost
+                };
+                let bucket = Bucket::new(endpoint, url_style, bucket_name, region)
+                    .context("Invalid bucket inside source section")?;
+                let access_key = yaml["access_key"]
+                    .as_str()
+                    .context("access_key is missing inside source section")?;
+                let secret_key = yaml["secret_key"]
+                    .as_str()
+                    .context("secret_key is missing inside source section")?;
+                let credentials = S3Credentials::new(access_key, secret_key);
+                let mut presign_expiration = constants::S3_PRESIGN_EXPIRATION_DEFAULT;
+                let presign_expiration_yaml = &yaml["presign_expiration"];
+                if !presign_expiration_yaml.is_badvalue() {
+                    presign_expiration = presign_expiration_yaml
+                        .as_i64()
+                        .filter(|x| *x > 0 && *x <= constants::S3_PRESIGN_EXPIRATION_MAX as i64)
+                        .context("invalid presign_expiration inside source section")? as u64;
+                }
+                Ok(SourceKind::S3 { bucket, credentials, presign_expiration })
+            }
+            _ => Err(SyncmiruError::from(anyhow!("Invalid kind inside source section")))
+        }
+    }
+
+    fn parse_list_root_url(yaml: &Yaml) -> Result<String> {
+        let list_root_url = yaml["list_root_url"]
+            .as_str()
+            .context("list_rool_url is missing inside source section")?
+            .to_string();
+        Ok(list_root_url)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ListingFormat {
+    Openresty,
+    Caddy,
+    Apache
+}
+
+impl ListingFormat {
+    pub fn from(s: &str) -> Option<ListingFormat> {
+        match s.to_lowercase().as_str() {
+            "openresty" => Some(ListingFormat::Openresty),
+            "caddy" => Some(ListingFormat::Caddy),
+            "apache" => Some(ListingFormat::Apache),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileJwt {
+    pub priv_pem: Vec<u8>,
+    pub alg: KeyAlg
+}
+
+impl FileJwt {
+    pub fn from(yaml: &Yaml) -> Result<Self> {
+        let alg_str = yaml["algorithm"]
+            .as_str()
+            .context("algorithm is missing inside source section")?
+            .to_lowercase();
+        let alg = KeyAlg::from(alg_str.as_ref())
+            .context("Invalid algorithm inside source section")?;
+        let priv_key_file = PathBuf::from(
+            yaml["priv_key_file"]
+                .as_str()
+                .context("priv_key_file is missing inside source section")?
+        );
+        let priv_pem = parse_key(priv_key_file, KeyType::Private, alg)?;
+        Ok(Self { priv_pem, alg })
+    }
+}
+
+impl JwtSigner for FileJwt {
     fn jwt_signer(&self) -> Result<Box<dyn JwsSigner>> {
         create_jwt_signer(&self.alg, &self.priv_pem)
     }
@@ -449,7 +583,8 @@ impl JwtSigner for Source {
 
 #[derive(Debug, Clone)]
 pub struct Extensions {
-    pub videos: Option<HashSet<String>>
+    pub videos: Option<HashSet<String>>,
+    pub subtitles: HashSet<String>
 }
 
 impl Extensions {
@@ -458,7 +593,9 @@ impl Extensions {
         if videos.is_none() {
             warn!("File extensions for video files not limited")
         }
-        Ok(Self { videos })
+        let subtitles = Self::parse_yaml_opt_string_arr(&yaml["subtitles"])?
+            .unwrap_or_else(|| constants::SUBTITLE_EXTENSIONS_DEFAULT.iter().map(|x| x.to_string()).collect());
+        Ok(Self { videos, subtitles })
     }
 
     fn parse_yaml_opt_string_arr(yaml: &Yaml) -> Result<Option<HashSet<String>>> {
@@ -483,6 +620,124 @@ impl Extensions {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct Admins {
+    pub usernames: HashSet<String>
+}
+
+impl Admins {
+    pub fn from(yaml: &Yaml) -> Result<Admins> {
+        let mut usernames = HashSet::<String>::new();
+        if !yaml.is_badvalue() {
+            let array = yaml
+                .as_vec()
+                .context("admins must be an array of usernames")?;
+            for item in array {
+                let username = item.as_str()
+                    .context("invalid item inside admins array")?;
+                usernames.insert(username.to_string());
+            }
+        }
+        if usernames.is_empty() {
+            warn!("No admins configured, administrative actions are disabled")
+        }
+        Ok(Self { usernames })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexerConfig {
+    pub enabled: bool,
+    pub refresh_interval: u64
+}
+
+impl IndexerConfig {
+    pub fn from(yaml: &Yaml) -> Result<Self> {
+        if yaml.is_badvalue() {
+            warn!("indexer section is missing, file search is disabled");
+            return Ok(Self { enabled: false, refresh_interval: constants::INDEXER_REFRESH_INTERVAL_DEFAULT })
+        }
+        let enabled = yaml["enabled"]
+            .as_bool()
+            .context("enabled is missing in indexer section")?;
+        let mut refresh_interval = constants::INDEXER_REFRESH_INTERVAL_DEFAULT;
+        let refresh_interval_yaml = &yaml["refresh_interval"];
+        if !refresh_interval_yaml.is_badvalue() {
+            refresh_interval = refresh_interval_yaml
+                .as_i64()
+                .filter(|x| *x > 0)
+                .context("invalid refresh_interval in indexer section")? as u64;
+        }
+        Ok(Self { enabled, refresh_interval })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadsConfig {
+    pub source_name: String,
+    pub dir: PathBuf,
+    pub user_quota: u64,
+    pub global_quota: u64,
+    pub file_jwt: FileJwt,
+    pub file_jwt_pub_pem: Vec<u8>
+}
+
+impl UploadsConfig {
+    pub fn from(yaml: &Yaml) -> Result<Option<Self>> {
+        if yaml.is_badvalue() {
+            return Ok(None)
+        }
+        let enabled = yaml["enabled"]
+            .as_bool()
+            .context("enabled is missing in uploads section")?;
+        if !enabled {
+            return Ok(None)
+        }
+        let source_name = yaml["source_name"]
+            .as_str()
+            .unwrap_or(constants::UPLOADS_SOURCE_NAME_DEFAULT)
+            .to_string();
+        let dir = PathBuf::from(
+            yaml["dir"]
+                .as_str()
+                .context("dir is missing in uploads section")?
+        );
+        fs::create_dir_all(dir.join(constants::UPLOADS_PARTIAL_DIR))?;
+
+        let user_quota = yaml["user_quota"]
+            .as_i64()
+            .filter(|x| *x > 0)
+            .context("invalid user_quota in uploads section")? as u64;
+        let global_quota = yaml["global_quota"]
+            .as_i64()
+            .filter(|x| *x > 0)
+            .context("invalid global_quota in uploads section")? as u64;
+
+        let file_jwt = FileJwt::from(yaml)?;
+        let pub_key_file = PathBuf::from(
+            yaml["pub_key_file"]
+                .as_str()
+                .context("pub_key_file is missing in uploads section")?
+        );
+        let file_jwt_pub_pem = parse_key(pub_key_file, KeyType::Public, file_jwt.alg)?;
+        Ok(Some(Self { source_name, dir, user_quota, global_quota, file_jwt, file_jwt_pub_pem }))
+    }
+
+    pub fn source(&self, srv: &SrvConfig) -> Source {
+        Source {
+            kind: SourceKind::Local { root: self.dir.clone(), file_jwt: self.file_jwt.clone() },
+            client_url: format!("{}/uploads/file", srv.url.trim_end_matches('/')),
+            listing_cache_ttl: 0
+        }
+    }
+}
+
+impl JwtVerifier for UploadsConfig {
+    fn jwt_verifier(&self) -> Result<Box<dyn JwsVerifier>> {
+        create_jwt_verifier(&self.file_jwt.alg, &self.file_jwt_pub_pem)
+    }
+}
+
 #[derive(PartialEq)]
 enum KeyType {
     Private,
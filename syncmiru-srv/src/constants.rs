@@ -4,4 +4,57 @@ pub const APP_NAME: &'static str = "syncmiru-srv";
 pub const SOCKETIO_ACK_TIMEOUT: Duration = Duration::from_secs(5);
 pub const HTTP_TIMEOUT: u64 = 5;
 pub const TIMESTAMP_TICK_MAX_OLD_MS: u128 = 2000;
-pub const DESYNC_TIMER_TICK_MS: u64 = 10;
\ No newline at end of file
+pub const DESYNC_TIMER_TICK_MS: u64 = 10;
+pub const ADD_DIRECTORY_MAX_FILES: usize = 1000;
+pub const ADD_DIRECTORY_MAX_DIRS: usize = 200;
+pub const LISTING_CACHE_TTL_DEFAULT: u64 = 30;
+pub const LISTING_CACHE_MAX_ENTRIES: usize = 10000;
+pub const INDEXER_REFRESH_INTERVAL_DEFAULT: u64 = 3600;
+pub const INDEXER_MAX_FILES: usize = 500000;
+pub const INDEXER_MAX_DIRS: usize = 100000;
+pub const INDEXER_MAX_DEPTH: usize = 32;
+pub const INDEXER_BATCH_SIZE: usize = 1000;
+pub const SEARCH_FILES_MAX_RESULTS: i64 = 50;
+pub const S3_PRESIGN_EXPIRATION_DEFAULT: u64 = 12 * 3600;
+pub const S3_PRESIGN_EXPIRATION_MAX: u64 = 7 * 24 * 3600;
+pub const S3_LIST_MAX_PAGES: usize = 100;
+pub const SIDECAR_SUBTITLES_MAX: usize = 20;
+pub const SUBTITLE_EXTENSIONS_DEFAULT: [&str; 5] = ["ass", "srt", "ssa", "sub", "vtt"];
+pub const POSTER_EXTENSIONS: [&str; 2] = ["jpg", "png"];
+pub const NFO_MAX_SIZE: usize = 256 * 1024;
+pub const METADATA_READ_CONCURRENCY: usize = 8;
+pub const POSTER_MAX_SIZE: usize = 5 * 1024 * 1024;
+pub const PROBE_HEAD_SIZE: usize = 1024 * 1024;
+pub const PROBE_MOOV_MAX_SIZE: usize = 16 * 1024 * 1024;
+pub const PROBE_MP4_MAX_BOXES: usize = 16;
+pub const PROBE_CACHE_MAX_ENTRIES: usize = 10000;
+pub const SUBTITLE_UPLOAD_FORMATS: [&str; 4] = ["ass", "srt", "ssa", "vtt"];
+pub const SUBTITLE_UPLOAD_MAX_SIZE: usize = 5 * 1024 * 1024;
+pub const SUBTITLE_UPLOAD_CHUNK_MAX_SIZE: usize = 256 * 1024;
+pub const SUBTITLE_UPLOADS_MAX_PER_ENTRY: usize = 20;
+pub const SUBTITLE_UPLOADS_MAX_PENDING: usize = 2;
+pub const UPLOADS_SOURCE_NAME_DEFAULT: &str = "uploads";
+pub const UPLOADS_PARTIAL_DIR: &str = ".partial";
+pub const UPLOADS_CHUNK_MAX_SIZE: usize = 4 * 1024 * 1024;
+pub const HWID_HASH_HEADER: &str = "hwid-hash";
+pub const UPLOAD_OFFSET_HEADER: &str = "upload-offset";
+pub const COMMENTS_MAX_LOADED: i64 = 5000;
+pub const COMMENTS_WINDOW: f64 = 10f64 * 60f64;
+pub const COMMENTS_RATE_MAX: usize = 5;
+pub const COMMENTS_RATE_PER: Duration = Duration::from_secs(10);
+pub const REACTIONS: [&str; 8] = ["👍", "😂", "😮", "😢", "😡", "❤️", "🔥", "👏"];
+pub const REACTIONS_RATE_MAX: usize = 5;
+pub const REACTIONS_RATE_PER: Duration = Duration::from_secs(10);
+pub const CHAT_MESSAGE_MAX_LENGTH: usize = 500;
+pub const CHAT_RATE_MAX: usize = 10;
+pub const CHAT_RATE_PER: Duration = Duration::from_secs(10);
+pub const POLL_OPTIONS_MAX: usize = 10;
+pub const POLL_DURATION_MIN: u64 = 10;
+pub const POLL_DURATION_MAX: u64 = 60 * 60;
+pub const DIRECT_MESSAGES_PAGE_SIZE: i64 = 50;
+pub const DIRECT_MESSAGES_RATE_MAX: usize = 10;
+pub const DIRECT_MESSAGES_RATE_PER: Duration = Duration::from_secs(10);
+pub const PRESENCE_BROADCAST_INTERVAL: Duration = Duration::from_secs(1);
+pub const ROOM_DESCRIPTION_MAX_LENGTH: usize = 500;
+pub const ROOM_TOPIC_MAX_LENGTH: usize = 128;
+pub const ROOM_CATEGORY_NAME_MAX_LENGTH: usize = 32;
\ No newline at end of file
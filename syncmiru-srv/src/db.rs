@@ -1,8 +1,9 @@
-use log::{debug, info};
+use log::{debug, error, info};
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use crate::config::DbConfig;
 use crate::result::Result;
 use sqlx::PgPool;
+use sqlx::migrate::MigrateError;
 use crate::constants;
 
 pub async fn create_connection_pool(db_config: &DbConfig) -> Result<PgPool> {
@@ -25,7 +26,20 @@ pub async fn create_connection_pool(db_config: &DbConfig) -> Result<PgPool> {
 
 pub async fn run_migrations(db: &PgPool) -> Result<()> {
     debug!("Migrations starting");
-    sqlx::migrate!().run(db).await?;
+    if let Err(e) = sqlx::migrate!().run(db).await {
+        if let MigrateError::ExecuteMigration(sqlx::Error::Database(db_err), version) = &e {
+            // 42501 is insufficient_privilege
+            if db_err.code().as_deref() == Some("42501") {
+                error!(
+                    "Migration {} failed, the DB user lacks a privilege: {}. \
+                    The pg_trgm extension has to be created by a superuser: CREATE EXTENSION pg_trgm;",
+                    version,
+                    db_err.message()
+                );
+            }
+        }
+        return Err(e.into())
+    }
     info!("Migrations finished");
     Ok(())
 }
\ No newline at end of file
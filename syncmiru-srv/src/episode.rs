@@ -0,0 +1,138 @@
+use crate::models::file::Episode;
+use crate::natsort;
+
+pub fn parse(name: &str) -> Option<Episode> {
+    let stem = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name);
+    let chars: Vec<char> = stem.chars().collect();
+    parse_sxxexx(&chars)
+        .or_else(|| parse_nxnn(&chars))
+        .or_else(|| parse_dash_number(&chars))
+}
+
+/// Returns the file among `names` holding the episode that follows `current`,
+/// the next episode of the same season is preferred over the first episode of the next season
+pub fn next<'a>(current: &str, names: &[&'a str]) -> Option<&'a str> {
+    let cur = parse(current)?;
+    names
+        .iter()
+        .filter_map(|x| parse(x).map(|ep| (ep, *x)))
+        .filter(|(ep, _)| match (cur.season, ep.season) {
+            (Some(s), Some(es)) => (es == s && ep.episode > cur.episode) || es == s + 1,
+            (None, None) => ep.episode > cur.episode,
+            _ => false
+        })
+        .min_by(|(a, a_name), (b, b_name)| {
+            a.season.cmp(&b.season)
+                .then(a.episode.cmp(&b.episode))
+                .then_with(|| natsort::natural_cmp(a_name, b_name))
+        })
+        .map(|(_, name)| name)
+}
+
+// S01E02, s1e2
+fn parse_sxxexx(chars: &[char]) -> Option<Episode> {
+    for i in 0..chars.len() {
+        if !chars[i].eq_ignore_ascii_case(&'s') || (i > 0 && chars[i - 1].is_alphanumeric()) {
+            continue
+        }
+        let Some((season, season_end)) = take_number(chars, i + 1, 2) else {
+            continue
+        };
+        if season_end >= chars.len() || !chars[season_end].eq_ignore_ascii_case(&'e') {
+            continue
+        }
+        if let Some((episode, episode_end)) = take_number(chars, season_end + 1, 3) {
+            if episode_end == chars.len() || !chars[episode_end].is_ascii_digit() {
+                return Some(Episode { season: Some(season), episode })
+            }
+        }
+    }
+    None
+}
+
+// 1x02
+fn parse_nxnn(chars: &[char]) -> Option<Episode> {
+    for i in 0..chars.len() {
+        if !chars[i].eq_ignore_ascii_case(&'x') || i == 0 {
+            continue
+        }
+        let mut start = i;
+        while start > 0 && chars[start - 1].is_ascii_digit() {
+            start -= 1;
+        }
+        if start == i || i - start > 2 || (start > 0 && chars[start - 1].is_alphanumeric()) {
+            continue
+        }
+        let season = chars[start..i].iter().collect::<String>().parse::<u32>().ok()?;
+        if let Some((episode, episode_end)) = take_number(chars, i + 1, 3) {
+            if episode_end - i > 2 && (episode_end == chars.len() || !chars[episode_end].is_alphanumeric()) {
+                return Some(Episode { season: Some(season), episode })
+            }
+        }
+    }
+    None
+}
+
+// Show - 02 [1080p]
+fn parse_dash_number(chars: &[char]) -> Option<Episode> {
+    let mut found: Option<Episode> = None;
+    for i in 0..chars.len().saturating_sub(2) {
+        if chars[i] != ' ' || chars[i + 1] != '-' || chars[i + 2] != ' ' {
+            continue
+        }
+        if let Some((episode, episode_end)) = take_number(chars, i + 3, 4) {
+            let boundary = episode_end == chars.len()
+                || matches!(chars[episode_end], ' ' | '.' | '[' | '(' | 'v' | 'V');
+            if boundary {
+                found = Some(Episode { season: None, episode });
+            }
+        }
+    }
+    found
+}
+
+fn take_number(chars: &[char], start: usize, max_digits: usize) -> Option<(u32, usize)> {
+    let mut end = start;
+    while end < chars.len() && chars[end].is_ascii_digit() && end - start < max_digits {
+        end += 1;
+    }
+    if end == start {
+        return None
+    }
+    let number = chars[start..end].iter().collect::<String>().parse::<u32>().ok()?;
+    Some((number, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::file::Episode;
+    use super::{next, parse};
+
+    fn ep(season: Option<u32>, episode: u32) -> Option<Episode> {
+        Some(Episode { season, episode })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("Show.S01E02.1080p.mkv"), ep(Some(1), 2));
+        assert_eq!(parse("show s2e10.mp4"), ep(Some(2), 10));
+        assert_eq!(parse("Show 1x02 Title.mkv"), ep(Some(1), 2));
+        assert_eq!(parse("[Group] Show - 02 [1080p].mkv"), ep(None, 2));
+        assert_eq!(parse("Show - Part 2 - 13v2.mkv"), ep(None, 13));
+        assert_eq!(parse("Movie 1920x1080.mkv"), None);
+        assert_eq!(parse("Movie (2001).mkv"), None);
+        assert_eq!(parse("Show.mkv"), None);
+    }
+
+    #[test]
+    fn test_next() {
+        let names = ["Show S01E01.mkv", "Show S01E02.mkv", "Show S01E03.mkv", "Show S02E01.mkv", "Extras.mkv"];
+        assert_eq!(next("Show S01E01.mkv", &names), Some("Show S01E02.mkv"));
+        assert_eq!(next("Show S01E03.mkv", &names), Some("Show S02E01.mkv"));
+        assert_eq!(next("Show S02E01.mkv", &names), None);
+        assert_eq!(next("Extras.mkv", &names), None);
+
+        let names = ["Show - 01.mkv", "Show - 03.mkv", "Show - 02.mkv"];
+        assert_eq!(next("Show - 01.mkv", &names), Some("Show - 02.mkv"));
+    }
+}
@@ -72,6 +72,9 @@ pub enum SyncmiruError {
     #[error("Serde JSON error")]
     SerdeJsonError(#[from] serde_json::error::Error),
 
+    #[error("XML error")]
+    XmlError(#[from] quick_xml::Error),
+
     #[error("Parsing CLI args failed {0}")]
     CliParseFailed(String),
 
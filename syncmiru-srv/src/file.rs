@@ -1,25 +1,63 @@
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::io::SeekFrom;
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, SystemTime};
 use anyhow::anyhow;
+use log::warn;
 use josekit::jws::JwsHeader;
 use josekit::jwt::JwtPayload;
 use reqwest::Client;
-use crate::{constants, Result};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use crate::{constants, episode, natsort, Result};
 use crate::error::SyncmiruError;
 use urlencoding::encode;
-use crate::config::{JwtSigner, Source};
-use crate::models::file::{FileInfo, FileType};
+use crate::config::{FileJwt, JwtSigner, Source, SourceKind};
+use crate::file::parsers::ListingParser;
+use crate::models::file::{EntryMetadata, FileAccess, FileInfo, FileSortBy, FileType};
+
+pub mod local;
+mod nfo;
+mod parsers;
+mod s3;
+mod webdav;
 
 pub async fn list(
+    source: &Source,
+    path: &str
+) -> Result<Vec<FileInfo>> {
+    match &source.kind {
+        SourceKind::Autoindex { list_root_url, srv_jwt, format, .. } => list_autoindex(
+            list_root_url,
+            srv_jwt.as_deref(),
+            &*format.parser(),
+            path
+        ).await,
+        SourceKind::WebDav { list_root_url, username, password, .. } => webdav::list(
+            list_root_url,
+            username.as_deref(),
+            password.as_deref(),
+            path
+        ).await,
+        SourceKind::S3 { bucket, credentials, .. } => s3::list(bucket, credentials, path).await,
+        SourceKind::Local { root, .. } => local::list(root, path).await
+    }
+}
+
+async fn list_autoindex(
     root_url: &str,
-    jwt: &str,
+    jwt: Option<&str>,
+    parser: &dyn ListingParser,
     path: &str
 ) -> Result<Vec<FileInfo>> {
-    let encoded_path = encode(path).into_owned();
-    let client = Client::new()
-        .get(&format!("{}{}", root_url, encoded_path))
+    let mut client = Client::new()
+        .get(parser.url(root_url, path))
         .timeout(Duration::from_secs(constants::HTTP_TIMEOUT))
-        .header(reqwest::header::AUTHORIZATION, format!("Bearer {}", jwt));
+        .header(reqwest::header::ACCEPT, parser.accept());
+    if let Some(j) = jwt {
+        client = client.header(reqwest::header::AUTHORIZATION, format!("Bearer {}", j));
+    }
 
     let response = client
         .send()
@@ -30,18 +68,70 @@ pub async fn list(
         return Err(SyncmiruError::from(anyhow!(response.text().await?)))
     }
 
-    let files_info = response.json::<Vec<FileInfo>>().await?;
-    Ok(files_info)
+    let body = response.text().await?;
+    parser.parse(&body)
+}
+
+pub struct ListingCache {
+    entries: RwLock<HashMap<(String, String), CachedListing>>
+}
+
+struct CachedListing {
+    fetched_at: Instant,
+    files: Vec<FileInfo>
+}
+
+impl ListingCache {
+    pub fn new() -> Self {
+        Self { entries: RwLock::new(HashMap::new()) }
+    }
+
+    pub async fn list(
+        &self,
+        source_name: &str,
+        source: &Source,
+        path: &str
+    ) -> Result<Vec<FileInfo>> {
+        let ttl = Duration::from_secs(source.listing_cache_ttl);
+        let key = (source_name.to_string(), path.to_string());
+        if !ttl.is_zero() {
+            let entries_rl = self.entries.read().await;
+            if let Some(cached) = entries_rl.get(&key) {
+                if cached.fetched_at.elapsed() < ttl {
+                    return Ok(cached.files.clone())
+                }
+            }
+        }
+
+        let files = list(source, path).await?;
+        if !ttl.is_zero() {
+            let mut entries_wl = self.entries.write().await;
+            if entries_wl.len() >= constants::LISTING_CACHE_MAX_ENTRIES {
+                entries_wl.clear();
+            }
+            entries_wl.insert(key, CachedListing { fetched_at: Instant::now(), files: files.clone() });
+        }
+        Ok(files)
+    }
+
+    pub async fn invalidate(&self, source_name: Option<&str>) {
+        let mut entries_wl = self.entries.write().await;
+        match source_name {
+            Some(name) => entries_wl.retain(|(source, _), _| source != name),
+            None => entries_wl.clear()
+        }
+    }
 }
 
 pub async fn f_exists(
-    root_url: &str,
-    jwt: &str,
+    cache: &ListingCache,
+    source_name: &str,
+    source: &Source,
     path: &str,
     allowed_extensions: &Option<HashSet<String>>
 ) -> Result<bool> {
     let (p, f) = split_on_last_occurrence(path, '/').unwrap();
-    let files_r = list(root_url, jwt, p).await;
+    let files_r = cache.list(source_name, source, p).await;
     if files_r.is_err() {
         return Ok(false)
     }
@@ -56,8 +146,322 @@ pub async fn f_exists(
     Ok(false)
 }
 
-pub async fn gen_access_jwt(
+pub struct VideoFile {
+    pub path: String,
+    pub subtitles: Vec<String>,
+    pub nfo: Option<String>,
+    pub poster: Option<String>
+}
+
+pub async fn find_sidecars(
+    cache: &ListingCache,
+    source_name: &str,
+    source: &Source,
+    path: &str,
+    subtitle_extensions: &HashSet<String>
+) -> Result<VideoFile> {
+    let (dir, name) = split_on_last_occurrence(path, '/').unwrap();
+    let files = cache.list(source_name, source, dir).await?;
+    Ok(video_file(dir, name, &files, subtitle_extensions))
+}
+
+pub async fn find_next_episode(
+    cache: &ListingCache,
+    source_name: &str,
+    source: &Source,
+    path: &str,
+    allowed_extensions: &Option<HashSet<String>>,
+    subtitle_extensions: &HashSet<String>
+) -> Result<Option<VideoFile>> {
+    let (dir, name) = split_on_last_occurrence(path, '/').unwrap();
+    let files = cache.list(source_name, source, dir).await?;
+    let videos: Vec<&str> = files
+        .iter()
+        .filter(|x| x.file_type == FileType::File && extension_allowed(&x.name, allowed_extensions))
+        .map(|x| x.name.as_str())
+        .collect();
+    Ok(episode::next(name, &videos).map(|x| video_file(dir, x, &files, subtitle_extensions)))
+}
+
+pub async fn list_dir_files(
+    cache: &ListingCache,
+    source_name: &str,
     source: &Source,
+    path: &str,
+    recursive: bool,
+    allowed_extensions: &Option<HashSet<String>>,
+    subtitle_extensions: &HashSet<String>
+) -> Result<Vec<VideoFile>> {
+    let mut files_found = Vec::<VideoFile>::new();
+    let mut dirs_to_visit = vec![path.to_string()];
+    let mut dirs_visited = 0;
+    while let Some(dir) = dirs_to_visit.pop() {
+        dirs_visited += 1;
+        if dirs_visited > constants::ADD_DIRECTORY_MAX_DIRS {
+            return Err(SyncmiruError::from(anyhow!("too many directories")))
+        }
+        let mut files = cache.list(source_name, source, &dir).await?;
+        files.sort_by(|a, b| natsort::natural_cmp(&a.name, &b.name));
+
+        let mut subdirs = Vec::<String>::new();
+        for file in &files {
+            match file.file_type {
+                FileType::File => {
+                    if extension_allowed(&file.name, allowed_extensions) {
+                        files_found.push(video_file(&dir, &file.name, &files, subtitle_extensions));
+                    }
+                }
+                FileType::Directory => {
+                    if recursive {
+                        subdirs.push(join_path(&dir, &file.name));
+                    }
+                }
+            }
+        }
+        if files_found.len() > constants::ADD_DIRECTORY_MAX_FILES {
+            return Err(SyncmiruError::from(anyhow!("too many files")))
+        }
+        dirs_to_visit.extend(subdirs.into_iter().rev());
+    }
+    Ok(files_found)
+}
+
+fn video_file(
+    dir: &str,
+    video_name: &str,
+    files: &[FileInfo],
+    subtitle_extensions: &HashSet<String>
+) -> VideoFile {
+    VideoFile {
+        path: join_path(dir, video_name),
+        subtitles: sidecar_subtitles(video_name, files, subtitle_extensions)
+            .into_iter()
+            .map(|x| join_path(dir, &x))
+            .collect(),
+        nfo: sidecar_nfo(video_name, files).map(|x| join_path(dir, &x)),
+        poster: sidecar_poster(video_name, files).map(|x| join_path(dir, &x))
+    }
+}
+
+fn sidecar_subtitles(
+    video_name: &str,
+    files: &[FileInfo],
+    subtitle_extensions: &HashSet<String>
+) -> Vec<String> {
+    let video_stem = extract_stem(video_name);
+    let mut subtitles: Vec<String> = files
+        .iter()
+        .filter(|x| x.file_type == FileType::File && subtitle_extensions.contains(extract_extension(&x.name)))
+        .filter(|x| {
+            let stem = extract_stem(&x.name);
+            stem == video_stem || stem.strip_prefix(video_stem).is_some_and(|lang| lang.starts_with('.'))
+        })
+        .map(|x| x.name.clone())
+        .collect();
+    subtitles.sort_by(|a, b| natsort::natural_cmp(a, b));
+    subtitles.truncate(constants::SIDECAR_SUBTITLES_MAX);
+    subtitles
+}
+
+fn sidecar_nfo(video_name: &str, files: &[FileInfo]) -> Option<String> {
+    let video_stem = extract_stem(video_name);
+    let candidates = [format!("{}.nfo", video_stem), "movie.nfo".to_string()];
+    find_first_file(&candidates, files)
+}
+
+fn sidecar_poster(video_name: &str, files: &[FileInfo]) -> Option<String> {
+    let video_stem = extract_stem(video_name);
+    let mut candidates = Vec::<String>::new();
+    for name in [format!("{}-poster", video_stem), format!("{}-thumb", video_stem), "poster".to_string(), "folder".to_string()] {
+        for ext in constants::POSTER_EXTENSIONS {
+            candidates.push(format!("{}.{}", name, ext));
+        }
+    }
+    find_first_file(&candidates, files)
+}
+
+fn find_first_file(candidates: &[String], files: &[FileInfo]) -> Option<String> {
+    candidates.iter().find_map(|c| files
+        .iter()
+        .find(|x| x.file_type == FileType::File && x.name.eq_ignore_ascii_case(c))
+        .map(|x| x.name.clone())
+    )
+}
+
+pub async fn read_metadata(source: &Source, video: &VideoFile) -> Option<EntryMetadata> {
+    if video.nfo.is_none() && video.poster.is_none() {
+        return None
+    }
+    let mut metadata = EntryMetadata { poster: video.poster.clone(), ..Default::default() };
+    if let Some(nfo_path) = &video.nfo {
+        let info_r = read(source, nfo_path, constants::NFO_MAX_SIZE)
+            .await
+            .and_then(|x| nfo::parse(&String::from_utf8_lossy(&x)));
+        match info_r {
+            Ok(info) => {
+                metadata.title = info.title;
+                metadata.year = info.year;
+                metadata.plot = info.plot;
+            }
+            Err(e) => warn!("Could not read {}: {}", nfo_path, e)
+        }
+    }
+    Some(metadata)
+}
+
+pub async fn read(
+    source: &Source,
+    path: &str,
+    max_size: usize
+) -> Result<Vec<u8>> {
+    if let SourceKind::Local { root, .. } = &source.kind {
+        let file = local::resolve(root, path)?;
+        if tokio::fs::metadata(&file).await?.len() > max_size as u64 {
+            return Err(SyncmiruError::from(anyhow!("file too large")))
+        }
+        return Ok(tokio::fs::read(file).await?)
+    }
+
+    let mut response = file_request(source, path)
+        .await?
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(SyncmiruError::from(anyhow!("status {} for {}", response.status(), path)))
+    }
+    if response.content_length().is_some_and(|x| x > max_size as u64) {
+        return Err(SyncmiruError::from(anyhow!("file too large")))
+    }
+    let mut data = Vec::<u8>::new();
+    while let Some(chunk) = response.chunk().await? {
+        if data.len() + chunk.len() > max_size {
+            return Err(SyncmiruError::from(anyhow!("file too large")))
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+pub async fn read_range(
+    source: &Source,
+    path: &str,
+    start: u64,
+    len: usize
+) -> Result<Vec<u8>> {
+    if len == 0 {
+        return Ok(Vec::new())
+    }
+    if let SourceKind::Local { root, .. } = &source.kind {
+        let mut file = tokio::fs::File::open(local::resolve(root, path)?).await?;
+        file.seek(SeekFrom::Start(start)).await?;
+        let mut data = Vec::<u8>::new();
+        file.take(len as u64).read_to_end(&mut data).await?;
+        return Ok(data)
+    }
+
+    let end = start
+        .checked_add(len as u64 - 1)
+        .ok_or_else(|| SyncmiruError::from(anyhow!("range out of bounds for {}", path)))?;
+    let mut response = file_request(source, path)
+        .await?
+        .header(reqwest::header::RANGE, format!("bytes={}-{}", start, end))
+        .send()
+        .await?;
+    if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(Vec::new())
+    }
+    if !response.status().is_success() {
+        return Err(SyncmiruError::from(anyhow!("status {} for {}", response.status(), path)))
+    }
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT && start > 0 {
+        return Err(SyncmiruError::from(anyhow!("range requests are not supported for {}", path)))
+    }
+    // a server ignoring the range sends the whole file, only its beginning is read then
+    let mut data = Vec::<u8>::new();
+    while let Some(chunk) = response.chunk().await? {
+        data.extend_from_slice(&chunk);
+        if data.len() >= len {
+            data.truncate(len);
+            break
+        }
+    }
+    Ok(data)
+}
+
+async fn file_request(source: &Source, path: &str) -> Result<reqwest::RequestBuilder> {
+    let request = match file_access(source, path).await? {
+        FileAccess::Jwt { jwt } => Client::new()
+            .get(&source.client_url)
+            .header(reqwest::header::AUTHORIZATION, format!("Bearer {}", jwt)),
+        FileAccess::Presigned { url } => Client::new().get(url)
+    };
+    Ok(request.timeout(Duration::from_secs(constants::HTTP_TIMEOUT)))
+}
+
+pub fn sort_filter_page(
+    mut files: Vec<FileInfo>,
+    sort_by: FileSortBy,
+    sort_desc: bool,
+    filter: Option<&str>,
+    cursor: Option<&str>,
+    limit: Option<usize>
+) -> Result<(Vec<FileInfo>, Option<String>)> {
+    if let Some(f) = filter {
+        let f = f.to_lowercase();
+        files.retain(|x| x.name.to_lowercase().contains(&f));
+    }
+
+    files.sort_by(|a, b| {
+        let dirs_first = (b.file_type == FileType::Directory).cmp(&(a.file_type == FileType::Directory));
+        let ord = match sort_by {
+            FileSortBy::Name => Ordering::Equal,
+            FileSortBy::Mtime => a.mtime.cmp(&b.mtime),
+            FileSortBy::Size => a.size.cmp(&b.size)
+        }.then_with(|| natsort::natural_cmp(&a.name, &b.name));
+        dirs_first.then(if sort_desc { ord.reverse() } else { ord })
+    });
+
+    let start = match cursor {
+        Some(c) => {
+            let pos = files.iter().position(|x| x.name == c);
+            if pos.is_none() {
+                return Err(SyncmiruError::from(anyhow!("cursor not found")))
+            }
+            pos.unwrap() + 1
+        }
+        None => 0
+    };
+    let mut page = files.split_off(start);
+    let mut next_cursor: Option<String> = None;
+    if let Some(l) = limit {
+        if page.len() > l {
+            page.truncate(l);
+            next_cursor = page.last().map(|x| x.name.clone());
+        }
+    }
+    Ok((page, next_cursor))
+}
+
+pub async fn file_access(
+    source: &Source,
+    path: &str
+) -> Result<FileAccess> {
+    match &source.kind {
+        SourceKind::Autoindex { file_jwt, .. }
+        | SourceKind::WebDav { file_jwt, .. }
+        | SourceKind::Local { file_jwt, .. } => {
+            let jwt = gen_access_jwt(file_jwt, path).await?;
+            Ok(FileAccess::Jwt { jwt })
+        }
+        SourceKind::S3 { bucket, credentials, presign_expiration } => {
+            let url = s3::presigned_url(bucket, credentials, path, *presign_expiration);
+            Ok(FileAccess::Presigned { url })
+        }
+    }
+}
+
+pub async fn gen_access_jwt(
+    file_jwt: &FileJwt,
     path: &str
 ) -> Result<String> {
     let mut header = JwsHeader::new();
@@ -69,19 +473,146 @@ pub async fn gen_access_jwt(
     let file_value = serde_json::from_str(&format!("\"{}\"", path))?;
     payload.set_claim("file", Some(file_value))?;
 
-    let signer = source.jwt_signer()?;
+    let signer = file_jwt.jwt_signer()?;
     header.set_algorithm(signer.algorithm().name());
     let signed = josekit::jwt::encode_with_signer(&payload, &header, &*signer)?;
     Ok(signed)
 }
 
+pub fn extension_allowed(name: &str, allowed_extensions: &Option<HashSet<String>>) -> bool {
+    allowed_extensions
+        .as_ref()
+        .map(|x| x.contains(extract_extension(name)))
+        .unwrap_or(true)
+}
+
 fn extract_extension(path: &str) -> &str {
     path.split(".").last().unwrap_or("")
 }
 
+fn extract_stem(name: &str) -> &str {
+    name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name)
+}
+
+fn dir_url(root_url: &str, path: &str) -> String {
+    let mut url = root_url.trim_end_matches('/').to_string();
+    for segment in path.split('/').filter(|x| !x.is_empty()) {
+        url.push('/');
+        url.push_str(&encode(segment));
+    }
+    url.push('/');
+    url
+}
+
+pub fn join_path(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
 fn split_on_last_occurrence(s: &str, delimiter: char) -> Option<(&str, &str)> {
     let mut parts = s.rsplitn(2, delimiter);
     let second_part = parts.next()?;
     let first_part = parts.next().unwrap_or("");
     Some((first_part, second_part))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use chrono::{TimeZone, Utc};
+    use crate::models::file::{FileInfo, FileSortBy, FileType};
+    use super::{sidecar_nfo, sidecar_poster, sidecar_subtitles, sort_filter_page};
+
+    fn file(name: &str, file_type: FileType, mtime: i64, size: Option<u64>) -> FileInfo {
+        FileInfo {
+            name: name.to_string(),
+            mtime: Utc.timestamp_opt(mtime, 0).unwrap(),
+            file_type,
+            size
+        }
+    }
+
+    fn sample() -> Vec<FileInfo> {
+        vec![
+            file("Episode 10.mkv", FileType::File, 30, Some(100)),
+            file("Extras", FileType::Directory, 50, None),
+            file("Episode 2.mkv", FileType::File, 10, Some(300)),
+            file("Episode 1.mkv", FileType::File, 20, Some(200)),
+        ]
+    }
+
+    fn names(files: &[FileInfo]) -> Vec<&str> {
+        files.iter().map(|x| x.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_name() {
+        let (files, next) = sort_filter_page(sample(), FileSortBy::Name, false, None, None, None).unwrap();
+        assert_eq!(names(&files), vec!["Extras", "Episode 1.mkv", "Episode 2.mkv", "Episode 10.mkv"]);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn test_sort_mtime_size_desc() {
+        let (files, _) = sort_filter_page(sample(), FileSortBy::Mtime, false, None, None, None).unwrap();
+        assert_eq!(names(&files), vec!["Extras", "Episode 2.mkv", "Episode 1.mkv", "Episode 10.mkv"]);
+
+        let (files, _) = sort_filter_page(sample(), FileSortBy::Size, true, None, None, None).unwrap();
+        assert_eq!(names(&files), vec!["Extras", "Episode 2.mkv", "Episode 1.mkv", "Episode 10.mkv"]);
+    }
+
+    #[test]
+    fn test_filter() {
+        let (files, _) = sort_filter_page(sample(), FileSortBy::Name, false, Some("EPISODE 1"), None, None).unwrap();
+        assert_eq!(names(&files), vec!["Episode 1.mkv", "Episode 10.mkv"]);
+    }
+
+    #[test]
+    fn test_pagination() {
+        let (files, next) = sort_filter_page(sample(), FileSortBy::Name, false, None, None, Some(2)).unwrap();
+        assert_eq!(names(&files), vec!["Extras", "Episode 1.mkv"]);
+        assert_eq!(next.as_deref(), Some("Episode 1.mkv"));
+
+        let (files, next) = sort_filter_page(sample(), FileSortBy::Name, false, None, next.as_deref(), Some(2)).unwrap();
+        assert_eq!(names(&files), vec!["Episode 2.mkv", "Episode 10.mkv"]);
+        assert!(next.is_none());
+
+        assert!(sort_filter_page(sample(), FileSortBy::Name, false, None, Some("missing"), Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_sidecar_subtitles() {
+        let files = vec![
+            file("Episode 1.mkv", FileType::File, 0, Some(100)),
+            file("Episode 1.srt", FileType::File, 0, Some(1)),
+            file("Episode 1.en.ass", FileType::File, 0, Some(1)),
+            file("Episode 1.cs.srt", FileType::File, 0, Some(1)),
+            file("Episode 10.srt", FileType::File, 0, Some(1)),
+            file("Episode 1.nfo", FileType::File, 0, Some(1)),
+            file("Episode 1.srt.d", FileType::Directory, 0, None),
+        ];
+        let extensions: HashSet<String> = ["srt", "ass"].iter().map(|x| x.to_string()).collect();
+        assert_eq!(
+            sidecar_subtitles("Episode 1.mkv", &files, &extensions),
+            vec!["Episode 1.cs.srt", "Episode 1.en.ass", "Episode 1.srt"]
+        );
+        assert!(sidecar_subtitles("Episode 2.mkv", &files, &extensions).is_empty());
+    }
+
+    #[test]
+    fn test_sidecar_nfo_poster() {
+        let files = vec![
+            file("Episode 1.mkv", FileType::File, 0, Some(100)),
+            file("Episode 1.nfo", FileType::File, 0, Some(1)),
+            file("Episode 1-thumb.jpg", FileType::File, 0, Some(1)),
+            file("Episode 2.mkv", FileType::File, 0, Some(100)),
+            file("movie.nfo", FileType::File, 0, Some(1)),
+            file("Poster.JPG", FileType::File, 0, Some(1)),
+            file("folder.jpg", FileType::Directory, 0, None),
+        ];
+        assert_eq!(sidecar_nfo("Episode 1.mkv", &files).as_deref(), Some("Episode 1.nfo"));
+        assert_eq!(sidecar_nfo("Episode 2.mkv", &files).as_deref(), Some("movie.nfo"));
+        assert_eq!(sidecar_poster("Episode 1.mkv", &files).as_deref(), Some("Episode 1-thumb.jpg"));
+        assert_eq!(sidecar_poster("Episode 2.mkv", &files).as_deref(), Some("Poster.JPG"));
+        assert!(sidecar_poster("Episode 2.mkv", &files[..5]).is_none());
+    }
+}
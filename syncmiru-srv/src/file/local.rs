@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use crate::error::SyncmiruError;
+use crate::models::file::{FileInfo, FileType};
+use crate::Result;
+
+pub async fn list(root: &Path, path: &str) -> Result<Vec<FileInfo>> {
+    let dir = resolve(root, path)?;
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    let mut files = Vec::<FileInfo>::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue
+        }
+        let metadata = entry.metadata().await?;
+        let (file_type, size) = if metadata.is_dir() {
+            (FileType::Directory, None)
+        }
+        else {
+            (FileType::File, Some(metadata.len()))
+        };
+        files.push(FileInfo {
+            name,
+            mtime: DateTime::<Utc>::from(metadata.modified()?),
+            file_type,
+            size
+        });
+    }
+    Ok(files)
+}
+
+pub fn resolve(root: &Path, path: &str) -> Result<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    for segment in path.split('/').filter(|x| !x.is_empty()) {
+        if segment.starts_with('.') || segment.contains('\\') {
+            return Err(SyncmiruError::from(anyhow!("invalid path")))
+        }
+        resolved.push(segment);
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use super::resolve;
+
+    #[test]
+    fn test_resolve() {
+        let root = Path::new("/srv/uploads");
+        assert_eq!(resolve(root, "/").unwrap(), PathBuf::from("/srv/uploads"));
+        assert_eq!(resolve(root, "/movie.mkv").unwrap(), PathBuf::from("/srv/uploads/movie.mkv"));
+        assert!(resolve(root, "/../etc/passwd").is_err());
+        assert!(resolve(root, "/.partial/1").is_err());
+    }
+}
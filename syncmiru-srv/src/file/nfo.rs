@@ -0,0 +1,108 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::Result;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct NfoInfo {
+    pub title: Option<String>,
+    pub year: Option<i32>,
+    pub plot: Option<String>
+}
+
+pub fn parse(xml: &str) -> Result<NfoInfo> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut info = NfoInfo::default();
+    let mut premiered: Option<String> = None;
+    let mut depth = 0usize;
+    let mut text_target: Option<Vec<u8>> = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                depth += 1;
+                let name = e.local_name().as_ref().to_vec();
+                // only the direct children of movie, episodedetails, tvshow, ... are of interest
+                if depth == 2 && matches!(name.as_slice(), b"title" | b"year" | b"premiered" | b"aired" | b"plot") {
+                    text_target = Some(name);
+                }
+            }
+            Event::Text(t) => {
+                if let Some(target) = &text_target {
+                    let text = t.unescape()?.into_owned();
+                    set_field(&mut info, &mut premiered, target, text);
+                }
+            }
+            Event::CData(t) => {
+                if let Some(target) = &text_target {
+                    let text = String::from_utf8_lossy(&t.into_inner()).trim().to_string();
+                    set_field(&mut info, &mut premiered, target, text);
+                }
+            }
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                text_target = None;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if info.year.is_none() {
+        info.year = premiered
+            .and_then(|x| x.get(0..4).and_then(|y| y.parse::<i32>().ok()));
+    }
+    Ok(info)
+}
+
+fn set_field(info: &mut NfoInfo, premiered: &mut Option<String>, target: &[u8], text: String) {
+    if text.is_empty() {
+        return
+    }
+    match target {
+        b"title" => info.title = Some(text),
+        b"year" => info.year = text.parse::<i32>().ok(),
+        b"premiered" | b"aired" => *premiered = Some(text),
+        b"plot" => info.plot = Some(text),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, NfoInfo};
+
+    #[test]
+    fn test_parse_movie() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes" ?>
+<movie>
+    <title>Spirited Away</title>
+    <originaltitle>千と千尋の神隠し</originaltitle>
+    <year>2001</year>
+    <plot><![CDATA[A girl wanders into a world of spirits.]]></plot>
+    <actor>
+        <name>Rumi Hiiragi</name>
+        <role>Chihiro</role>
+    </actor>
+    <set><title>Ghibli</title></set>
+</movie>"#;
+        assert_eq!(parse(xml).unwrap(), NfoInfo {
+            title: Some("Spirited Away".to_string()),
+            year: Some(2001),
+            plot: Some("A girl wanders into a world of spirits.".to_string())
+        });
+    }
+
+    #[test]
+    fn test_parse_episode() {
+        let xml = r#"<episodedetails>
+    <title>The Boy &amp; the Beast</title>
+    <aired>2015-07-11</aired>
+    <plot/>
+</episodedetails>"#;
+        assert_eq!(parse(xml).unwrap(), NfoInfo {
+            title: Some("The Boy & the Beast".to_string()),
+            year: Some(2015),
+            plot: None
+        });
+    }
+}
@@ -0,0 +1,26 @@
+use crate::config::ListingFormat;
+use crate::file::parsers::apache::ApacheParser;
+use crate::file::parsers::caddy::CaddyParser;
+use crate::file::parsers::openresty::OpenrestyParser;
+use crate::models::file::FileInfo;
+use crate::Result;
+
+mod apache;
+mod caddy;
+mod openresty;
+
+pub trait ListingParser: Send + Sync {
+    fn url(&self, root_url: &str, path: &str) -> String;
+    fn accept(&self) -> &'static str;
+    fn parse(&self, body: &str) -> Result<Vec<FileInfo>>;
+}
+
+impl ListingFormat {
+    pub fn parser(&self) -> Box<dyn ListingParser> {
+        match self {
+            ListingFormat::Openresty => Box::new(OpenrestyParser),
+            ListingFormat::Caddy => Box::new(CaddyParser),
+            ListingFormat::Apache => Box::new(ApacheParser)
+        }
+    }
+}
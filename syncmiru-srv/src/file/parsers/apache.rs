@@ -0,0 +1,158 @@
+use anyhow::anyhow;
+use chrono::{NaiveDateTime, Utc};
+use urlencoding::decode;
+use crate::error::SyncmiruError;
+use crate::file::dir_url;
+use crate::file::parsers::ListingParser;
+use crate::models::file::{FileInfo, FileType};
+use crate::Result;
+
+pub struct ApacheParser;
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M", "%d-%b-%Y %H:%M"];
+
+impl ListingParser for ApacheParser {
+    fn url(&self, root_url: &str, path: &str) -> String {
+        dir_url(root_url, path)
+    }
+
+    fn accept(&self) -> &'static str {
+        "text/html"
+    }
+
+    fn parse(&self, body: &str) -> Result<Vec<FileInfo>> {
+        let mut files_info = Vec::<FileInfo>::new();
+        let mut rest = body;
+        while let Some(start) = rest.find("<a href=\"") {
+            rest = &rest[start + 9..];
+            let href_end = rest.find('"')
+                .ok_or(SyncmiruError::from(anyhow!("unterminated href")))?;
+            let href = &rest[..href_end];
+            let anchor_end = rest.find("</a>")
+                .ok_or(SyncmiruError::from(anyhow!("unterminated anchor")))?;
+            rest = &rest[anchor_end + 4..];
+
+            if href.starts_with('?') || href.starts_with('/') || href.starts_with('#')
+                || href.starts_with("../") || href.contains("://") {
+                continue
+            }
+
+            let row_end = ["</tr>", "\n", "<a "]
+                .iter()
+                .filter_map(|x| rest.find(x))
+                .min()
+                .unwrap_or(rest.len());
+            let columns = strip_tags(&rest[..row_end]).replace("&nbsp;", " ");
+            let mut tokens = columns.split_whitespace();
+            let date = tokens.next().unwrap_or("");
+            let time = tokens.next().unwrap_or("");
+            let size = tokens.next().unwrap_or("-");
+
+            let href_unescaped = href.replace("&amp;", "&");
+            let href_decoded = decode(href_unescaped.trim_start_matches("./"))
+                .map_err(|e| SyncmiruError::from(anyhow!(e)))?;
+            let is_dir = href_decoded.ends_with('/');
+            let name = href_decoded.trim_end_matches('/').to_string();
+            let mtime = parse_mtime(&format!("{} {}", date, time))?;
+
+            if is_dir {
+                files_info.push(FileInfo { name, mtime, file_type: FileType::Directory, size: None });
+            }
+            else {
+                files_info.push(FileInfo { name, mtime, file_type: FileType::File, size: parse_size(size) });
+            }
+        }
+        Ok(files_info)
+    }
+}
+
+fn strip_tags(s: &str) -> String {
+    let mut output = String::new();
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                output.push(' ');
+            }
+            _ if !in_tag => output.push(c),
+            _ => {}
+        }
+    }
+    output
+}
+
+fn parse_mtime(s: &str) -> Result<chrono::DateTime<Utc>> {
+    for format in DATE_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(dt.and_utc())
+        }
+    }
+    Err(SyncmiruError::from(anyhow!("unknown date format: {}", s)))
+}
+
+fn parse_size(s: &str) -> Option<u64> {
+    if s == "-" {
+        return None
+    }
+    let (number, multiplier) = match s.chars().last()? {
+        'K' => (&s[..s.len() - 1], 1u64 << 10),
+        'M' => (&s[..s.len() - 1], 1u64 << 20),
+        'G' => (&s[..s.len() - 1], 1u64 << 30),
+        'T' => (&s[..s.len() - 1], 1u64 << 40),
+        _ => (s, 1u64)
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .map(|x| (x * multiplier as f64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file::parsers::ListingParser;
+    use crate::models::file::FileType;
+    use super::{parse_size, ApacheParser};
+
+    #[test]
+    fn test_parse_table_fixture() {
+        let files = ApacheParser.parse(include_str!("fixtures/apache_table.html")).unwrap();
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].name, "Season 1");
+        assert_eq!(files[0].file_type, FileType::Directory);
+        assert_eq!(files[0].size, None);
+        assert_eq!(files[0].mtime.to_rfc3339(), "2025-01-15T11:30:00+00:00");
+
+        assert_eq!(files[1].name, "Episode 01 & Extras.mkv");
+        assert_eq!(files[1].file_type, FileType::File);
+        assert_eq!(files[1].size, Some(1 << 20));
+
+        assert_eq!(files[2].name, "A very long episode name that apache truncates.mkv");
+        assert_eq!(files[2].size, Some(734003200));
+    }
+
+    #[test]
+    fn test_parse_pre_fixture() {
+        let files = ApacheParser.parse(include_str!("fixtures/apache_pre.html")).unwrap();
+        assert_eq!(files.len(), 2);
+
+        assert_eq!(files[0].name, "Season 1");
+        assert_eq!(files[0].file_type, FileType::Directory);
+        assert_eq!(files[0].mtime.to_rfc3339(), "2025-01-15T11:30:00+00:00");
+
+        assert_eq!(files[1].name, "Episode 01.mkv");
+        assert_eq!(files[1].file_type, FileType::File);
+        assert_eq!(files[1].size, Some(734003200));
+        assert_eq!(files[1].mtime.to_rfc3339(), "2025-01-16T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("-"), None);
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("4.0K"), Some(4096));
+        assert_eq!(parse_size("1.5M"), Some(1572864));
+    }
+}
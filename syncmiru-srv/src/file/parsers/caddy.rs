@@ -0,0 +1,82 @@
+use chrono::Utc;
+use serde::Deserialize;
+use crate::file::dir_url;
+use crate::file::parsers::ListingParser;
+use crate::models::file::{FileInfo, FileType};
+use crate::Result;
+
+pub struct CaddyParser;
+
+#[derive(Debug, Deserialize)]
+struct CaddyFileInfo {
+    name: String,
+    size: Option<u64>,
+    mod_time: chrono::DateTime<Utc>,
+    is_dir: bool
+}
+
+impl ListingParser for CaddyParser {
+    fn url(&self, root_url: &str, path: &str) -> String {
+        dir_url(root_url, path)
+    }
+
+    fn accept(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn parse(&self, body: &str) -> Result<Vec<FileInfo>> {
+        let caddy_files = serde_json::from_str::<Vec<CaddyFileInfo>>(body)?;
+        let files_info = caddy_files
+            .into_iter()
+            .map(|x| {
+                if x.is_dir {
+                    FileInfo {
+                        name: x.name.trim_end_matches('/').to_string(),
+                        mtime: x.mod_time,
+                        file_type: FileType::Directory,
+                        size: None
+                    }
+                }
+                else {
+                    FileInfo {
+                        name: x.name,
+                        mtime: x.mod_time,
+                        file_type: FileType::File,
+                        size: x.size
+                    }
+                }
+            })
+            .collect::<Vec<FileInfo>>();
+        Ok(files_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file::parsers::ListingParser;
+    use crate::models::file::FileType;
+    use super::CaddyParser;
+
+    #[test]
+    fn test_parse_fixture() {
+        let files = CaddyParser.parse(include_str!("fixtures/caddy.json")).unwrap();
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].name, "Season 1");
+        assert_eq!(files[0].file_type, FileType::Directory);
+        assert_eq!(files[0].size, None);
+
+        assert_eq!(files[1].name, "Episode 01.mkv");
+        assert_eq!(files[1].file_type, FileType::File);
+        assert_eq!(files[1].size, Some(1048576));
+        assert_eq!(files[1].mtime.to_rfc3339(), "2025-01-16T11:00:00.123456789+00:00");
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(
+            CaddyParser.url("http://caddy.localhost/media/", "/Show Name"),
+            "http://caddy.localhost/media/Show%20Name/"
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use urlencoding::encode;
+use crate::file::parsers::ListingParser;
+use crate::models::file::FileInfo;
+use crate::Result;
+
+pub struct OpenrestyParser;
+
+impl ListingParser for OpenrestyParser {
+    fn url(&self, root_url: &str, path: &str) -> String {
+        format!("{}{}", root_url, encode(path))
+    }
+
+    fn accept(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn parse(&self, body: &str) -> Result<Vec<FileInfo>> {
+        let files_info = serde_json::from_str::<Vec<FileInfo>>(body)?;
+        Ok(files_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file::parsers::ListingParser;
+    use crate::models::file::FileType;
+    use super::OpenrestyParser;
+
+    #[test]
+    fn test_parse_fixture() {
+        let files = OpenrestyParser.parse(include_str!("fixtures/openresty.json")).unwrap();
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].name, "Season 1");
+        assert_eq!(files[0].file_type, FileType::Directory);
+        assert_eq!(files[0].size, None);
+        assert_eq!(files[0].mtime.to_rfc3339(), "2025-01-15T11:30:00+00:00");
+
+        assert_eq!(files[2].name, "Episode 02.mkv");
+        assert_eq!(files[2].file_type, FileType::File);
+        assert_eq!(files[2].size, Some(734003200));
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(
+            OpenrestyParser.url("http://openresty/syncmiru-server/?dir=", "/Show Name"),
+            "http://openresty/syncmiru-server/?dir=%2FShow%20Name"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::time::Duration;
+use anyhow::anyhow;
+use chrono::Utc;
+use reqwest::Client;
+use rusty_s3::{Bucket, Credentials, S3Action};
+use rusty_s3::actions::{ListObjectsV2, ListObjectsV2Response};
+use crate::{constants, Result};
+use crate::error::SyncmiruError;
+use crate::models::file::{FileInfo, FileType};
+
+pub async fn list(
+    bucket: &Bucket,
+    credentials: &Credentials,
+    path: &str
+) -> Result<Vec<FileInfo>> {
+    let prefix = path_prefix(path);
+    let mut files = Vec::<FileInfo>::new();
+    let mut continuation_token: Option<String> = None;
+    for _ in 0..constants::S3_LIST_MAX_PAGES {
+        let mut action = bucket.list_objects_v2(Some(credentials));
+        action.with_prefix(prefix.as_str());
+        action.with_delimiter("/");
+        if let Some(token) = &continuation_token {
+            action.with_continuation_token(token.as_str());
+        }
+        let url = action.sign(Duration::from_secs(constants::HTTP_TIMEOUT * 2));
+
+        let response = Client::new()
+            .get(url)
+            .timeout(Duration::from_secs(constants::HTTP_TIMEOUT))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(SyncmiruError::from(anyhow!(response.text().await?)))
+        }
+
+        let body = response.text().await?;
+        let parsed = ListObjectsV2::parse_response(&body)
+            .map_err(|e| SyncmiruError::from(anyhow!(e)))?;
+        continuation_token = parsed.next_continuation_token.clone();
+        files.extend(to_file_info(parsed, &prefix)?);
+        if continuation_token.is_none() {
+            return Ok(files)
+        }
+    }
+    Err(SyncmiruError::from(anyhow!("too many objects")))
+}
+
+pub fn presigned_url(
+    bucket: &Bucket,
+    credentials: &Credentials,
+    path: &str,
+    expires_in: u64
+) -> String {
+    let key = path.trim_start_matches('/');
+    bucket
+        .get_object(Some(credentials), key)
+        .sign(Duration::from_secs(expires_in))
+        .to_string()
+}
+
+fn path_prefix(path: &str) -> String {
+    let trimmed = path.trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    }
+    else {
+        format!("{}/", trimmed)
+    }
+}
+
+fn to_file_info(response: ListObjectsV2Response, prefix: &str) -> Result<Vec<FileInfo>> {
+    let mut files = Vec::<FileInfo>::new();
+    for common_prefix in response.common_prefixes {
+        let name = common_prefix.prefix
+            .strip_prefix(prefix)
+            .unwrap_or(&common_prefix.prefix)
+            .trim_end_matches('/')
+            .to_string();
+        if name.is_empty() {
+            continue
+        }
+        files.push(FileInfo {
+            name,
+            mtime: chrono::DateTime::<Utc>::UNIX_EPOCH,
+            file_type: FileType::Directory,
+            size: None
+        });
+    }
+    for object in response.contents {
+        let name = object.key
+            .strip_prefix(prefix)
+            .unwrap_or(&object.key)
+            .to_string();
+        if name.is_empty() || name.ends_with('/') {
+            continue
+        }
+        let mtime = chrono::DateTime::parse_from_rfc3339(&object.last_modified)
+            .map_err(|e| SyncmiruError::from(anyhow!(e)))?
+            .to_utc();
+        files.push(FileInfo {
+            name,
+            mtime,
+            file_type: FileType::File,
+            size: Some(object.size)
+        });
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use rusty_s3::actions::ListObjectsV2;
+    use crate::models::file::FileType;
+    use super::{path_prefix, to_file_info};
+
+    const LIST_RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Name>media</Name>
+    <Prefix>Show Name/</Prefix>
+    <KeyCount>3</KeyCount>
+    <MaxKeys>1000</MaxKeys>
+    <Delimiter>/</Delimiter>
+    <IsTruncated>false</IsTruncated>
+    <Contents>
+        <Key>Show Name/</Key>
+        <LastModified>2025-01-15T11:30:00.000Z</LastModified>
+        <ETag>&quot;d41d8cd98f00b204e9800998ecf8427e&quot;</ETag>
+        <Size>0</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Contents>
+    <Contents>
+        <Key>Show Name/Episode 01.mkv</Key>
+        <LastModified>2025-01-16T12:00:00.000Z</LastModified>
+        <ETag>&quot;b1946ac92492d2347c6235b4d2611184&quot;</ETag>
+        <Size>1048576</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Contents>
+    <CommonPrefixes>
+        <Prefix>Show Name/Season 1/</Prefix>
+    </CommonPrefixes>
+</ListBucketResult>"#;
+
+    #[test]
+    fn test_to_file_info() {
+        let parsed = ListObjectsV2::parse_response(LIST_RESPONSE).unwrap();
+        let files = to_file_info(parsed, &path_prefix("/Show Name")).unwrap();
+        assert_eq!(files.len(), 2);
+
+        assert_eq!(files[0].name, "Season 1");
+        assert_eq!(files[0].file_type, FileType::Directory);
+
+        assert_eq!(files[1].name, "Episode 01.mkv");
+        assert_eq!(files[1].file_type, FileType::File);
+        assert_eq!(files[1].size, Some(1048576));
+        assert_eq!(files[1].mtime.to_rfc3339(), "2025-01-16T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_path_prefix() {
+        assert_eq!(path_prefix("/"), "");
+        assert_eq!(path_prefix("/a/b"), "a/b/");
+        assert_eq!(path_prefix("/a/b/"), "a/b/");
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+use anyhow::anyhow;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use reqwest::{Client, Method};
+use urlencoding::decode;
+use crate::{constants, Result};
+use crate::error::SyncmiruError;
+use crate::file::dir_url;
+use crate::models::file::{FileInfo, FileType};
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+    <d:prop>
+        <d:resourcetype/>
+        <d:getlastmodified/>
+        <d:getcontentlength/>
+    </d:prop>
+</d:propfind>"#;
+
+#[derive(Default)]
+struct DavResponse {
+    href: String,
+    last_modified: Option<String>,
+    content_length: Option<u64>,
+    collection: bool
+}
+
+pub async fn list(
+    root_url: &str,
+    username: Option<&str>,
+    password: Option<&str>,
+    path: &str
+) -> Result<Vec<FileInfo>> {
+    let url = dir_url(root_url, path);
+    let mut request = Client::new()
+        .request(Method::from_bytes(b"PROPFIND").unwrap(), &url)
+        .timeout(Duration::from_secs(constants::HTTP_TIMEOUT))
+        .header("Depth", "1")
+        .header(reqwest::header::CONTENT_TYPE, "application/xml")
+        .body(PROPFIND_BODY);
+    if let Some(u) = username {
+        request = request.basic_auth(u, password);
+    }
+
+    let response = request
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(SyncmiruError::from(anyhow!(response.text().await?)))
+    }
+
+    let body = response.text().await?;
+    parse_multistatus(&body, &url)
+}
+
+fn parse_multistatus(xml: &str, dir_url: &str) -> Result<Vec<FileInfo>> {
+    let dir_path = href_path(dir_url);
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut files = Vec::<FileInfo>::new();
+    let mut current: Option<DavResponse> = None;
+    let mut text_target: Option<Vec<u8>> = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = e.local_name().as_ref().to_vec();
+                match name.as_slice() {
+                    b"response" => current = Some(DavResponse::default()),
+                    b"collection" => if let Some(r) = current.as_mut() { r.collection = true },
+                    b"href" | b"getlastmodified" | b"getcontentlength" => text_target = Some(name),
+                    _ => {}
+                }
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"collection" => {
+                if let Some(r) = current.as_mut() {
+                    r.collection = true
+                }
+            }
+            Event::Text(t) => {
+                if let (Some(target), Some(r)) = (&text_target, current.as_mut()) {
+                    let text = t.unescape()?.into_owned();
+                    match target.as_slice() {
+                        b"href" => r.href = text,
+                        b"getlastmodified" => r.last_modified = Some(text),
+                        b"getcontentlength" => r.content_length = text.parse::<u64>().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            Event::End(e) => {
+                match e.local_name().as_ref() {
+                    b"response" => {
+                        if let Some(r) = current.take() {
+                            if let Some(file_info) = to_file_info(r, &dir_path)? {
+                                files.push(file_info);
+                            }
+                        }
+                    }
+                    b"href" | b"getlastmodified" | b"getcontentlength" => text_target = None,
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(files)
+}
+
+fn to_file_info(r: DavResponse, dir_path: &str) -> Result<Option<FileInfo>> {
+    let path = href_path(&r.href);
+    if path == dir_path {
+        return Ok(None)
+    }
+    let name = path.rsplit('/').next().unwrap_or("").to_string();
+    if name.is_empty() {
+        return Ok(None)
+    }
+    let last_modified = r.last_modified
+        .ok_or(SyncmiruError::from(anyhow!("getlastmodified missing for {}", r.href)))?;
+    let mtime = chrono::DateTime::parse_from_rfc2822(&last_modified)
+        .map_err(|e| SyncmiruError::from(anyhow!(e)))?
+        .to_utc();
+
+    if r.collection {
+        Ok(Some(FileInfo { name, mtime, file_type: FileType::Directory, size: None }))
+    }
+    else {
+        Ok(Some(FileInfo { name, mtime, file_type: FileType::File, size: r.content_length }))
+    }
+}
+
+fn href_path(href: &str) -> String {
+    let path = url::Url::parse(href)
+        .map(|x| x.path().to_string())
+        .unwrap_or(href.to_string());
+    decode(&path)
+        .map(|x| x.into_owned())
+        .unwrap_or(path.clone())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::file::FileType;
+    use super::parse_multistatus;
+
+    const MULTISTATUS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:">
+    <D:response>
+        <D:href>/media/Show%20Name/</D:href>
+        <D:propstat>
+            <D:prop>
+                <D:resourcetype><D:collection/></D:resourcetype>
+                <D:getlastmodified>Tue, 14 Jan 2025 10:00:00 GMT</D:getlastmodified>
+            </D:prop>
+            <D:status>HTTP/1.1 200 OK</D:status>
+        </D:propstat>
+    </D:response>
+    <D:response>
+        <D:href>/media/Show%20Name/Season%201/</D:href>
+        <D:propstat>
+            <D:prop>
+                <D:resourcetype><D:collection/></D:resourcetype>
+                <D:getlastmodified>Wed, 15 Jan 2025 11:30:00 GMT</D:getlastmodified>
+            </D:prop>
+            <D:status>HTTP/1.1 200 OK</D:status>
+        </D:propstat>
+        <D:propstat>
+            <D:prop><D:getcontentlength/></D:prop>
+            <D:status>HTTP/1.1 404 Not Found</D:status>
+        </D:propstat>
+    </D:response>
+    <D:response>
+        <D:href>http://dav.localhost/media/Show%20Name/Episode%2001.mkv</D:href>
+        <D:propstat>
+            <D:prop>
+                <D:resourcetype/>
+                <D:getlastmodified>Thu, 16 Jan 2025 12:00:00 GMT</D:getlastmodified>
+                <D:getcontentlength>1048576</D:getcontentlength>
+            </D:prop>
+            <D:status>HTTP/1.1 200 OK</D:status>
+        </D:propstat>
+    </D:response>
+</D:multistatus>"#;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let files = parse_multistatus(MULTISTATUS, "http://dav.localhost/media/Show%20Name/").unwrap();
+        assert_eq!(files.len(), 2);
+
+        assert_eq!(files[0].name, "Season 1");
+        assert_eq!(files[0].file_type, FileType::Directory);
+        assert_eq!(files[0].size, None);
+
+        assert_eq!(files[1].name, "Episode 01.mkv");
+        assert_eq!(files[1].file_type, FileType::File);
+        assert_eq!(files[1].size, Some(1048576));
+        assert_eq!(files[1].mtime.to_rfc2822(), "Thu, 16 Jan 2025 12:00:00 +0000");
+    }
+}
@@ -3,20 +3,29 @@ use crate::email;
 use crate::error::SyncmiruError;
 use crate::handlers::utils;
 use crate::html;
-use crate::models::http::{BooleanResp, Email, EmailVerify, ForgottenPasswordChange, Jwt, Login, RegForm, ServiceStatus, TknEmail, Username};
+use crate::models::http::{BooleanResp, Email, EmailVerify, ForgottenPasswordChange, Jwt, Login, RegForm, ServiceStatus, TknEmail, UploadCreate, UploadStatus, Username};
 use crate::models::query::EmailTknType;
 use crate::models::{EmailWithLang, Tkn};
 use crate::result::Result;
 use crate::srvstate::SrvState;
-use crate::{query, tkn};
+use crate::{constants, file, query, tkn};
+use crate::config::{JwtVerifier, UploadsConfig};
+use crate::models::query::{Id, Upload};
 use anyhow::Context;
-use axum::extract::{Query, State};
-use axum::http::StatusCode;
-use axum::response::{Html, IntoResponse};
+use axum::body::Bytes;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
 use axum::Json;
+use josekit::jwt::JwtPayloadValidator;
 use std::borrow::Cow;
+use std::io::ErrorKind;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tower::BoxError;
+use std::time::SystemTime;
+use tokio::io::AsyncWriteExt;
+use tower::{BoxError, ServiceExt};
+use tower_http::services::ServeFile;
 use validator::Validate;
 
 pub async fn index() -> &'static str {
@@ -354,6 +363,217 @@ pub async fn reg_tkn_valid(
     }
 }
 
+pub async fn uploads(
+    State(state): State<Arc<SrvState>>,
+    headers: HeaderMap
+) -> Result<Json<Vec<UploadStatus>>> {
+    let uid = utils::http_auth(&state, &headers).await?;
+    let uploads_conf = state.config.uploads.as_ref().unwrap();
+    let uploads = query::get_uploads_by_uid(&state.db, uid).await?;
+    let mut resp = Vec::<UploadStatus>::new();
+    for upload in uploads {
+        resp.push(upload_status_of(uploads_conf, upload).await?);
+    }
+    Ok(Json(resp))
+}
+
+pub async fn upload_create(
+    State(state): State<Arc<SrvState>>,
+    headers: HeaderMap,
+    Json(payload): Json<UploadCreate>
+) -> Result<Json<UploadStatus>> {
+    let uid = utils::http_auth(&state, &headers).await?;
+    payload.validate()?;
+    let uploads_conf = state.config.uploads.as_ref().unwrap();
+    if !file::extension_allowed(&payload.name, &state.config.extensions.videos) {
+        return Err(SyncmiruError::UnprocessableEntity("extension".to_string()))
+    }
+
+    let active_uploads = state.active_uploads.lock().await;
+    let name_unique = query::upload_name_unique(&state.db, &payload.name).await?;
+    if !name_unique || tokio::fs::try_exists(uploads_conf.dir.join(&payload.name)).await? {
+        return Err(SyncmiruError::Conflict("name".to_string()))
+    }
+    let user_used = query::get_uploads_size_by_uid(&state.db, uid).await?;
+    let user_total = user_used.checked_add(payload.size);
+    if user_total.is_none() || user_total.unwrap() as u64 > uploads_conf.user_quota {
+        return Err(SyncmiruError::UnprocessableEntity("user_quota".to_string()))
+    }
+    let global_used = query::get_uploads_size(&state.db).await?;
+    let global_total = global_used.checked_add(payload.size);
+    if global_total.is_none() || global_total.unwrap() as u64 > uploads_conf.global_quota {
+        return Err(SyncmiruError::UnprocessableEntity("global_quota".to_string()))
+    }
+    let id = query::new_upload(&state.db, uid, &payload.name, payload.size).await?;
+    tokio::fs::File::create(partial_path(uploads_conf, id)).await?;
+    drop(active_uploads);
+
+    Ok(Json(UploadStatus { id, name: payload.name, size: payload.size, offset: 0, finished: false }))
+}
+
+pub async fn upload_status(
+    State(state): State<Arc<SrvState>>,
+    headers: HeaderMap,
+    Path(id): Path<Id>
+) -> Result<Json<UploadStatus>> {
+    let uid = utils::http_auth(&state, &headers).await?;
+    let uploads_conf = state.config.uploads.as_ref().unwrap();
+    let upload = query::get_upload(&state.db, id, uid)
+        .await?
+        .ok_or(SyncmiruError::UnprocessableEntity("upload".to_string()))?;
+    Ok(Json(upload_status_of(uploads_conf, upload).await?))
+}
+
+pub async fn upload_chunk(
+    State(state): State<Arc<SrvState>>,
+    headers: HeaderMap,
+    Path(id): Path<Id>,
+    body: Bytes
+) -> Result<Json<UploadStatus>> {
+    let uid = utils::http_auth(&state, &headers).await?;
+    let uploads_conf = state.config.uploads.as_ref().unwrap();
+    let upload = query::get_upload(&state.db, id, uid)
+        .await?
+        .ok_or(SyncmiruError::UnprocessableEntity("upload".to_string()))?;
+    if upload.finished {
+        return Err(SyncmiruError::Conflict("finished".to_string()))
+    }
+    let offset = headers
+        .get(constants::UPLOAD_OFFSET_HEADER)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.parse::<i64>().ok())
+        .ok_or(SyncmiruError::UnprocessableEntity("offset".to_string()))?;
+    if body.is_empty() {
+        return Err(SyncmiruError::UnprocessableEntity("chunk".to_string()))
+    }
+
+    {
+        let mut active_uploads = state.active_uploads.lock().await;
+        if !active_uploads.insert(id) {
+            return Err(SyncmiruError::Conflict("active".to_string()))
+        }
+    }
+    let status_r = append_chunk(&state, uploads_conf, upload, offset, &body).await;
+    state.active_uploads.lock().await.remove(&id);
+    Ok(Json(status_r?))
+}
+
+pub async fn upload_delete(
+    State(state): State<Arc<SrvState>>,
+    headers: HeaderMap,
+    Path(id): Path<Id>
+) -> Result<()> {
+    let uid = utils::http_auth(&state, &headers).await?;
+    let uploads_conf = state.config.uploads.as_ref().unwrap();
+    let upload = query::get_upload(&state.db, id, uid)
+        .await?
+        .ok_or(SyncmiruError::UnprocessableEntity("upload".to_string()))?;
+
+    let active_uploads = state.active_uploads.lock().await;
+    if active_uploads.contains(&id) {
+        return Err(SyncmiruError::Conflict("active".to_string()))
+    }
+    let path = if upload.finished {
+        uploads_conf.dir.join(&upload.name)
+    }
+    else {
+        partial_path(uploads_conf, id)
+    };
+    if let Err(e) = tokio::fs::remove_file(path).await {
+        if e.kind() != ErrorKind::NotFound {
+            return Err(SyncmiruError::from(e))
+        }
+    }
+    query::delete_upload(&state.db, id).await?;
+    drop(active_uploads);
+
+    state.listing_cache.invalidate(Some(&uploads_conf.source_name)).await;
+    Ok(())
+}
+
+pub async fn uploaded_file(
+    State(state): State<Arc<SrvState>>,
+    req: Request
+) -> Result<Response> {
+    let uploads_conf = state.config.uploads.as_ref().unwrap();
+    let jwt = utils::bearer_tkn(req.headers())?;
+    let verifier = uploads_conf.jwt_verifier()?;
+    let (payload, _) = josekit::jwt::decode_with_verifier(jwt, &*verifier)
+        .map_err(|_| SyncmiruError::AuthError)?;
+    let mut validator = JwtPayloadValidator::new();
+    validator.set_base_time(SystemTime::now());
+    validator
+        .validate(&payload)
+        .map_err(|_| SyncmiruError::AuthError)?;
+    let file_path = payload
+        .claim("file")
+        .and_then(|x| x.as_str())
+        .ok_or(SyncmiruError::AuthError)?;
+    let path = file::local::resolve(&uploads_conf.dir, file_path)?;
+
+    let resp = ServeFile::new(path)
+        .oneshot(req)
+        .await
+        .map_err(|e| SyncmiruError::from(anyhow::anyhow!(e)))?;
+    Ok(resp.into_response())
+}
+
+async fn append_chunk(
+    state: &Arc<SrvState>,
+    uploads_conf: &UploadsConfig,
+    upload: Upload,
+    offset: i64,
+    chunk: &[u8]
+) -> Result<UploadStatus> {
+    let partial = partial_path(uploads_conf, upload.id);
+    let current = tokio::fs::metadata(&partial).await?.len() as i64;
+    if offset != current {
+        return Err(SyncmiruError::Conflict("offset".to_string()))
+    }
+    if current + chunk.len() as i64 > upload.size {
+        return Err(SyncmiruError::UnprocessableEntity("size".to_string()))
+    }
+    let dest = uploads_conf.dir.join(&upload.name);
+    let finished = current + chunk.len() as i64 == upload.size;
+    if finished && tokio::fs::try_exists(&dest).await? {
+        return Err(SyncmiruError::Conflict("name".to_string()))
+    }
+
+    let mut f = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(&partial)
+        .await?;
+    f.write_all(chunk).await?;
+    f.flush().await?;
+    let new_offset = current + chunk.len() as i64;
+
+    if finished {
+        tokio::fs::rename(&partial, dest).await?;
+        query::set_upload_finished(&state.db, upload.id).await?;
+        state.listing_cache.invalidate(Some(&uploads_conf.source_name)).await;
+    }
+    Ok(UploadStatus { id: upload.id, name: upload.name, size: upload.size, offset: new_offset, finished })
+}
+
+async fn upload_status_of(uploads_conf: &UploadsConfig, upload: Upload) -> Result<UploadStatus> {
+    let offset = if upload.finished {
+        upload.size
+    }
+    else {
+        tokio::fs::metadata(partial_path(uploads_conf, upload.id))
+            .await
+            .map(|x| x.len() as i64)
+            .unwrap_or(0)
+    };
+    Ok(UploadStatus { id: upload.id, name: upload.name, size: upload.size, offset, finished: upload.finished })
+}
+
+fn partial_path(uploads_conf: &UploadsConfig, id: Id) -> PathBuf {
+    uploads_conf.dir
+        .join(constants::UPLOADS_PARTIAL_DIR)
+        .join(id.to_string())
+}
+
 pub async fn error(error: BoxError) -> impl IntoResponse {
     if error.is::<tower::timeout::error::Elapsed>() {
         return (StatusCode::REQUEST_TIMEOUT, Cow::from("request timed out"));
@@ -4,19 +4,23 @@ use indexmap::{IndexMap, IndexSet};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 use socketioxide::extract::{AckSender, Data, SocketRef, State};
-use tokio::time::Instant;
+use std::time::Duration;
+use chrono::Utc;
+use tokio::time::{sleep, Instant};
 use validator::Validate;
 use crate::models::{EmailWithLang, Tkn};
-use crate::models::query::{EmailTknType, Id, RegDetail, RegTkn, RoomClient, RoomSettings, RoomsClientWOrder};
-use crate::models::socketio::{IdStruct, Displayname, DisplaynameChange, SocketIoAck, EmailChangeTknType, EmailChangeTkn, ChangeEmail, AvatarBin, AvatarChange, Password, ChangePassword, Language, TknWithLang, RegTknCreate, RegTknName, PlaybackSpeed, DesyncTolerance, MajorDesyncMin, MinorDesyncPlaybackSlow, RoomName, RoomNameChange, RoomPlaybackSpeed, RoomDesyncTolerance, RoomMinorDesyncPlaybackSlow, RoomMajorDesyncMin, RoomOrder, JoinRoomReq, UserRoomChange, UserRoomJoin, UserRoomDisconnect, RoomPing, RoomUserPingChange, JoinedRoomInfo, GetFilesInfo, FileKind, AddVideoFiles, PlaylistEntryIdStruct, PlaylistOrder, AddUrls, UserReadyStateChangeReq, UserReadyStateChangeClient, AddEntryFilesResp, DeletePlaylistEntry, ChangePlaylistOrder, UploadMpvState, MpvState};
-use crate::{crypto, email, file, query};
+use crate::models::query::{Bookmark, Comment, DirectMessage, EmailTknType, Id, RegDetail, RegTkn, RoomCategory, RoomClient, RoomSettings, RoomsClientWOrder, UnreadCount};
+use crate::models::socketio::{IdStruct, Displayname, DisplaynameChange, SocketIoAck, EmailChangeTknType, EmailChangeTkn, ChangeEmail, AvatarBin, AvatarChange, Password, ChangePassword, Language, TknWithLang, RegTknCreate, RegTknName, PlaybackSpeed, DesyncTolerance, MajorDesyncMin, MinorDesyncPlaybackSlow, RoomName, RoomNameChange, RoomPlaybackSpeed, RoomDesyncTolerance, RoomMinorDesyncPlaybackSlow, RoomMajorDesyncMin, RoomOrder, JoinRoomReq, UserRoomChange, UserRoomJoin, UserRoomDisconnect, RoomPing, RoomUserPingChange, JoinedRoomInfo, GetFilesInfo, FileKind, FilesPage, InvalidateListingCache, SearchFiles, FoundFile, AddVideoFiles, AddDirectory, PlaylistEntryIdStruct, PlaylistOrder, AddUrls, UserReadyStateChangeReq, UserReadyStateChangeClient, AddEntryFilesResp, DeletePlaylistEntry, ChangePlaylistOrder, UploadMpvState, MpvState, UploadSubtitleStart, UploadSubtitleChunk, UploadSubtitleId, GetSubtitle, SubtitleInfo, SubtitleBin, PosterBin, ListedFile, RoomAutoNextEpisode, PlaylistEntryClip, ClipEnded, AddBookmark, BookmarkIdStruct, BookmarkAdded, BookmarkDeleted, GetComments, CommentsWindow, AddComment, CommentAdded, React, UserReaction, SendChatMessage, ChatMessage, CreatePoll, PollIdStruct, VotePoll, PollState, PollTallies, SendDirectMessage, GetDirectMessages, DirectMessagesRead, Presence, RoomPreview, RoomsWPreviews, RoomCategoryOrder, RoomCategoryName, RoomCategoryNameChange, RoomDescription, RoomTopic, RoomIconBin, RoomIconChange, CustomStatus};
+use crate::{constants, crypto, email, episode, file, probe, query, validators};
 use crate::handlers::timers::DesyncTimerInterface;
-use crate::models::file::FileInfo;
+use crate::poll::{Poll, PollId};
+use crate::models::file::{FileInfo, PlayingAccess, SubtitleAccess};
 use crate::handlers::utils;
-use crate::handlers::utils::{disconnect_from_room, video_id_in_room};
+use crate::handlers::utils::{activate_entry, disconnect_from_room, video_id_in_room};
 use crate::models::file::FileType;
-use crate::models::mpv::{UserChangeAudio, UserChangeAudioDelay, UserChangeAudioSync, UserChangeSub, UserChangeSubDelay, UserChangeSubSync, UserLoadedInfo, UserPause, UserPlayInfoClient, UserSeek, UserSpeedChange, UserUploadMpvState};
-use crate::srvstate::{PlayingState, PlaylistEntry, RoomPlayInfo, RoomRuntimeState, UserPlayInfo, UserReadyStatus, PlaylistEntryId, SrvState, TimestampInfo};
+use crate::file::VideoFile;
+use crate::models::mpv::{UserChangeAudio, UserChangeAudioDelay, UserChangeAudioSync, UserChangeSub, UserChangeSubDelay, UserChangeSubSync, UserLoadedInfo, UserPause, UserPlayInfoClient, UserSeek, UserSpeedChange, UserUploadMpvState, SetLoop, UserLoop};
+use crate::srvstate::{Clip, PlayingState, PlaylistEntry, RoomRuntimeState, UserPlayInfo, UserReadyStatus, PlaylistEntryId, SrvState, TimestampInfo, SubtitleId, SubtitleUpload, UploadedSubtitle, UserPresence};
 
 pub async fn ns_callback(State(state): State<Arc<SrvState>>, s: SocketRef) {
     s.on_disconnect(disconnect);
@@ -58,6 +62,11 @@ pub async fn ns_callback(State(state): State<Arc<SrvState>>, s: SocketRef) {
     s.on("create_room", create_room);
     s.on("get_rooms", get_rooms);
     s.on("set_room_name", set_room_name);
+    s.on("get_room_details", get_room_details);
+    s.on("set_room_description", set_room_description);
+    s.on("set_room_topic", set_room_topic);
+    s.on("set_room_icon", set_room_icon);
+    s.on("delete_room_icon", delete_room_icon);
     s.on("delete_room", delete_room);
     s.on("get_room_playback_speed", get_room_playback_speed);
     s.on("set_room_playback_speed", set_room_playback_speed);
@@ -67,7 +76,12 @@ pub async fn ns_callback(State(state): State<Arc<SrvState>>, s: SocketRef) {
     s.on("set_room_minor_desync_playback_slow", set_room_minor_desync_playback_slow);
     s.on("get_room_major_desync_min", get_room_major_desync_min);
     s.on("set_room_major_desync_min", set_room_major_desync_min);
+    s.on("get_room_auto_next_episode", get_room_auto_next_episode);
+    s.on("set_room_auto_next_episode", set_room_auto_next_episode);
     s.on("set_room_order", set_room_order);
+    s.on("create_room_category", create_room_category);
+    s.on("set_room_category_name", set_room_category_name);
+    s.on("delete_room_category", delete_room_category);
     s.on("ping", ping);
     s.on("join_room", join_room);
     s.on("disconnect_room", disconnect_room);
@@ -76,18 +90,24 @@ pub async fn ns_callback(State(state): State<Arc<SrvState>>, s: SocketRef) {
     s.on("get_sources", get_sources);
     s.on("get_files", get_files);
     s.on("add_video_files", add_video_files);
+    s.on("add_directory", add_directory);
+    s.on("invalidate_listing_cache", invalidate_listing_cache);
+    s.on("search_files", search_files);
     s.on("add_urls", add_urls);
     s.on("req_playing_jwt", req_playing_jwt);
     s.on("change_active_video", change_active_video);
     s.on("set_playlist_order", set_playlist_order);
+    s.on("set_playlist_entry_clip", set_playlist_entry_clip);
     s.on("delete_playlist_entry", delete_playlist_entry);
     s.on("mpv_file_loaded", mpv_file_loaded);
     s.on("mpv_file_load_failed", mpv_file_load_failed);
+    s.on("mpv_file_ended", mpv_file_ended);
     s.on("user_ready_state_change", user_ready_state_change);
     s.on("user_file_load_retry", user_file_load_retry);
     s.on("mpv_play", mpv_play);
     s.on("mpv_pause", mpv_pause);
     s.on("mpv_seek", mpv_seek);
+    s.on("set_loop", set_loop);
     s.on("mpv_speed_change", mpv_speed_change);
     s.on("change_audio_sync", change_audio_sync);
     s.on("change_sub_sync", change_sub_sync);
@@ -102,6 +122,33 @@ pub async fn ns_callback(State(state): State<Arc<SrvState>>, s: SocketRef) {
     s.on("user_change_sub_delay", user_change_sub_delay);
     s.on("timestamp_tick", timestamp_tick);
     s.on("get_mpv_state", get_mpv_state);
+    s.on("upload_subtitle_start", upload_subtitle_start);
+    s.on("upload_subtitle_chunk", upload_subtitle_chunk);
+    s.on("upload_subtitle_finish", upload_subtitle_finish);
+    s.on("get_entry_subtitles", get_entry_subtitles);
+    s.on("get_entry_poster", get_entry_poster);
+    s.on("get_subtitle", get_subtitle);
+    s.on("get_bookmarks", get_bookmarks);
+    s.on("add_bookmark", add_bookmark);
+    s.on("delete_bookmark", delete_bookmark);
+    s.on("get_comments", get_comments);
+    s.on("add_comment", add_comment);
+    s.on("react", react);
+    s.on("send_chat_message", send_chat_message);
+    s.on("get_poll", get_poll);
+    s.on("create_poll", create_poll);
+    s.on("vote_poll", vote_poll);
+    s.on("close_poll", close_poll);
+    s.on("send_direct_message", send_direct_message);
+    s.on("get_direct_messages", get_direct_messages);
+    s.on("get_direct_message_unread_counts", get_direct_message_unread_counts);
+    s.on("read_direct_messages", read_direct_messages);
+    s.on("get_blocked_users", get_blocked_users);
+    s.on("block_user", block_user);
+    s.on("unblock_user", unblock_user);
+    s.on("get_presence", get_presence);
+    s.on("set_idle", set_idle);
+    s.on("set_custom_status", set_custom_status);
 
     let uid = state.socket2uid(&s).await;
     let user = query::get_user(&state.db, uid)
@@ -110,6 +157,12 @@ pub async fn ns_callback(State(state): State<Arc<SrvState>>, s: SocketRef) {
 
     s.broadcast().emit("users", &user).ok();
     s.broadcast().emit("online", &uid).ok();
+
+    let custom_status = query::get_custom_status(&state.db, uid)
+        .await
+        .expect("db error");
+    state.uid2presence.write().await.insert(uid, UserPresence { idle: false, custom_status });
+    utils::spawn_presence_broadcast(&state, uid);
 }
 
 pub async fn disconnect(State(state): State<Arc<SrvState>>, s: SocketRef) {
@@ -143,8 +196,10 @@ pub async fn disconnect(State(state): State<Arc<SrvState>>, s: SocketRef) {
 
         let urd = UserRoomDisconnect { rid, uid };
         s.broadcast().emit("user_room_disconnect", &urd).ok();
+        utils::spawn_room_preview_broadcast(&state, vec![rid]);
     }
 
+    state.uid2presence.write().await.remove(&uid);
     s.broadcast().emit("offline", &uid).ok();
 }
 
@@ -987,6 +1042,9 @@ pub async fn create_room(
     let room = RoomClient {
         id: room_id,
         name: payload.room_name,
+        description: None,
+        topic: None,
+        icon: None
     };
     s.broadcast().emit("rooms", &[[&room]]).ok();
     s.emit("rooms", &[[&room]]).ok();
@@ -1009,9 +1067,16 @@ pub async fn get_rooms(
     let room_order = query::get_room_order_for_update(&mut transaction)
         .await
         .expect("db error");
+    let categories = query::get_room_categories_for_update(&mut transaction)
+        .await
+        .expect("db error");
 
-    let rooms_w_order = RoomsClientWOrder { room_order, rooms };
-    ack.send(&rooms_w_order).ok();
+    let mut previews = Vec::<RoomPreview>::new();
+    for room in &rooms {
+        previews.push(utils::room_preview(&state, room.id).await);
+    }
+    let rooms_w_order = RoomsClientWOrder { room_order, categories, rooms };
+    ack.send(&RoomsWPreviews { rooms: rooms_w_order, previews }).ok();
     transaction
         .commit()
         .await
@@ -1037,6 +1102,119 @@ pub async fn set_room_name(
     ack.send(&SocketIoAck::<()>::ok(None)).ok();
 }
 
+pub async fn get_room_details(
+    State(state): State<Arc<SrvState>>,
+    ack: AckSender,
+    Data(payload): Data<IdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<RoomClient>::err()).ok();
+        return;
+    }
+    let room_opt = query::get_room(&state.db, payload.id)
+        .await
+        .expect("db error");
+    if room_opt.is_none() {
+        ack.send(&SocketIoAck::<RoomClient>::err()).ok();
+        return;
+    }
+    ack.send(&SocketIoAck::<RoomClient>::ok(room_opt)).ok();
+}
+
+pub async fn set_room_description(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<RoomDescription>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let updated = query::set_room_description(&state.db, payload.id, payload.description.as_deref())
+        .await
+        .expect("db error");
+    if !updated {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    s.broadcast().emit("room_description", &payload).ok();
+    s.emit("room_description", &payload).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn set_room_topic(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<RoomTopic>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let updated = query::set_room_topic(&state.db, payload.id, payload.topic.as_deref())
+        .await
+        .expect("db error");
+    if !updated {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    s.broadcast().emit("room_topic", &payload).ok();
+    s.emit("room_topic", &payload).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn set_room_icon(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<RoomIconBin>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let updated = query::set_room_icon(&state.db, payload.id, Some(&payload.data))
+        .await
+        .expect("db error");
+    if !updated {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let icon_change = RoomIconChange { id: payload.id, icon: Some(payload.data) };
+    s.broadcast().emit("room_icon", &icon_change).ok();
+    s.emit("room_icon", &icon_change).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn delete_room_icon(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<IdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let updated = query::set_room_icon(&state.db, payload.id, None)
+        .await
+        .expect("db error");
+    if !updated {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let icon_change = RoomIconChange { id: payload.id, icon: None };
+    s.broadcast().emit("room_icon", &icon_change).ok();
+    s.emit("room_icon", &icon_change).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
 pub async fn delete_room(
     State(state): State<Arc<SrvState>>,
     s: SocketRef,
@@ -1066,6 +1244,9 @@ pub async fn delete_room(
         query::set_room_order(&mut transaction, &new_room_order)
             .await
             .expect("db error");
+        query::remove_room_from_categories(&mut transaction, payload.id)
+            .await
+            .expect("db error");
 
         let room_name = payload.id.to_string();
         {
@@ -1256,6 +1437,48 @@ pub async fn set_room_major_desync_min(
     ack.send(&SocketIoAck::<()>::ok(None)).ok();
 }
 
+pub async fn get_room_auto_next_episode(
+    State(state): State<Arc<SrvState>>,
+    ack: AckSender,
+    Data(payload): Data<IdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<bool>::err()).ok();
+        return;
+    }
+    let auto_next_episode_opt = query::get_room_auto_next_episode(&state.db, payload.id)
+        .await
+        .expect("db error");
+    if auto_next_episode_opt.is_none() {
+        ack.send(&SocketIoAck::<bool>::err()).ok();
+        return;
+    }
+    let auto_next_episode = auto_next_episode_opt.unwrap();
+    ack.send(&SocketIoAck::<bool>::ok(Some(auto_next_episode))).ok();
+}
+
+pub async fn set_room_auto_next_episode(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<RoomAutoNextEpisode>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let updated = query::set_room_auto_next_episode(&state.db, payload.id, payload.auto_next_episode)
+        .await
+        .expect("db error");
+
+    if !updated {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    s.broadcast().emit("room_auto_next_episode", &payload).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
 pub async fn set_room_order(
     State(state): State<Arc<SrvState>>,
     s: SocketRef,
@@ -1267,23 +1490,184 @@ pub async fn set_room_order(
         return;
     }
     let mut transaction = state.db.begin().await.expect("db error");
-    let valid = query::room_order_valid(&mut transaction, &payload.room_order)
+    let room_ids = payload.room_ids();
+    let rooms_unique = room_ids.iter().collect::<HashSet<&Id>>().len() == room_ids.len();
+    let rooms_valid = query::room_order_valid(&mut transaction, &room_ids)
+        .await
+        .expect("db error");
+    let room_count = query::room_count_for_update(&mut transaction)
+        .await
+        .expect("db error");
+
+    let mut category_ids = payload.category_ids();
+    let mut current_category_ids = query::get_category_order_for_update(&mut transaction)
         .await
         .expect("db error");
-    if !valid {
+    category_ids.sort();
+    current_category_ids.sort();
+
+    // every room and category has to be placed exactly once, moves between categories are one update
+    if !rooms_unique || !rooms_valid || room_count != room_ids.len() || category_ids != current_category_ids {
         ack.send(&SocketIoAck::<()>::err()).ok();
         return;
     }
     query::set_room_order(&mut transaction, &payload.room_order)
         .await
         .expect("db error");
+    for category in &payload.categories {
+        query::set_room_category_room_order(&mut transaction, category.id, &category.room_order)
+            .await
+            .expect("db error");
+    }
+    query::set_category_order(&mut transaction, &payload.category_ids())
+        .await
+        .expect("db error");
+    transaction
+        .commit()
+        .await
+        .expect("db error");
+
+    s.broadcast().emit("room_order", &payload).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn create_room_category(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<RoomCategoryName>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let mut transaction = state.db.begin().await.expect("db error");
+    // the locked settings row serializes category changes, so the name cannot be taken before the insert
+    let mut category_order = query::get_category_order_for_update(&mut transaction)
+        .await
+        .expect("db error");
+    let unique = query::room_category_name_unique(&mut transaction, &payload.name, None)
+        .await
+        .expect("db error");
+    if !unique {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let id = query::new_room_category(&mut transaction, &payload.name)
+        .await
+        .expect("db error");
+    category_order.push(id);
+    query::set_category_order(&mut transaction, &category_order)
+        .await
+        .expect("db error");
+    transaction
+        .commit()
+        .await
+        .expect("db error");
+
+    let category = RoomCategory { id, name: payload.name, room_order: vec![] };
+    s.broadcast().emit("room_categories", &[[&category]]).ok();
+    s.emit("room_categories", &[[&category]]).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn set_room_category_name(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<RoomCategoryNameChange>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let mut transaction = state.db.begin().await.expect("db error");
+    query::get_category_order_for_update(&mut transaction)
+        .await
+        .expect("db error");
+    let unique = query::room_category_name_unique(&mut transaction, &payload.name, Some(payload.id))
+        .await
+        .expect("db error");
+    if !unique {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let updated = query::set_room_category_name(&mut transaction, payload.id, &payload.name)
+        .await
+        .expect("db error");
+    if !updated {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    transaction
+        .commit()
+        .await
+        .expect("db error");
 
-    s.broadcast().emit("room_order", &[payload.room_order]).ok();
+    s.broadcast().emit("room_category_name_change", &payload).ok();
+    s.emit("room_category_name_change", &payload).ok();
     ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn delete_room_category(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<IdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let mut transaction = state.db.begin().await.expect("db error");
+    let mut room_order = query::get_room_order_for_update(&mut transaction)
+        .await
+        .expect("db error");
+    let category_opt = query::get_room_category_for_update(&mut transaction, payload.id)
+        .await
+        .expect("db error");
+    let Some(category) = category_opt else {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    };
+
+    // rooms of the deleted category become uncategorized, appended after the others
+    room_order.extend(category.room_order);
+    query::set_room_order(&mut transaction, &room_order)
+        .await
+        .expect("db error");
+    let category_order = query::get_category_order_for_update(&mut transaction)
+        .await
+        .expect("db error")
+        .into_iter()
+        .filter(|&x| x != payload.id)
+        .collect::<Vec<Id>>();
+    query::set_category_order(&mut transaction, &category_order)
+        .await
+        .expect("db error");
+    query::delete_room_category(&mut transaction, payload.id)
+        .await
+        .expect("db error");
+    let categories = query::get_room_categories_for_update(&mut transaction)
+        .await
+        .expect("db error");
     transaction
         .commit()
         .await
         .expect("db error");
+
+    let layout = RoomOrder {
+        room_order,
+        categories: categories
+            .into_iter()
+            .map(|x| RoomCategoryOrder { id: x.id, room_order: x.room_order })
+            .collect()
+    };
+    s.broadcast().emit("del_room_categories", &[[payload.id]]).ok();
+    s.emit("del_room_categories", &[[payload.id]]).ok();
+    s.broadcast().emit("room_order", &layout).ok();
+    s.emit("room_order", &layout).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
 }
 
 pub async fn ping(
@@ -1424,6 +1808,10 @@ pub async fn join_room(
         users_audio_sub
     }))).ok();
     transaction.commit().await.expect("db error");
+    utils::spawn_presence_broadcast(&state, uid);
+    let mut preview_rids = vec![payload.rid];
+    preview_rids.extend(old_connected_room_opt.filter(|&x| x != payload.rid));
+    utils::spawn_room_preview_broadcast(&state, preview_rids);
 }
 
 pub async fn disconnect_room(
@@ -1445,6 +1833,8 @@ pub async fn disconnect_room(
     s.broadcast().emit("user_room_disconnect", &urd).ok();
     s.emit("user_room_disconnect", &urd).ok();
     ack.send(&SocketIoAck::<()>::ok(None)).ok();
+    utils::spawn_presence_broadcast(&state, uid);
+    utils::spawn_room_preview_broadcast(&state, vec![rid]);
 }
 
 pub async fn get_room_users(
@@ -1494,17 +1884,17 @@ pub async fn get_files(
     Data(payload): Data<GetFilesInfo>,
 ) {
     if let Err(_) = payload.validate() {
-        ack.send(&SocketIoAck::<Vec<FileInfo>>::err()).ok();
+        ack.send(&SocketIoAck::<FilesPage>::err()).ok();
         return;
     }
     if !state.config.sources.contains_key(&payload.file_srv) {
-        ack.send(&SocketIoAck::<Vec<FileInfo>>::err()).ok();
+        ack.send(&SocketIoAck::<FilesPage>::err()).ok();
         return;
     }
     let source = state.config.sources.get(&payload.file_srv).unwrap();
-    let mut files = file::list(
-        &source.list_root_url,
-        &source.srv_jwt,
+    let mut files = state.listing_cache.list(
+        &payload.file_srv,
+        source,
         &payload.path,
     )
         .await
@@ -1524,29 +1914,107 @@ pub async fn get_files(
             .map(|x| x.clone())
             .collect::<Vec<FileInfo>>();
     }
-    ack.send(&SocketIoAck::<Vec<FileInfo>>::ok(Some(files))).ok();
+
+    let page_r = file::sort_filter_page(
+        files,
+        payload.sort_by,
+        payload.sort_desc,
+        payload.filter.as_deref(),
+        payload.cursor.as_deref(),
+        payload.limit
+    );
+    if page_r.is_err() {
+        ack.send(&SocketIoAck::<FilesPage>::err()).ok();
+        return;
+    }
+    let (files, next_cursor) = page_r.unwrap();
+    let files = files
+        .into_iter()
+        .map(|x| {
+            let episode = if x.file_type == FileType::File { episode::parse(&x.name) } else { None };
+            ListedFile { info: x, episode }
+        })
+        .collect();
+    ack.send(&SocketIoAck::<FilesPage>::ok(Some(FilesPage { files, next_cursor }))).ok();
 }
 
-pub async fn add_video_files(
+pub async fn search_files(
     State(state): State<Arc<SrvState>>,
-    s: SocketRef,
     ack: AckSender,
-    Data(payload): Data<AddVideoFiles>,
+    Data(payload): Data<SearchFiles>,
 ) {
-    let rid_opt = state.socket_connected_room(&s).await;
-    if rid_opt.is_none() {
-        ack.send(&SocketIoAck::<()>::err()).ok();
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<Vec<FoundFile>>::err()).ok();
         return;
     }
-    let rid = rid_opt.unwrap();
-    if let Err(_) = payload.validate() {
-        ack.send(&SocketIoAck::<()>::err()).ok();
+    if !state.config.indexer.enabled {
+        ack.send(&SocketIoAck::<Vec<FoundFile>>::err()).ok();
         return;
     }
-    let uid = state.socket2uid(&s).await;
+    let indexed_files = query::search_indexed_files(
+        &state.db,
+        &payload.query,
+        constants::SEARCH_FILES_MAX_RESULTS
+    )
+        .await
+        .expect("db error");
 
-    let mut v = Vec::<(&str, &str)>::new();
-    for full_path in &payload.full_paths {
+    let found_files = indexed_files
+        .into_iter()
+        .filter(|x| state.config.sources.contains_key(&x.source))
+        .map(|x| FoundFile {
+            full_path: format!("{}:{}", x.source, x.path),
+            source: x.source,
+            path: x.path,
+            name: x.name,
+            mtime: x.mtime,
+            size: x.size
+        })
+        .collect::<Vec<FoundFile>>();
+    ack.send(&SocketIoAck::<Vec<FoundFile>>::ok(Some(found_files))).ok();
+}
+
+pub async fn invalidate_listing_cache(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<InvalidateListingCache>,
+) {
+    let uid = state.socket2uid(&s).await;
+    if !state.is_admin(uid).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    if let Some(file_srv) = &payload.file_srv {
+        if !state.config.sources.contains_key(file_srv) {
+            ack.send(&SocketIoAck::<()>::err()).ok();
+            return;
+        }
+    }
+    state.listing_cache.invalidate(payload.file_srv.as_deref()).await;
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn add_video_files(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<AddVideoFiles>,
+) {
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+
+    let mut v = Vec::<(&str, VideoFile)>::new();
+    for full_path in &payload.full_paths {
         let (source, path) = full_path.split_once(":").unwrap();
         if !state.config.sources.contains_key(source) {
             ack.send(&SocketIoAck::<()>::err()).ok();
@@ -1554,8 +2022,9 @@ pub async fn add_video_files(
         }
         let source_info = state.config.sources.get(source).unwrap();
         let exists = file::f_exists(
-            &source_info.list_root_url,
-            &source_info.srv_jwt,
+            &state.listing_cache,
+            source,
+            source_info,
             path,
             &state.config.extensions.videos
         )
@@ -1565,24 +2034,100 @@ pub async fn add_video_files(
             ack.send(&SocketIoAck::<()>::err()).ok();
             return;
         }
-        v.push((source, path));
+        let video = file::find_sidecars(
+            &state.listing_cache,
+            source,
+            source_info,
+            path,
+            &state.config.extensions.subtitles
+        )
+            .await
+            .unwrap_or(VideoFile { path: path.to_string(), subtitles: Vec::new(), nfo: None, poster: None });
+        v.push((source, video));
+    }
+    let mut rid_video_id_wl = state.rid_video_id.write().await;
+    let mut playlist_wl = state.playlist.write().await;
+    let mut send_entries: IndexMap<PlaylistEntryId, PlaylistEntry> = IndexMap::new();
+    let mut to_read = Vec::<(PlaylistEntryId, String, VideoFile)>::new();
+    for (source, video) in v {
+        let entry_id = state.next_playlist_entry_id().await;
+        let entry = PlaylistEntry::Video { source: source.to_string(), path: video.path.clone(), subtitles: video.subtitles.clone(), metadata: None, media: None, clip: payload.clip };
+        send_entries.insert(entry_id, entry.clone());
+        playlist_wl.insert(entry_id, entry);
+        rid_video_id_wl.insert(rid, entry_id);
+        to_read.push((entry_id, source.to_string(), video));
+    }
+
+    s.within(rid.to_string()).emit("add_video_files", &AddEntryFilesResp { uid, entries: send_entries.clone() }).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+
+    drop(rid_video_id_wl);
+    drop(playlist_wl);
+    utils::spawn_read_metadata(&state, rid, to_read);
+    probe::spawn_probe(&state, rid, &send_entries);
+}
+
+pub async fn add_directory(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<AddDirectory>,
+) {
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    if !state.config.sources.contains_key(&payload.file_srv) {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+    let source_info = state.config.sources.get(&payload.file_srv).unwrap();
+    let paths_r = file::list_dir_files(
+        &state.listing_cache,
+        &payload.file_srv,
+        source_info,
+        &payload.path,
+        payload.recursive,
+        &state.config.extensions.videos,
+        &state.config.extensions.subtitles
+    ).await;
+    if paths_r.is_err() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let paths = paths_r.unwrap();
+    if paths.is_empty() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
     }
+
     let mut rid_video_id_wl = state.rid_video_id.write().await;
     let mut playlist_wl = state.playlist.write().await;
     let mut send_entries: IndexMap<PlaylistEntryId, PlaylistEntry> = IndexMap::new();
-    for (source, path) in v {
+    let mut to_read = Vec::<(PlaylistEntryId, String, VideoFile)>::new();
+    for video in paths {
         let entry_id = state.next_playlist_entry_id().await;
-        let entry = PlaylistEntry::Video { source: source.to_string(), path: path.to_string() };
+        let entry = PlaylistEntry::Video { source: payload.file_srv.clone(), path: video.path.clone(), subtitles: video.subtitles.clone(), metadata: None, media: None, clip: Clip::default() };
         send_entries.insert(entry_id, entry.clone());
         playlist_wl.insert(entry_id, entry);
         rid_video_id_wl.insert(rid, entry_id);
+        to_read.push((entry_id, payload.file_srv.clone(), video));
     }
 
-    s.within(rid.to_string()).emit("add_video_files", &AddEntryFilesResp { uid, entries: send_entries }).ok();
+    s.within(rid.to_string()).emit("add_video_files", &AddEntryFilesResp { uid, entries: send_entries.clone() }).ok();
     ack.send(&SocketIoAck::<()>::ok(None)).ok();
 
     drop(rid_video_id_wl);
     drop(playlist_wl);
+    utils::spawn_read_metadata(&state, rid, to_read);
+    probe::spawn_probe(&state, rid, &send_entries);
 }
 
 pub async fn add_urls(
@@ -1608,7 +2153,7 @@ pub async fn add_urls(
     let mut send_entries: IndexMap<PlaylistEntryId, PlaylistEntry> = IndexMap::new();
     for url in payload.urls {
         let entry_id = state.next_playlist_entry_id().await;
-        let entry = PlaylistEntry::Url { url };
+        let entry = PlaylistEntry::Url { url, clip: payload.clip };
         send_entries.insert(entry_id, entry.clone());
         playlist_wl.insert(entry_id, entry);
         rid_video_id_wl.insert(rid, entry_id);
@@ -1627,45 +2172,56 @@ pub async fn req_playing_jwt(
     Data(payload): Data<PlaylistEntryIdStruct>,
 ) {
     if let Err(_) = payload.validate() {
-        ack.send(&SocketIoAck::<String>::err()).ok();
+        ack.send(&SocketIoAck::<PlayingAccess>::err()).ok();
         return;
     }
     let rid_opt = state.socket_connected_room(&s).await;
     if rid_opt.is_none() {
-        ack.send(&SocketIoAck::<String>::err()).ok();
+        ack.send(&SocketIoAck::<PlayingAccess>::err()).ok();
         return;
     }
 
     let rid = rid_opt.unwrap();
     if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
-        ack.send(&SocketIoAck::<String>::err()).ok();
+        ack.send(&SocketIoAck::<PlayingAccess>::err()).ok();
         return;
     }
 
     let playlist_rl = state.playlist.read().await;
     let entry = playlist_rl.get(&payload.playlist_entry_id).unwrap();
     if let PlaylistEntry::Url { .. } = entry {
-        ack.send(&SocketIoAck::<String>::err()).ok();
+        ack.send(&SocketIoAck::<PlayingAccess>::err()).ok();
         return;
     }
     let s: &str;
     let p: &str;
+    let subs: &Vec<String>;
     match entry {
-        PlaylistEntry::Video { source, path } => {
+        PlaylistEntry::Video { source, path, subtitles, .. } => {
             s = source;
             p = path;
+            subs = subtitles;
         }
         _ => {
-            ack.send(&SocketIoAck::<String>::err()).ok();
+            ack.send(&SocketIoAck::<PlayingAccess>::err()).ok();
             return;
         }
     }
     let source = state.config.sources.get(s).unwrap();
-    let jwt = file::gen_access_jwt(source, p)
+    let file_access = file::file_access(source, p)
         .await
         .expect("jwt signer error");
+    let mut subtitles = Vec::<SubtitleAccess>::new();
+    for sub in subs {
+        let sub_access = file::file_access(source, sub)
+            .await
+            .expect("jwt signer error");
+        let name = sub.rsplit('/').next().unwrap_or(sub).to_string();
+        subtitles.push(SubtitleAccess { name, access: sub_access });
+    }
+    let access = PlayingAccess { file: file_access, subtitles };
 
-    ack.send(&SocketIoAck::<String>::ok(Some(jwt))).ok();
+    ack.send(&SocketIoAck::<PlayingAccess>::ok(Some(access))).ok();
 }
 
 pub async fn change_active_video(
@@ -1689,39 +2245,14 @@ pub async fn change_active_video(
         ack.send(&SocketIoAck::<()>::err()).ok();
         return;
     }
-    let mut rid2play_info_wl = state.rid2play_info.write().await;
-    let play_info_opt = rid2play_info_wl.get(&rid);
-    if play_info_opt.is_some() && play_info_opt.unwrap().playing_entry_id == payload.playlist_entry_id {
+    if !activate_entry(&state, rid, payload.playlist_entry_id).await {
         ack.send(&SocketIoAck::<()>::ok(None)).ok();
         return;
     }
-    state.clear_uid2play_info_by_rid(rid).await;
-    rid2play_info_wl.insert(
-        rid,
-        RoomPlayInfo {
-            playing_entry_id: payload.playlist_entry_id,
-            playing_state: PlayingState::Pause,
-            last_change_at: Instant::now()
-        }
-    );
-
-    let mut rid2runtime_state_wl = state.rid2runtime_state.write().await;
-    let room_runtime_state = rid2runtime_state_wl.get_mut(&rid).unwrap();
-    room_runtime_state.playback_speed = room_runtime_state.runtime_config.playback_speed;
-
-    let mut uid2ready_status_wl = state.uid2ready_status.write().await;
-    let mut uid2timestamp_wl = state.uid2timestamp.write().await;
-    let rid_uids_rl = state.rid_uids.read().await;
-    let uids = rid_uids_rl.get_by_left(&rid).unwrap();
-    for uid in uids {
-        uid2ready_status_wl.insert(*uid, UserReadyStatus::Loading);
-        uid2timestamp_wl.remove(uid);
-    }
 
     ack.send(&SocketIoAck::<()>::ok(None)).ok();
     s.within(rid.to_string()).emit("change_active_video", &payload.playlist_entry_id).ok();
-
-    drop(rid2play_info_wl);
+    utils::spawn_room_presence_broadcast(&state, rid);
 }
 
 pub async fn set_playlist_order(
@@ -1766,6 +2297,49 @@ pub async fn set_playlist_order(
     drop(rid_video_id_wl);
 }
 
+pub async fn set_playlist_entry_clip(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<PlaylistEntryClip>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let mut rid2play_info_wl = state.rid2play_info.write().await;
+    let mut playlist_wl = state.playlist.write().await;
+    match playlist_wl.get_mut(&payload.playlist_entry_id) {
+        Some(PlaylistEntry::Video { clip, .. } | PlaylistEntry::Url { clip, .. }) => *clip = payload.clip,
+        None => {
+            ack.send(&SocketIoAck::<()>::err()).ok();
+            return;
+        }
+    }
+    if let Some(play_info) = rid2play_info_wl.get_mut(&rid) {
+        if play_info.playing_entry_id == payload.playlist_entry_id {
+            play_info.clip_ended = false;
+        }
+    }
+
+    s.within(rid.to_string()).emit("playlist_entry_clip", &payload).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+
+    drop(playlist_wl);
+    drop(rid2play_info_wl);
+}
+
 pub async fn delete_playlist_entry(
     State(state): State<Arc<SrvState>>,
     s: SocketRef,
@@ -1799,17 +2373,141 @@ pub async fn delete_playlist_entry(
     }
     let mut rid2play_info_wl = state.rid2play_info.write().await;
 
+    let mut removed_playing = false;
     let room_play_info_opt = rid2play_info_wl.get(&rid);
     if let Some(room_play_info) = room_play_info_opt {
         if room_play_info.playing_entry_id == payload.playlist_entry_id {
             rid2play_info_wl.remove(&rid);
             state.clear_uid2play_info_by_rid(rid).await;
+            removed_playing = true;
         }
     }
+    drop(rid2play_info_wl);
+    let entry = state.playlist.read().await.get(&payload.playlist_entry_id).cloned();
     state.remove_video_entry(payload.playlist_entry_id).await;
 
     s.within(rid.to_string()).emit("del_playlist_entry", &DeletePlaylistEntry { uid, entry_id: payload.playlist_entry_id }).ok();
     ack.send(&SocketIoAck::<()>::ok(None)).ok();
+
+    if let (true, Some(e)) = (removed_playing, entry) {
+        utils::spawn_room_presence_broadcast(&state, rid);
+        queue_next_episode(&state, &s, rid, uid, &e).await;
+    }
+}
+
+pub async fn mpv_file_ended(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<PlaylistEntryIdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+
+    let room_uids = state.rid_uids
+        .read()
+        .await
+        .get_by_left(&rid)
+        .map(|x| x.iter().copied().collect::<Vec<Id>>())
+        .unwrap_or_default();
+    let mut loaded_uids = Vec::<Id>::new();
+    for room_uid in room_uids {
+        if state.user_file_loaded(room_uid).await {
+            loaded_uids.push(room_uid);
+        }
+    }
+    let mut rid2play_info_wl = state.rid2play_info.write().await;
+    let play_info_opt = rid2play_info_wl
+        .get_mut(&rid)
+        .filter(|x| x.playing_entry_id == payload.playlist_entry_id);
+    if play_info_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let play_info = play_info_opt.unwrap();
+    play_info.ended_uids.insert(uid);
+    // members with a slower player are still watching, the entry ends once everyone reached its end
+    let all_ended = loaded_uids.iter().all(|x| play_info.ended_uids.contains(x));
+    drop(rid2play_info_wl);
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+    if !all_ended {
+        return;
+    }
+
+    let entry_opt = state.playlist.read().await.get(&payload.playlist_entry_id).cloned();
+    if let Some(entry) = entry_opt {
+        queue_next_episode(&state, &s, rid, uid, &entry).await;
+    }
+}
+
+async fn queue_next_episode(
+    state: &Arc<SrvState>,
+    s: &SocketRef,
+    rid: Id,
+    uid: Id,
+    entry: &PlaylistEntry
+) {
+    let auto_next_episode = query::get_room_auto_next_episode(&state.db, rid)
+        .await
+        .expect("db error")
+        .unwrap_or(false);
+    if !auto_next_episode {
+        return;
+    }
+    let PlaylistEntry::Video { source, path, .. } = entry else {
+        return;
+    };
+    let source_info_opt = state.config.sources.get(source);
+    if source_info_opt.is_none() {
+        return;
+    }
+    let source_info = source_info_opt.unwrap();
+    let next_r = file::find_next_episode(
+        &state.listing_cache,
+        source,
+        source_info,
+        path,
+        &state.config.extensions.videos,
+        &state.config.extensions.subtitles
+    ).await;
+    let Ok(Some(video)) = next_r else {
+        return;
+    };
+
+    let mut rid_video_id_wl = state.rid_video_id.write().await;
+    let mut playlist_wl = state.playlist.write().await;
+    // several members usually reach the end at once, the episode is queued only by the first of them
+    let already_queued = rid_video_id_wl
+        .get_by_left(&rid)
+        .is_some_and(|ids| ids.iter().any(|id| matches!(
+            playlist_wl.get(id),
+            Some(PlaylistEntry::Video { source: s, path: p, .. }) if s == source && *p == video.path
+        )));
+    if already_queued {
+        return;
+    }
+    let entry_id = state.next_playlist_entry_id().await;
+    let next_entry = PlaylistEntry::Video { source: source.clone(), path: video.path.clone(), subtitles: video.subtitles.clone(), metadata: None, media: None, clip: Clip::default() };
+    let mut send_entries: IndexMap<PlaylistEntryId, PlaylistEntry> = IndexMap::new();
+    send_entries.insert(entry_id, next_entry.clone());
+    playlist_wl.insert(entry_id, next_entry);
+    rid_video_id_wl.insert(rid, entry_id);
+
+    s.within(rid.to_string()).emit("add_video_files", &AddEntryFilesResp { uid, entries: send_entries.clone() }).ok();
+
+    drop(rid_video_id_wl);
+    drop(playlist_wl);
+    utils::spawn_read_metadata(state, rid, vec![(entry_id, source.clone(), video)]);
+    probe::spawn_probe(state, rid, &send_entries);
 }
 
 pub async fn mpv_file_loaded(
@@ -1986,6 +2684,7 @@ pub async fn mpv_play(
             .emit("mpv_play", &uid).ok();
 
         ack.send(&SocketIoAck::<()>::ok(None)).ok();
+        utils::spawn_room_presence_broadcast(&state, rid);
         return;
     }
     ack.send(&SocketIoAck::<()>::err()).ok();
@@ -2034,6 +2733,7 @@ pub async fn mpv_pause(
             .emit("mpv_pause", &UserPause { uid, timestamp: payload }).ok();
 
         ack.send(&SocketIoAck::<()>::ok(None)).ok();
+        utils::spawn_room_presence_broadcast(&state, rid);
         return;
     }
     ack.send(&SocketIoAck::<()>::err()).ok();
@@ -2072,11 +2772,51 @@ pub async fn mpv_seek(
             }
             uid2timestamp_wl.insert(*uid, TimestampInfo { timestamp: payload, recv: now });
         }
+        if let Some(play_info) = state.rid2play_info.write().await.get_mut(&rid) {
+            play_info.clip_ended = false;
+        }
 
         s
             .within(rid.to_string())
             .emit("mpv_seek", &UserSeek { uid, timestamp: payload }).ok();
 
+        ack.send(&SocketIoAck::<()>::ok(None)).ok();
+        utils::spawn_room_presence_broadcast(&state, rid);
+        return;
+    }
+    ack.send(&SocketIoAck::<()>::err()).ok();
+}
+
+pub async fn set_loop(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<SetLoop>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+
+    if state.user_file_loaded(uid).await {
+        let mut rid2play_info_wl = state.rid2play_info.write().await;
+        let Some(play_info) = rid2play_info_wl.get_mut(&rid) else {
+            ack.send(&SocketIoAck::<()>::err()).ok();
+            return;
+        };
+        play_info.ab_loop = payload.ab_loop;
+
+        s
+            .within(rid.to_string())
+            .emit("loop", &UserLoop { uid, ab_loop: payload.ab_loop }).ok();
+
         ack.send(&SocketIoAck::<()>::ok(None)).ok();
         return;
     }
@@ -2394,17 +3134,85 @@ pub async fn timestamp_tick(
         ack.send(&SocketIoAck::<()>::err()).ok();
         return;
     }
+    let rid = rid_opt.unwrap();
     let uid = state.socket2uid(&s).await;
     if state.user_file_loaded(uid).await {
         let mut uid2timestamp_wl = state.uid2timestamp.write().await;
         uid2timestamp_wl.insert(uid, TimestampInfo{ timestamp: payload, recv: Instant::now() });
+        drop(uid2timestamp_wl);
 
         ack.send(&SocketIoAck::<()>::ok(None)).ok();
+        end_clip_if_reached(&state, &s, rid, uid, payload).await;
         return;
     }
     ack.send(&SocketIoAck::<()>::err()).ok();
 }
 
+/// Pauses the room at the end of the playing entry's clip, which is then handled as if the file ended
+async fn end_clip_if_reached(
+    state: &Arc<SrvState>,
+    s: &SocketRef,
+    rid: Id,
+    uid: Id,
+    timestamp: f64
+) {
+    let entry_id_opt = state.rid2play_info
+        .read()
+        .await
+        .get(&rid)
+        .filter(|x| !x.clip_ended)
+        .map(|x| x.playing_entry_id);
+    let Some(entry_id) = entry_id_opt else {
+        return;
+    };
+    let entry_opt = state.playlist.read().await.get(&entry_id).cloned();
+    let Some(entry) = entry_opt else {
+        return;
+    };
+    let Some(end) = entry.clip().end.filter(|end| timestamp >= *end) else {
+        return;
+    };
+
+    let mut uid2timestamp_wl = state.uid2timestamp.write().await;
+    let mut uid2minor_desync_wl = state.uid2minor_desync.write().await;
+    let rid_uids_rl = state.rid_uids.read().await;
+    let mut rid2play_info_wl = state.rid2play_info.write().await;
+    let play_info_opt = rid2play_info_wl.get_mut(&rid);
+    if play_info_opt.is_none() {
+        return;
+    }
+    let play_info = play_info_opt.unwrap();
+    if play_info.clip_ended || play_info.playing_entry_id != entry_id {
+        return;
+    }
+    let now = Instant::now();
+    play_info.clip_ended = true;
+    play_info.playing_state = PlayingState::Pause;
+    play_info.last_change_at = now;
+
+    let uids = rid_uids_rl.get_by_left(&rid).unwrap();
+    for uid in uids {
+        if uid2minor_desync_wl.remove(uid) {
+            let io_rl = state.io.read().await;
+            let io = io_rl.as_ref().unwrap();
+            if let Some(sid) = state.uid2sid(*uid).await {
+                if let Some(target_socket) = io.get_socket(sid) {
+                    target_socket.emit("minor_desync_stop", &{}).ok();
+                }
+            }
+        }
+        uid2timestamp_wl.insert(*uid, TimestampInfo { timestamp: end, recv: now });
+    }
+
+    s.within(rid.to_string()).emit("clip_ended", &ClipEnded { playlist_entry_id: entry_id, timestamp: end }).ok();
+
+    drop(rid2play_info_wl);
+    drop(rid_uids_rl);
+    drop(uid2minor_desync_wl);
+    drop(uid2timestamp_wl);
+    queue_next_episode(state, s, rid, uid, &entry).await;
+}
+
 pub async fn get_mpv_state(
     State(state): State<Arc<SrvState>>,
     s: SocketRef,
@@ -2450,7 +3258,13 @@ pub async fn get_mpv_state(
             .collect::<Vec<f64>>();
 
         timestamps.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let mut timestamp = *timestamps.first().unwrap_or(&0f64);
+        let clip_start = state.playlist
+            .read()
+            .await
+            .get(&play_info.playing_entry_id)
+            .and_then(|x| x.clip().start)
+            .unwrap_or(0f64);
+        let mut timestamp = *timestamps.first().unwrap_or(&clip_start);
         if play_info.playing_state == PlayingState::Play {
             timestamp += room_runtime_state.runtime_config.desync_tolerance.to_f64().unwrap()
         }
@@ -2459,9 +3273,919 @@ pub async fn get_mpv_state(
         ack.send(&SocketIoAck::<MpvState>::ok(Some(MpvState{
             timestamp,
             playing_state: play_info.playing_state,
-            playback_speed: room_runtime_state.playback_speed
+            playback_speed: room_runtime_state.playback_speed,
+            ab_loop: play_info.ab_loop
         }))).ok();
         return;
     }
     ack.send(&SocketIoAck::<MpvState>::err()).ok();
+}
+
+pub async fn upload_subtitle_start(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<UploadSubtitleStart>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<SubtitleId>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<SubtitleId>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<SubtitleId>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+
+    let entry_subtitles_rl = state.entry_subtitles.read().await;
+    let uploaded = entry_subtitles_rl
+        .get(&payload.playlist_entry_id)
+        .map(|x| x.len())
+        .unwrap_or(0);
+    drop(entry_subtitles_rl);
+    if uploaded >= constants::SUBTITLE_UPLOADS_MAX_PER_ENTRY {
+        ack.send(&SocketIoAck::<SubtitleId>::err()).ok();
+        return;
+    }
+
+    let mut subtitle_uploads_wl = state.subtitle_uploads.write().await;
+    let pending = subtitle_uploads_wl
+        .values()
+        .filter(|x| x.uid == uid)
+        .count();
+    if pending >= constants::SUBTITLE_UPLOADS_MAX_PENDING {
+        ack.send(&SocketIoAck::<SubtitleId>::err()).ok();
+        return;
+    }
+
+    let upload_id = state.next_subtitle_id().await;
+    subtitle_uploads_wl.insert(upload_id, SubtitleUpload {
+        uid,
+        playlist_entry_id: payload.playlist_entry_id,
+        name: payload.name,
+        size: payload.size,
+        data: Vec::with_capacity(payload.size)
+    });
+    ack.send(&SocketIoAck::<SubtitleId>::ok(Some(upload_id))).ok();
+}
+
+pub async fn upload_subtitle_chunk(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<UploadSubtitleChunk>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+
+    let mut subtitle_uploads_wl = state.subtitle_uploads.write().await;
+    let upload_opt = subtitle_uploads_wl.get_mut(&payload.upload_id);
+    if upload_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let upload = upload_opt.unwrap();
+    if upload.uid != uid {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    if upload.data.len() + payload.data.len() > upload.size {
+        subtitle_uploads_wl.remove(&payload.upload_id);
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    upload.data.extend_from_slice(&payload.data);
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn upload_subtitle_finish(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<UploadSubtitleId>,
+) {
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+
+    let mut subtitle_uploads_wl = state.subtitle_uploads.write().await;
+    let upload_opt = subtitle_uploads_wl.get(&payload.upload_id);
+    if upload_opt.is_none() || upload_opt.unwrap().uid != uid {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let upload = subtitle_uploads_wl.remove(&payload.upload_id).unwrap();
+    drop(subtitle_uploads_wl);
+
+    if upload.data.len() != upload.size
+        || validators::check_subtitle_data(&upload.name, &upload.data).is_err() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    if !video_id_in_room(&state, rid, upload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let mut entry_subtitles_wl = state.entry_subtitles.write().await;
+    let subtitles = entry_subtitles_wl
+        .entry(upload.playlist_entry_id)
+        .or_default();
+    if subtitles.len() >= constants::SUBTITLE_UPLOADS_MAX_PER_ENTRY {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let subtitle_info = SubtitleInfo {
+        playlist_entry_id: upload.playlist_entry_id,
+        id: payload.upload_id,
+        uid,
+        name: upload.name.clone()
+    };
+    subtitles.push(UploadedSubtitle {
+        id: payload.upload_id,
+        uid,
+        name: upload.name,
+        data: upload.data
+    });
+
+    s.within(rid.to_string()).emit("add_subtitle", &subtitle_info).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn get_entry_subtitles(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<PlaylistEntryIdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<Vec<SubtitleInfo>>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<Vec<SubtitleInfo>>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<Vec<SubtitleInfo>>::err()).ok();
+        return;
+    }
+
+    let entry_subtitles_rl = state.entry_subtitles.read().await;
+    let subtitles = entry_subtitles_rl
+        .get(&payload.playlist_entry_id)
+        .map(|x| x
+            .iter()
+            .map(|sub| SubtitleInfo {
+                playlist_entry_id: payload.playlist_entry_id,
+                id: sub.id,
+                uid: sub.uid,
+                name: sub.name.clone()
+            })
+            .collect::<Vec<SubtitleInfo>>()
+        )
+        .unwrap_or_default();
+    ack.send(&SocketIoAck::<Vec<SubtitleInfo>>::ok(Some(subtitles))).ok();
+}
+
+pub async fn get_subtitle(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<GetSubtitle>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<SubtitleBin>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<SubtitleBin>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<SubtitleBin>::err()).ok();
+        return;
+    }
+
+    let entry_subtitles_rl = state.entry_subtitles.read().await;
+    let subtitle_opt = entry_subtitles_rl
+        .get(&payload.playlist_entry_id)
+        .and_then(|x| x.iter().find(|sub| sub.id == payload.subtitle_id));
+    if subtitle_opt.is_none() {
+        ack.send(&SocketIoAck::<SubtitleBin>::err()).ok();
+        return;
+    }
+    let subtitle = subtitle_opt.unwrap();
+    ack.send(&SocketIoAck::<SubtitleBin>::ok(Some(SubtitleBin {
+        name: subtitle.name.clone(),
+        data: subtitle.data.clone()
+    }))).ok();
+}
+
+pub async fn get_entry_poster(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<PlaylistEntryIdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<PosterBin>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<PosterBin>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<PosterBin>::err()).ok();
+        return;
+    }
+
+    let playlist_rl = state.playlist.read().await;
+    let poster_opt = match playlist_rl.get(&payload.playlist_entry_id) {
+        Some(PlaylistEntry::Video { source, metadata: Some(metadata), .. }) => metadata
+            .poster
+            .as_ref()
+            .map(|x| (source.clone(), x.clone())),
+        _ => None
+    };
+    drop(playlist_rl);
+    if poster_opt.is_none() {
+        ack.send(&SocketIoAck::<PosterBin>::err()).ok();
+        return;
+    }
+    let (source_name, poster) = poster_opt.unwrap();
+    let source = state.config.sources.get(&source_name).unwrap();
+    let data_r = file::read(source, &poster, constants::POSTER_MAX_SIZE).await;
+    if data_r.is_err() {
+        ack.send(&SocketIoAck::<PosterBin>::err()).ok();
+        return;
+    }
+    ack.send(&SocketIoAck::<PosterBin>::ok(Some(PosterBin { data: data_r.unwrap() }))).ok();
+}
+
+async fn playlist_entry_media_key(state: &Arc<SrvState>, playlist_entry_id: PlaylistEntryId) -> Option<String> {
+    let playlist_rl = state.playlist.read().await;
+    playlist_rl.get(&playlist_entry_id).map(|x| x.media_key())
+}
+
+pub async fn get_bookmarks(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<PlaylistEntryIdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<Vec<Bookmark>>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<Vec<Bookmark>>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<Vec<Bookmark>>::err()).ok();
+        return;
+    }
+    let media_opt = playlist_entry_media_key(&state, payload.playlist_entry_id).await;
+    if media_opt.is_none() {
+        ack.send(&SocketIoAck::<Vec<Bookmark>>::err()).ok();
+        return;
+    }
+
+    let bookmarks = query::get_bookmarks(&state.db, rid, &media_opt.unwrap())
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<Vec<Bookmark>>::ok(Some(bookmarks))).ok();
+}
+
+pub async fn add_bookmark(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<AddBookmark>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<Id>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<Id>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<Id>::err()).ok();
+        return;
+    }
+    let media_opt = playlist_entry_media_key(&state, payload.playlist_entry_id).await;
+    if media_opt.is_none() {
+        ack.send(&SocketIoAck::<Id>::err()).ok();
+        return;
+    }
+
+    let uid = state.socket2uid(&s).await;
+    let label = payload.label.trim().to_string();
+    let id = query::new_bookmark(&state.db, rid, uid, &media_opt.unwrap(), payload.timestamp, &label)
+        .await
+        .expect("db error");
+
+    let bookmark_added = BookmarkAdded {
+        playlist_entry_id: payload.playlist_entry_id,
+        bookmark: Bookmark { id, timestamp: payload.timestamp, label, uid: Some(uid) }
+    };
+    s.within(rid.to_string()).emit("add_bookmark", &bookmark_added).ok();
+    ack.send(&SocketIoAck::<Id>::ok(Some(id))).ok();
+}
+
+pub async fn delete_bookmark(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<BookmarkIdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let media_opt = playlist_entry_media_key(&state, payload.playlist_entry_id).await;
+    if media_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let deleted = query::delete_bookmark(&state.db, payload.bookmark_id, rid, &media_opt.unwrap(), uid)
+        .await
+        .expect("db error");
+    if !deleted {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let bookmark_deleted = BookmarkDeleted {
+        playlist_entry_id: payload.playlist_entry_id,
+        bookmark_id: payload.bookmark_id
+    };
+    s.within(rid.to_string()).emit("del_bookmark", &bookmark_deleted).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn get_comments(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<GetComments>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<CommentsWindow>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<CommentsWindow>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<CommentsWindow>::err()).ok();
+        return;
+    }
+    let media_opt = playlist_entry_media_key(&state, payload.playlist_entry_id).await;
+    if media_opt.is_none() {
+        ack.send(&SocketIoAck::<CommentsWindow>::err()).ok();
+        return;
+    }
+
+    let to = payload.from + constants::COMMENTS_WINDOW;
+    let comments = query::get_comments(&state.db, &media_opt.unwrap(), payload.from, to, constants::COMMENTS_MAX_LOADED)
+        .await
+        .expect("db error");
+    let comments_window = CommentsWindow { from: payload.from, to, comments };
+    ack.send(&SocketIoAck::<CommentsWindow>::ok(Some(comments_window))).ok();
+}
+
+pub async fn add_comment(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<AddComment>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    if !video_id_in_room(&state, rid, payload.playlist_entry_id).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let media_opt = playlist_entry_media_key(&state, payload.playlist_entry_id).await;
+    if media_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let uid = state.socket2uid(&s).await;
+    if !state.comment_limiter.hit(uid).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let content = payload.content.trim().to_string();
+    let id = query::new_comment(&state.db, uid, &media_opt.unwrap(), payload.timestamp, &content)
+        .await
+        .expect("db error");
+
+    let comment_added = CommentAdded {
+        playlist_entry_id: payload.playlist_entry_id,
+        comment: Comment { id, timestamp: payload.timestamp, content, uid: Some(uid) }
+    };
+    s.within(rid.to_string()).emit("add_comment", &comment_added).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn react(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<React>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+    if !state.reaction_limiter.hit(uid).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let user_reaction = UserReaction { uid, reaction: payload.reaction };
+    s.within(rid.to_string()).emit("react", &user_reaction).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn send_chat_message(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<SendChatMessage>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+    if !state.chat_limiter.hit(uid).await {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let chat_message = ChatMessage { uid, content: payload.content.trim().to_string() };
+    s.within(rid.to_string()).emit("chat_message", &chat_message).ok();
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn get_poll(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+) {
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<Option<PollState>>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+
+    let rid2poll_rl = state.rid2poll.read().await;
+    let poll_state = rid2poll_rl
+        .get(&rid)
+        .map(|x| PollState { poll: x.info(), votes: x.votes_of(uid) });
+    ack.send(&SocketIoAck::<Option<PollState>>::ok(Some(poll_state))).ok();
+}
+
+pub async fn create_poll(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<CreatePoll>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<PollId>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<PollId>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+    for entry_id in payload.options.iter().filter_map(|x| x.playlist_entry_id) {
+        if !video_id_in_room(&state, rid, entry_id).await {
+            ack.send(&SocketIoAck::<PollId>::err()).ok();
+            return;
+        }
+    }
+
+    let mut rid2poll_wl = state.rid2poll.write().await;
+    if rid2poll_wl.get(&rid).is_some_and(|x| !x.closed) {
+        ack.send(&SocketIoAck::<PollId>::err()).ok();
+        return;
+    }
+    let poll_id = state.next_poll_id().await;
+    let closes_at = payload.duration.map(|x| Utc::now() + chrono::Duration::seconds(x as i64));
+    let poll = Poll::new(
+        poll_id,
+        uid,
+        payload.question.trim().to_string(),
+        payload.options,
+        payload.multi_choice,
+        payload.activate_winner,
+        closes_at
+    );
+    let info = poll.info();
+    rid2poll_wl.insert(rid, poll);
+    drop(rid2poll_wl);
+
+    if let Some(duration) = payload.duration {
+        tokio::spawn(close_poll_after(state.clone(), rid, poll_id, Duration::from_secs(duration)));
+    }
+
+    ack.send(&SocketIoAck::<PollId>::ok(Some(poll_id))).ok();
+    s.within(rid.to_string()).emit("poll", &info).ok();
+}
+
+pub async fn vote_poll(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<VotePoll>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+
+    let mut rid2poll_wl = state.rid2poll.write().await;
+    let poll_opt = rid2poll_wl.get_mut(&rid).filter(|x| x.id == payload.id);
+    if poll_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let poll = poll_opt.unwrap();
+    if !poll.vote(uid, &payload.choices) {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let tallies = PollTallies { id: poll.id, tallies: poll.tallies() };
+    drop(rid2poll_wl);
+
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+    s.within(rid.to_string()).emit("poll_tallies", &tallies).ok();
+}
+
+pub async fn close_poll(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<PollIdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid_opt = state.socket_connected_room(&s).await;
+    if rid_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let rid = rid_opt.unwrap();
+    let uid = state.socket2uid(&s).await;
+    let is_creator = state.rid2poll
+        .read()
+        .await
+        .get(&rid)
+        .is_some_and(|x| x.id == payload.id && x.uid == uid);
+    if !is_creator {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+
+    let closed_opt = utils::close_poll(&state, rid, payload.id).await;
+    if closed_opt.is_none() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let (info, activated) = closed_opt.unwrap();
+
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+    s.within(rid.to_string()).emit("poll_closed", &info).ok();
+    if let Some(entry_id) = activated {
+        s.within(rid.to_string()).emit("change_active_video", &entry_id).ok();
+        utils::spawn_room_presence_broadcast(&state, rid);
+    }
+}
+
+async fn close_poll_after(state: Arc<SrvState>, rid: Id, poll_id: PollId, duration: Duration) {
+    sleep(duration).await;
+    let closed_opt = utils::close_poll(&state, rid, poll_id).await;
+    if closed_opt.is_none() {
+        return;
+    }
+    let (info, activated) = closed_opt.unwrap();
+
+    let io_rl = state.io.read().await;
+    let io = io_rl.as_ref().unwrap();
+    io.within(rid.to_string()).emit("poll_closed", &info).ok();
+    if let Some(entry_id) = activated {
+        io.within(rid.to_string()).emit("change_active_video", &entry_id).ok();
+        utils::spawn_room_presence_broadcast(&state, rid);
+    }
+}
+
+pub async fn send_direct_message(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<SendDirectMessage>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<DirectMessage>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+    if payload.uid == uid {
+        ack.send(&SocketIoAck::<DirectMessage>::err()).ok();
+        return;
+    }
+    let exists = query::user_exists(&state.db, payload.uid)
+        .await
+        .expect("db error");
+    if !exists {
+        ack.send(&SocketIoAck::<DirectMessage>::err()).ok();
+        return;
+    }
+    let blocked = query::is_blocked_between(&state.db, uid, payload.uid)
+        .await
+        .expect("db error");
+    if blocked {
+        ack.send(&SocketIoAck::<DirectMessage>::err()).ok();
+        return;
+    }
+    if !state.direct_message_limiter.hit(uid).await {
+        ack.send(&SocketIoAck::<DirectMessage>::err()).ok();
+        return;
+    }
+
+    let message = query::new_direct_message(&state.db, uid, payload.uid, &payload.content)
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<DirectMessage>::ok(Some(message.clone()))).ok();
+
+    // offline recipients find the message among the unread ones after they connect
+    if let Some(sid) = state.uid2sid(payload.uid).await {
+        let io_rl = state.io.read().await;
+        let io = io_rl.as_ref().unwrap();
+        if let Some(target_socket) = io.get_socket(sid) {
+            target_socket.emit("direct_message", &message).ok();
+        }
+    }
+}
+
+pub async fn get_direct_messages(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<GetDirectMessages>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<Vec<DirectMessage>>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+    let messages = query::get_direct_messages(
+        &state.db,
+        uid,
+        payload.uid,
+        payload.before_id,
+        constants::DIRECT_MESSAGES_PAGE_SIZE
+    )
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<Vec<DirectMessage>>::ok(Some(messages))).ok();
+}
+
+pub async fn get_direct_message_unread_counts(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+) {
+    let uid = state.socket2uid(&s).await;
+    let counts = query::get_unread_counts(&state.db, uid)
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<Vec<UnreadCount>>::ok(Some(counts))).ok();
+}
+
+pub async fn read_direct_messages(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<IdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+    let read_count = query::set_direct_messages_read(&state.db, uid, payload.id)
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+
+    if read_count > 0 {
+        if let Some(sid) = state.uid2sid(payload.id).await {
+            let io_rl = state.io.read().await;
+            let io = io_rl.as_ref().unwrap();
+            if let Some(target_socket) = io.get_socket(sid) {
+                target_socket.emit("direct_messages_read", &DirectMessagesRead { uid }).ok();
+            }
+        }
+    }
+}
+
+pub async fn get_blocked_users(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+) {
+    let uid = state.socket2uid(&s).await;
+    let blocked = query::get_blocked_users(&state.db, uid)
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<Vec<Id>>::ok(Some(blocked))).ok();
+}
+
+pub async fn block_user(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<IdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+    if payload.id == uid {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let exists = query::user_exists(&state.db, payload.id)
+        .await
+        .expect("db error");
+    if !exists {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    query::block_user(&state.db, uid, payload.id)
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn unblock_user(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<IdStruct>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+    query::unblock_user(&state.db, uid, payload.id)
+        .await
+        .expect("db error");
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+}
+
+pub async fn get_presence(
+    State(state): State<Arc<SrvState>>,
+    ack: AckSender,
+) {
+    let uids = state.socket_uid
+        .read()
+        .await
+        .right_values()
+        .copied()
+        .collect::<Vec<Id>>();
+    let mut presences = Vec::<Presence>::new();
+    for uid in uids {
+        presences.push(utils::user_presence(&state, uid).await);
+    }
+    ack.send(&SocketIoAck::<Vec<Presence>>::ok(Some(presences))).ok();
+}
+
+pub async fn set_idle(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<bool>,
+) {
+    let uid = state.socket2uid(&s).await;
+    {
+        let mut uid2presence_wl = state.uid2presence.write().await;
+        let presence = uid2presence_wl.entry(uid).or_default();
+        if presence.idle == payload {
+            ack.send(&SocketIoAck::<()>::ok(None)).ok();
+            return;
+        }
+        presence.idle = payload;
+    }
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+    utils::spawn_presence_broadcast(&state, uid);
+}
+
+pub async fn set_custom_status(
+    State(state): State<Arc<SrvState>>,
+    s: SocketRef,
+    ack: AckSender,
+    Data(payload): Data<CustomStatus>,
+) {
+    if let Err(_) = payload.validate() {
+        ack.send(&SocketIoAck::<()>::err()).ok();
+        return;
+    }
+    let uid = state.socket2uid(&s).await;
+    let custom_status = payload.custom_status
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty());
+    query::set_custom_status(&state.db, uid, custom_status.as_deref())
+        .await
+        .expect("db error");
+    state.uid2presence.write().await.entry(uid).or_default().custom_status = custom_status;
+
+    ack.send(&SocketIoAck::<()>::ok(None)).ok();
+    utils::spawn_presence_broadcast(&state, uid);
 }
\ No newline at end of file
@@ -61,6 +61,11 @@ async fn desync_timer(state: Arc<SrvState>) {
                 if play_info_opt.is_none() {
                     continue;
                 }
+                let ab_loop = play_info_opt.unwrap().ab_loop;
+                let distance = |from: f64, to: f64| match ab_loop {
+                    Some(l) => l.distance(from, to),
+                    None => to - from
+                };
 
                 let mut relevant_uids = Vec::<Id>::new();
                 for uid in uids {
@@ -70,7 +75,7 @@ async fn desync_timer(state: Arc<SrvState>) {
                         }
                     }
                 }
-                let mut timestamps = relevant_uids
+                let timestamps = relevant_uids
                     .iter()
                     .map(|uid| state.get_compensated_timestamp_of_uid(
                         *uid,
@@ -83,9 +88,16 @@ async fn desync_timer(state: Arc<SrvState>) {
                     .filter_map(|x| x)
                     .collect::<Vec<f64>>();
 
-                timestamps.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                if !timestamps.is_empty() {
-                    let smallest_timestamp = timestamps.first().unwrap();
+                // the member everyone else is ahead of, within a loop the smallest timestamp may be ahead of those before the wrap
+                let max_ahead = |from: f64| timestamps
+                    .iter()
+                    .map(|x| distance(from, *x))
+                    .fold(0f64, f64::max);
+                let reference_timestamp_opt = timestamps
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| max_ahead(*a).total_cmp(&max_ahead(*b)));
+                if let Some(reference_timestamp) = reference_timestamp_opt {
 
                     for uid in uids {
                         let compensated_timestamp_opt = state.get_compensated_timestamp_of_uid(
@@ -102,7 +114,7 @@ async fn desync_timer(state: Arc<SrvState>) {
                         let compensated_timestamp = compensated_timestamp_opt.unwrap();
                         let timestamp_info_opt = uid2timestamp_wl.get_mut(uid);
                         if let Some(timestamp_info) = timestamp_info_opt {
-                            if compensated_timestamp - smallest_timestamp >= major_desync_min_f64 {
+                            if distance(reference_timestamp, compensated_timestamp) >= major_desync_min_f64 {
                                 let io_rl = state.io.read().await;
                                 let io = io_rl.as_ref().unwrap();
                                 if let Some(sid) = state.uid2sid(*uid).await {
@@ -111,13 +123,13 @@ async fn desync_timer(state: Arc<SrvState>) {
                                             s.emit("minor_desync_stop", &{}).ok();
                                         }
 
-                                        s.emit("major_desync_seek", smallest_timestamp).ok();
-                                        timestamp_info.timestamp = *smallest_timestamp;
+                                        s.emit("major_desync_seek", &reference_timestamp).ok();
+                                        timestamp_info.timestamp = reference_timestamp;
                                     }
                                 }
                             }
                             else if uid2minor_desync_wl.contains(&uid) {
-                                let curr_diff = compensated_timestamp - smallest_timestamp;
+                                let curr_diff = distance(reference_timestamp, compensated_timestamp);
                                 let next_tick_in = constants::DESYNC_TIMER_TICK_MS as f64 / 1000f64;
 
                                 if curr_diff < next_tick_in {
@@ -132,7 +144,7 @@ async fn desync_timer(state: Arc<SrvState>) {
                                 }
                             }
                             else {
-                                if compensated_timestamp - smallest_timestamp >= desync_tolerance_f64 {
+                                if distance(reference_timestamp, compensated_timestamp) >= desync_tolerance_f64 {
                                     let io_rl = state.io.read().await;
                                     let io = io_rl.as_ref().unwrap();
                                     if let Some(sid) = state.uid2sid(*uid).await {
@@ -1,13 +1,23 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
+use axum::http::header::AUTHORIZATION;
+use axum::http::HeaderMap;
 use rand::Rng;
 use socketioxide::extract::SocketRef;
+use tokio::task::JoinSet;
+use tokio::time::{sleep, Instant};
+use validator::Validate;
 use crate::config::Rate;
+use crate::error::SyncmiruError;
 use crate::models::query::{EmailTknType, Id};
-use crate::models::socketio::{EmailChangeTkn, EmailChangeTknType};
-use crate::{crypto, query};
+use crate::models::file::EntryMetadata;
+use crate::models::socketio::{EmailChangeTkn, EmailChangeTknType, LoginTkns, PlaylistEntryMetadata, Presence, PresenceStatus, RoomPreview, Watching};
+use crate::{constants, crypto, file, query, tkn};
+use crate::file::VideoFile;
 use crate::handlers::timers::DesyncTimerInterface;
-use crate::srvstate::{PlaylistEntryId, SrvState};
+use crate::poll::{PollId, PollInfo};
+use crate::srvstate::{PlayingState, PlaylistEntry, PlaylistEntryId, RoomPlayInfo, SrvState, UserReadyStatus};
 use crate::result::Result;
 
 pub(super) async fn check_email_tkn_within_quota(
@@ -151,6 +161,9 @@ pub(super) async fn disconnect_from_room(
     uid2timestamp_lock.remove(&uid);
     uid2minor_desync_lock.remove(&uid);
 
+    let mut subtitle_uploads_wl = state.subtitle_uploads.write().await;
+    subtitle_uploads_wl.retain(|_, upload| upload.uid != uid);
+    drop(subtitle_uploads_wl);
 
     if rid_uids_lock.get_by_left(&rid).is_none() {
         // last user disconnect
@@ -159,11 +172,14 @@ pub(super) async fn disconnect_from_room(
         let mut rid2play_info_wl = state.rid2play_info.write().await;
         let mut rid2runtime_state_wl = state.rid2runtime_state.write().await;
 
+        let mut entry_subtitles_wl = state.entry_subtitles.write().await;
+
         let video_ids_opt = rid_video_id_wl.get_by_left(&rid);
         if let Some(video_ids) = video_ids_opt {
             for video_id in video_ids {
                 // remove video from playlist
                 playlist_wl.remove(video_id);
+                entry_subtitles_wl.remove(video_id);
             }
         }
 
@@ -174,6 +190,7 @@ pub(super) async fn disconnect_from_room(
         rid_video_id_wl.remove_by_left(&rid);
         rid2play_info_wl.remove(&rid);
         rid2runtime_state_wl.remove(&rid);
+        state.rid2poll.write().await.remove(&rid);
     }
 }
 
@@ -185,4 +202,293 @@ pub(super) async fn video_id_in_room(
     let rid2video_id_rl = state.rid_video_id.read().await;
     let rid_of_entry_opt = rid2video_id_rl.get_by_right(&playlist_entry_id);
     rid_of_entry_opt.is_some() && *rid_of_entry_opt.unwrap() == rid
+}
+
+/// Makes the entry the one played in the room, returns false if it is already played or the room is empty
+pub(super) async fn activate_entry(
+    state: &Arc<SrvState>,
+    rid: Id,
+    playlist_entry_id: PlaylistEntryId
+) -> bool {
+    let mut rid2play_info_wl = state.rid2play_info.write().await;
+    let play_info_opt = rid2play_info_wl.get(&rid);
+    if play_info_opt.is_some() && play_info_opt.unwrap().playing_entry_id == playlist_entry_id {
+        return false
+    }
+    state.clear_uid2play_info_by_rid(rid).await;
+    rid2play_info_wl.insert(
+        rid,
+        RoomPlayInfo {
+            playing_entry_id: playlist_entry_id,
+            playing_state: PlayingState::Pause,
+            last_change_at: Instant::now(),
+            clip_ended: false,
+            ab_loop: None,
+            ended_uids: HashSet::new()
+        }
+    );
+
+    let mut rid2runtime_state_wl = state.rid2runtime_state.write().await;
+    let Some(room_runtime_state) = rid2runtime_state_wl.get_mut(&rid) else {
+        // everyone left in the meantime, e.g. while a poll was running
+        rid2play_info_wl.remove(&rid);
+        return false
+    };
+    room_runtime_state.playback_speed = room_runtime_state.runtime_config.playback_speed;
+
+    let mut uid2ready_status_wl = state.uid2ready_status.write().await;
+    let mut uid2timestamp_wl = state.uid2timestamp.write().await;
+    let rid_uids_rl = state.rid_uids.read().await;
+    let Some(uids) = rid_uids_rl.get_by_left(&rid) else {
+        rid2play_info_wl.remove(&rid);
+        return false
+    };
+    for uid in uids {
+        uid2ready_status_wl.insert(*uid, UserReadyStatus::Loading);
+        uid2timestamp_wl.remove(uid);
+    }
+    true
+}
+
+/// Closes the open poll of the room and activates the winning entry if the poll asks for it,
+/// returns the closed poll and the activated entry
+pub(super) async fn close_poll(
+    state: &Arc<SrvState>,
+    rid: Id,
+    poll_id: PollId
+) -> Option<(PollInfo, Option<PlaylistEntryId>)> {
+    let mut rid2poll_wl = state.rid2poll.write().await;
+    let poll = rid2poll_wl.get_mut(&rid).filter(|x| x.id == poll_id && !x.closed)?;
+    poll.closed = true;
+    let info = poll.info();
+    drop(rid2poll_wl);
+
+    let winner_entry_id = info.winner
+        .filter(|_| info.activate_winner)
+        .and_then(|x| info.options[x].playlist_entry_id);
+    let mut activated = None;
+    if let Some(entry_id) = winner_entry_id {
+        if video_id_in_room(state, rid, entry_id).await && activate_entry(state, rid, entry_id).await {
+            activated = Some(entry_id);
+        }
+    }
+    Some((info, activated))
+}
+
+/// Presence of an online user, what the user watches is taken from the room the user is in
+pub(super) async fn user_presence(state: &Arc<SrvState>, uid: Id) -> Presence {
+    let presence = state.uid2presence.read().await.get(&uid).cloned().unwrap_or_default();
+    let rid_opt = state.rid_uids.read().await.get_by_right(&uid).copied();
+    let mut watching: Option<Watching> = None;
+    if let Some(rid) = rid_opt {
+        let uid2timestamp_rl = state.uid2timestamp.read().await;
+        let rid2play_info_rl = state.rid2play_info.read().await;
+        let uid_ping_rl = state.uid_ping.read().await;
+        let rid2runtime_state_rl = state.rid2runtime_state.read().await;
+        if let Some(play_info) = rid2play_info_rl.get(&rid) {
+            let title_opt = state.playlist
+                .read()
+                .await
+                .get(&play_info.playing_entry_id)
+                .map(|x| x.title());
+            if let Some(title) = title_opt {
+                let position = state.get_compensated_timestamp_of_uid(
+                    uid,
+                    rid,
+                    &uid2timestamp_rl,
+                    &rid2play_info_rl,
+                    &uid_ping_rl,
+                    &rid2runtime_state_rl
+                );
+                watching = Some(Watching {
+                    rid,
+                    playlist_entry_id: play_info.playing_entry_id,
+                    title,
+                    position,
+                    playing_state: play_info.playing_state
+                });
+            }
+        }
+    }
+    Presence {
+        uid,
+        status: if presence.idle { PresenceStatus::Idle } else { PresenceStatus::Online },
+        custom_status: presence.custom_status,
+        watching
+    }
+}
+
+/// Position of the room is the median of its members, a single desynced client does not skew it
+pub(super) async fn room_preview(state: &Arc<SrvState>, rid: Id) -> RoomPreview {
+    let members = state.rid_uids
+        .read()
+        .await
+        .get_by_left(&rid)
+        .map(|x| x.iter().copied().collect::<Vec<Id>>())
+        .unwrap_or_default();
+    let mut watching: Option<Watching> = None;
+    let uid2timestamp_rl = state.uid2timestamp.read().await;
+    let rid2play_info_rl = state.rid2play_info.read().await;
+    let uid_ping_rl = state.uid_ping.read().await;
+    let rid2runtime_state_rl = state.rid2runtime_state.read().await;
+    if let Some(play_info) = rid2play_info_rl.get(&rid) {
+        let title_opt = state.playlist
+            .read()
+            .await
+            .get(&play_info.playing_entry_id)
+            .map(|x| x.title());
+        if let Some(title) = title_opt {
+            let mut positions = members
+                .iter()
+                .filter_map(|&uid| state.get_compensated_timestamp_of_uid(
+                    uid,
+                    rid,
+                    &uid2timestamp_rl,
+                    &rid2play_info_rl,
+                    &uid_ping_rl,
+                    &rid2runtime_state_rl
+                ))
+                .collect::<Vec<f64>>();
+            positions.sort_by(|a, b| a.total_cmp(b));
+            watching = Some(Watching {
+                rid,
+                playlist_entry_id: play_info.playing_entry_id,
+                title,
+                position: positions.get(positions.len() / 2).copied(),
+                playing_state: play_info.playing_state
+            });
+        }
+    }
+    RoomPreview { rid, members, watching }
+}
+
+async fn broadcast_room_preview(state: Arc<SrvState>, rids: Vec<Id>) {
+    let mut previews = Vec::<RoomPreview>::new();
+    for rid in rids {
+        previews.push(room_preview(&state, rid).await);
+    }
+    let io_rl = state.io.read().await;
+    let io = io_rl.as_ref().unwrap();
+    io.emit("room_previews", &previews).ok();
+}
+
+async fn broadcast_presence(state: Arc<SrvState>, uids: Vec<Id>) {
+    let mut presences = Vec::<Presence>::new();
+    for uid in uids {
+        presences.push(user_presence(&state, uid).await);
+    }
+    if presences.is_empty() {
+        return;
+    }
+    let io_rl = state.io.read().await;
+    let io = io_rl.as_ref().unwrap();
+    io.emit("presence", &presences).ok();
+}
+
+/// Reads at most METADATA_READ_CONCURRENCY .nfo files at once, each entry is updated as soon as its file is read
+async fn read_entries_metadata(state: Arc<SrvState>, rid: Id, entries: Vec<(PlaylistEntryId, String, VideoFile)>) {
+    let mut tasks = JoinSet::new();
+    for (entry_id, source_name, video) in entries {
+        if video.nfo.is_none() && video.poster.is_none() {
+            continue
+        }
+        if tasks.len() >= constants::METADATA_READ_CONCURRENCY {
+            if let Some(Ok(Some((id, metadata)))) = tasks.join_next().await {
+                set_entry_metadata(&state, rid, id, metadata).await;
+            }
+        }
+        let state = state.clone();
+        tasks.spawn(async move {
+            let source = state.config.sources.get(&source_name)?;
+            file::read_metadata(source, &video).await.map(|x| (entry_id, x))
+        });
+    }
+    while let Some(r) = tasks.join_next().await {
+        if let Ok(Some((id, metadata))) = r {
+            set_entry_metadata(&state, rid, id, metadata).await;
+        }
+    }
+}
+
+async fn set_entry_metadata(state: &Arc<SrvState>, rid: Id, entry_id: PlaylistEntryId, metadata: EntryMetadata) {
+    let mut playlist_wl = state.playlist.write().await;
+    if let Some(PlaylistEntry::Video { metadata: entry_metadata, .. }) = playlist_wl.get_mut(&entry_id) {
+        *entry_metadata = Some(metadata.clone());
+    }
+    else {
+        return
+    }
+    drop(playlist_wl);
+
+    let io_rl = state.io.read().await;
+    let io = io_rl.as_ref().unwrap();
+    io.within(rid.to_string())
+        .emit("playlist_entry_metadata", &PlaylistEntryMetadata { playlist_entry_id: entry_id, metadata })
+        .ok();
+}
+
+async fn broadcast_room_presence(state: Arc<SrvState>, rid: Id) {
+    let uids = state.rid_uids
+        .read()
+        .await
+        .get_by_left(&rid)
+        .map(|x| x.iter().copied().collect::<Vec<Id>>())
+        .unwrap_or_default();
+    broadcast_presence(state.clone(), uids).await;
+    broadcast_room_preview(state, vec![rid]).await;
+}
+
+/// Presence is computed in a separate task, handlers may still hold the locks it reads
+pub(super) fn spawn_presence_broadcast(state: &Arc<SrvState>, uid: Id) {
+    tokio::spawn(broadcast_presence(state.clone(), vec![uid]));
+}
+
+pub(super) fn spawn_room_preview_broadcast(state: &Arc<SrvState>, rids: Vec<Id>) {
+    tokio::spawn(broadcast_room_preview(state.clone(), rids));
+}
+
+/// Playback changes come in bursts, changes of a room within PRESENCE_BROADCAST_INTERVAL are sent in one broadcast
+pub(super) fn spawn_room_presence_broadcast(state: &Arc<SrvState>, rid: Id) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        if !state.rid_presence_broadcast_pending.lock().await.insert(rid) {
+            return;
+        }
+        sleep(constants::PRESENCE_BROADCAST_INTERVAL).await;
+        state.rid_presence_broadcast_pending.lock().await.remove(&rid);
+        broadcast_room_presence(state, rid).await;
+    });
+}
+
+pub(super) fn spawn_read_metadata(state: &Arc<SrvState>, rid: Id, entries: Vec<(PlaylistEntryId, String, VideoFile)>) {
+    if !entries.is_empty() {
+        tokio::spawn(read_entries_metadata(state.clone(), rid, entries));
+    }
+}
+
+pub(super) fn bearer_tkn(headers: &HeaderMap) -> Result<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.strip_prefix("Bearer "))
+        .ok_or(SyncmiruError::AuthError)
+}
+
+pub(super) async fn http_auth(state: &Arc<SrvState>, headers: &HeaderMap) -> Result<Id> {
+    let jwt = bearer_tkn(headers)?;
+    let hwid_hash = headers
+        .get(constants::HWID_HASH_HEADER)
+        .and_then(|x| x.to_str().ok())
+        .ok_or(SyncmiruError::AuthError)?;
+    let login_tkns = LoginTkns { hwid_hash: hwid_hash.to_string(), jwt: jwt.to_string() };
+    if login_tkns.validate().is_err() {
+        return Err(SyncmiruError::AuthError)
+    }
+    let (valid, uid) = tkn::login_jwt_check(&login_tkns, &state.config.login_jwt, &state.db)
+        .await
+        .map_err(|_| SyncmiruError::AuthError)?;
+    if !valid {
+        return Err(SyncmiruError::AuthError)
+    }
+    Ok(uid.unwrap())
 }
\ No newline at end of file
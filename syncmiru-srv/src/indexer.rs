@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::anyhow;
+use chrono::Utc;
+use log::{info, warn};
+use tokio::time::sleep;
+use crate::{constants, file, query, Result};
+use crate::config::{Source, SourceKind};
+use crate::error::SyncmiruError;
+use crate::file::local;
+use crate::models::file::FileType;
+use crate::models::query::IndexedFile;
+use crate::srvstate::SrvState;
+
+pub async fn indexer(state: Arc<SrvState>) {
+    let source_names = state.config.sources
+        .keys()
+        .cloned()
+        .collect::<Vec<String>>();
+    query::delete_indexed_files_not_in_sources(&state.db, &source_names)
+        .await
+        .expect("db error");
+
+    loop {
+        for (source_name, source) in &state.config.sources {
+            index_source(&state, source_name, source).await;
+        }
+        sleep(Duration::from_secs(state.config.indexer.refresh_interval)).await;
+    }
+}
+
+async fn index_source(state: &SrvState, source_name: &str, source: &Source) {
+    let indexed_at = Utc::now();
+    let files_r = crawl(source_name, source, &state.config.extensions.videos).await;
+    if let Err(e) = files_r {
+        warn!("Indexing of source {} failed, keeping the previous index: {}", source_name, e);
+        return;
+    }
+    let files = files_r.unwrap();
+
+    let mut transaction = state.db.begin().await.expect("db error");
+    for batch in files.chunks(constants::INDEXER_BATCH_SIZE) {
+        query::upsert_indexed_files(&mut transaction, source_name, batch, &indexed_at)
+            .await
+            .expect("db error");
+    }
+    query::delete_indexed_files_older_than(&mut transaction, source_name, &indexed_at)
+        .await
+        .expect("db error");
+    transaction.commit().await.expect("db error");
+    info!("Indexed {} files of source {}", files.len(), source_name);
+}
+
+async fn crawl(
+    source_name: &str,
+    source: &Source,
+    allowed_extensions: &Option<HashSet<String>>
+) -> Result<Vec<IndexedFile>> {
+    let mut files_found = Vec::<IndexedFile>::new();
+    let mut dirs_visited = HashSet::<String>::new();
+    let mut dirs_to_visit = vec![("/".to_string(), 0usize)];
+    while let Some((dir, depth)) = dirs_to_visit.pop() {
+        // the same directory can be reachable by several paths, it is crawled only once
+        if !dirs_visited.insert(canonical_dir(source, &dir).await?) {
+            continue
+        }
+        if dirs_visited.len() > constants::INDEXER_MAX_DIRS {
+            return Err(SyncmiruError::from(anyhow!("too many directories")))
+        }
+        let files = file::list(source, &dir).await?;
+        for f in files {
+            let path = file::join_path(&dir, &f.name);
+            match f.file_type {
+                FileType::Directory => {
+                    if depth < constants::INDEXER_MAX_DEPTH {
+                        dirs_to_visit.push((path, depth + 1))
+                    }
+                },
+                FileType::File => {
+                    if file::extension_allowed(&f.name, allowed_extensions) {
+                        files_found.push(IndexedFile {
+                            source: source_name.to_string(),
+                            path,
+                            name: f.name,
+                            mtime: f.mtime,
+                            size: f.size.map(|x| x as i64)
+                        });
+                    }
+                }
+            }
+        }
+        if files_found.len() > constants::INDEXER_MAX_FILES {
+            return Err(SyncmiruError::from(anyhow!("too many files")))
+        }
+    }
+    Ok(files_found)
+}
+
+async fn canonical_dir(source: &Source, dir: &str) -> Result<String> {
+    // symlinks of local sources can point back to a parent directory
+    if let SourceKind::Local { root, .. } = &source.kind {
+        let canonical = tokio::fs::canonicalize(local::resolve(root, dir)?).await?;
+        return Ok(canonical.to_string_lossy().to_string())
+    }
+
+    let mut segments = Vec::<&str>::new();
+    for segment in dir.split('/') {
+        match segment {
+            "" | "." => {},
+            ".." => { segments.pop(); },
+            _ => segments.push(segment)
+        }
+    }
+    Ok(format!("/{}", segments.join("/")))
+}
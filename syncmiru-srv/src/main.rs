@@ -6,6 +6,7 @@ use ::log::{debug, info};
 use axum::{Router};
 use axum::error_handling::HandleErrorLayer;
 use axum::http::{Method};
+use axum::extract::DefaultBodyLimit;
 use axum::routing::{get, post};
 use bimap::BiMap;
 use tower_http::trace::TraceLayer;
@@ -16,9 +17,12 @@ use tokio::sync::mpsc::{Receiver, Sender};
 use tower::{ServiceBuilder};
 use tower_http::cors::CorsLayer;
 use crate::args::Args;
-use crate::constants::SOCKETIO_ACK_TIMEOUT;
+use crate::constants::{COMMENTS_RATE_MAX, COMMENTS_RATE_PER, DIRECT_MESSAGES_RATE_MAX, DIRECT_MESSAGES_RATE_PER, REACTIONS_RATE_MAX, REACTIONS_RATE_PER, CHAT_RATE_MAX, CHAT_RATE_PER, SOCKETIO_ACK_TIMEOUT, UPLOADS_CHUNK_MAX_SIZE};
 use crate::bimultimap::BiMultiMap;
 use crate::result::Result;
+use crate::file::ListingCache;
+use crate::probe::ProbeCache;
+use crate::ratelimit::RateLimiter;
 use crate::srvstate::{SrvState};
 
 mod error;
@@ -40,6 +44,12 @@ mod tkn;
 mod constants;
 mod bimultimap;
 mod file;
+mod natsort;
+mod episode;
+mod probe;
+mod indexer;
+mod ratelimit;
+mod poll;
 
 
 #[macro_use]
@@ -75,7 +85,21 @@ async fn main() -> Result<()> {
          uid2play_info: HashMap::new().into(),
          rid2runtime_state: HashMap::new().into(),
          uid2timestamp: HashMap::new().into(),
-         uid2minor_desync: HashSet::new().into()
+         uid2presence: HashMap::new().into(),
+         rid_presence_broadcast_pending: HashSet::new().into(),
+         uid2minor_desync: HashSet::new().into(),
+         subtitle_next_id: 1u64.into(),
+         subtitle_uploads: HashMap::new().into(),
+         entry_subtitles: HashMap::new().into(),
+         active_uploads: HashSet::new().into(),
+         listing_cache: ListingCache::new(),
+         probe_cache: ProbeCache::new(),
+         comment_limiter: RateLimiter::new(COMMENTS_RATE_MAX, COMMENTS_RATE_PER),
+         reaction_limiter: RateLimiter::new(REACTIONS_RATE_MAX, REACTIONS_RATE_PER),
+         chat_limiter: RateLimiter::new(CHAT_RATE_MAX, CHAT_RATE_PER),
+         direct_message_limiter: RateLimiter::new(DIRECT_MESSAGES_RATE_MAX, DIRECT_MESSAGES_RATE_PER),
+         poll_next_id: 1u64.into(),
+         rid2poll: HashMap::new().into()
       });
 
    let socketio_srvstate = srvstate.clone();
@@ -89,10 +113,13 @@ async fn main() -> Result<()> {
    }
 
    tokio::spawn(handlers::timers::desync_timer_controller(srvstate.clone(), desync_timer_rx));
+   if config.indexer.enabled {
+      tokio::spawn(indexer::indexer(srvstate.clone()));
+   }
 
    io.ns("/", handlers::socketio::ns_callback.with(middleware::auth));
 
-   let app = Router::new()
+   let mut app = Router::new()
        .route("/", get(handlers::http::index))
        .route("/service", get(handlers::http::service))
        .route("/register", post(handlers::http::register))
@@ -105,12 +132,26 @@ async fn main() -> Result<()> {
        .route("/forgotten-password-tkn-valid", get(handlers::http::forgotten_password_tkn_valid))
        .route("/forgotten-password-change", post(handlers::http::forgotten_password_change))
        .route("/new-login", post(handlers::http::new_login))
-       .route("/reg-tkn-valid", get(handlers::http::reg_tkn_valid))
+       .route("/reg-tkn-valid", get(handlers::http::reg_tkn_valid));
+   if config.uploads.is_some() {
+      app = app
+          .route("/uploads", get(handlers::http::uploads).post(handlers::http::upload_create))
+          .route(
+             "/uploads/{id}",
+             get(handlers::http::upload_status)
+                 .patch(handlers::http::upload_chunk)
+                 .delete(handlers::http::upload_delete)
+                 .layer(DefaultBodyLimit::max(UPLOADS_CHUNK_MAX_SIZE))
+          )
+          .route("/uploads/file", get(handlers::http::uploaded_file));
+   }
+   let app = app
        .layer(socketio_layer)
        .layer(
           ServiceBuilder::new()
               .layer(CorsLayer::new()
-                  .allow_methods([Method::GET, Method::POST])
+                  .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
+                  .allow_headers(tower_http::cors::Any)
                   .allow_origin(tower_http::cors::Any)
               )
               .layer(HandleErrorLayer::new(handlers::http::error))
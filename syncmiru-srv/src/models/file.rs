@@ -1,6 +1,6 @@
 use chrono::Utc;
 use serde::{Deserialize, Deserializer, Serialize};
-use serde_repr::Serialize_repr;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileInfo {
@@ -31,4 +31,66 @@ where
 pub enum FileType {
     File = 0,
     Directory = 1
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Deserialize_repr)]
+#[repr(u8)]
+pub enum FileSortBy {
+    #[default]
+    Name = 0,
+    Mtime = 1,
+    Size = 2
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum FileAccess {
+    Jwt { jwt: String },
+    Presigned { url: String }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubtitleAccess {
+    pub name: String,
+
+    #[serde(flatten)]
+    pub access: FileAccess
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayingAccess {
+    #[serde(flatten)]
+    pub file: FileAccess,
+
+    pub subtitles: Vec<SubtitleAccess>
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EntryMetadata {
+    pub title: Option<String>,
+    pub year: Option<i32>,
+    pub plot: Option<String>,
+    pub poster: Option<String>
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Episode {
+    pub season: Option<u32>,
+    pub episode: u32
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MediaInfo {
+    pub duration: Option<f64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub audio_tracks: Vec<TrackInfo>,
+    pub subtitle_tracks: Vec<TrackInfo>
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TrackInfo {
+    pub language: Option<String>,
+    pub name: Option<String>,
+    pub codec: Option<String>
 }
\ No newline at end of file
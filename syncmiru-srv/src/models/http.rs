@@ -111,4 +111,22 @@ impl BooleanResp {
     pub fn from(b: bool) -> Self {
         Self { resp: b }
     }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UploadCreate {
+    #[validate(custom(function = "validators::check_upload_name"))]
+    pub name: String,
+
+    #[validate(range(min = 1))]
+    pub size: i64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadStatus {
+    pub id: Id,
+    pub name: String,
+    pub size: i64,
+    pub offset: i64,
+    pub finished: bool
 }
\ No newline at end of file
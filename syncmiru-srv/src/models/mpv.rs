@@ -2,7 +2,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 use crate::models::query::Id;
-use crate::srvstate::UserReadyStatus;
+use crate::srvstate::{AbLoop, UserReadyStatus};
 use crate::validators;
 
 #[derive(Debug, Copy, Clone, Deserialize, Validate)]
@@ -41,6 +41,18 @@ pub struct UserSeek {
     pub timestamp: f64
 }
 
+#[derive(Debug, Copy, Clone, Deserialize, Validate)]
+pub struct SetLoop {
+    #[validate(custom(function = "validators::check_ab_loop"))]
+    pub ab_loop: Option<AbLoop>
+}
+
+#[derive(Debug, Copy, Clone, Serialize)]
+pub struct UserLoop {
+    pub uid: Id,
+    pub ab_loop: Option<AbLoop>
+}
+
 #[derive(Debug, Copy, Clone, Serialize)]
 pub struct UserSpeedChange {
     pub uid: Id,
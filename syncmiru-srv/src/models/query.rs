@@ -48,11 +48,71 @@ pub struct RoomSettings {
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct RoomClient {
     pub id: Id,
-    pub name: String
+    pub name: String,
+    pub description: Option<String>,
+    pub topic: Option<String>,
+    pub icon: Option<Vec<u8>>
 }
 
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct RoomsClientWOrder {
     pub rooms: Vec<RoomClient>,
+    pub room_order: Vec<Id>,
+    pub categories: Vec<RoomCategory>
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RoomCategory {
+    pub id: Id,
+    pub name: String,
     pub room_order: Vec<Id>
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct IndexedFile {
+    pub source: String,
+    pub path: String,
+    pub name: String,
+    pub mtime: chrono::DateTime<Utc>,
+    pub size: Option<i64>
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Upload {
+    pub id: Id,
+    pub name: String,
+    pub size: i64,
+    pub finished: bool
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Bookmark {
+    pub id: Id,
+    pub timestamp: f64,
+    pub label: String,
+    pub uid: Option<Id>
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Comment {
+    pub id: Id,
+    pub timestamp: f64,
+    pub content: String,
+    pub uid: Option<Id>
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DirectMessage {
+    pub id: Id,
+    pub sender: Id,
+    pub recipient: Id,
+    pub content: String,
+    pub created_at: chrono::DateTime<Utc>,
+    pub read: bool
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct UnreadCount {
+    pub uid: Id,
+    pub count: i64
 }
\ No newline at end of file
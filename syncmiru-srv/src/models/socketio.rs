@@ -1,12 +1,15 @@
 use std::collections::HashMap;
+use chrono::Utc;
 use indexmap::{IndexMap, IndexSet};
 use rust_decimal::Decimal;
 use serde::{Serialize, Deserialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use validator::Validate;
 use crate::validators;
-use crate::models::query::{Id, RoomSettings};
-use crate::srvstate::{PlayingState, PlaylistEntry, PlaylistEntryId, UserPlayInfo, UserReadyStatus};
+use crate::models::file::{EntryMetadata, Episode, FileInfo, FileSortBy, MediaInfo};
+use crate::models::query::{Bookmark, Comment, Id, RoomSettings, RoomsClientWOrder};
+use crate::poll::{PollId, PollInfo, PollOption};
+use crate::srvstate::{AbLoop, Clip, PlayingState, PlaylistEntry, PlaylistEntryId, SubtitleId, UserPlayInfo, UserReadyStatus};
 
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct LoginTkns {
@@ -199,12 +202,95 @@ pub struct RoomMajorDesyncMin {
     pub major_desync_min: Decimal
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RoomAutoNextEpisode {
+    #[validate(range(min = 1))]
+    pub id: Id,
+
+    pub auto_next_episode: bool
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RoomDescription {
+    #[validate(range(min = 1))]
+    pub id: Id,
+
+    #[validate(custom(function = "validators::check_room_description"))]
+    pub description: Option<String>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RoomTopic {
+    #[validate(range(min = 1))]
+    pub id: Id,
+
+    #[validate(custom(function = "validators::check_room_topic"))]
+    pub topic: Option<String>
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct RoomIconBin {
+    #[validate(range(min = 1))]
+    pub id: Id,
+
+    #[validate(custom(function = "validators::check_avatar"))]
+    pub data: Vec<u8>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomIconChange {
+    pub id: Id,
+    pub icon: Option<Vec<u8>>
+}
+
+/// Whole layout of the room list, rooms outside of `categories` are uncategorized
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct RoomOrder {
+    #[validate(custom(function = "validators::check_room_order"))]
+    pub room_order: Vec<Id>,
+
+    #[validate(nested)]
+    pub categories: Vec<RoomCategoryOrder>
+}
+
+impl RoomOrder {
+    pub fn room_ids(&self) -> Vec<Id> {
+        self.room_order
+            .iter()
+            .chain(self.categories.iter().flat_map(|x| x.room_order.iter()))
+            .copied()
+            .collect()
+    }
+
+    pub fn category_ids(&self) -> Vec<Id> {
+        self.categories.iter().map(|x| x.id).collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RoomCategoryOrder {
+    #[validate(range(min = 1))]
+    pub id: Id,
+
     #[validate(custom(function = "validators::check_room_order"))]
     pub room_order: Vec<Id>
 }
 
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct RoomCategoryName {
+    #[validate(custom(function = "validators::check_room_category_name"))]
+    pub name: String
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RoomCategoryNameChange {
+    #[validate(range(min = 1))]
+    pub id: Id,
+
+    #[validate(custom(function = "validators::check_room_category_name"))]
+    pub name: String
+}
+
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct JoinRoomReq {
     #[validate(range(min = 1))]
@@ -263,7 +349,22 @@ pub struct GetFilesInfo {
     #[validate(custom(function = "validators::check_path"))]
     pub path: String,
 
-    pub file_kind: FileKind
+    pub file_kind: FileKind,
+
+    #[serde(default)]
+    pub sort_by: FileSortBy,
+
+    #[serde(default)]
+    pub sort_desc: bool,
+
+    #[validate(length(max = 255))]
+    pub filter: Option<String>,
+
+    #[validate(length(min = 1, max = 255))]
+    pub cursor: Option<String>,
+
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: Option<usize>
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize_repr)]
@@ -272,16 +373,69 @@ pub enum FileKind {
     Video = 0
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ListedFile {
+    #[serde(flatten)]
+    pub info: FileInfo,
+
+    pub episode: Option<Episode>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilesPage {
+    pub files: Vec<ListedFile>,
+    pub next_cursor: Option<String>
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SearchFiles {
+    #[validate(length(min = 1, max = 255))]
+    pub query: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FoundFile {
+    pub full_path: String,
+    pub source: String,
+    pub path: String,
+    pub name: String,
+    pub mtime: chrono::DateTime<Utc>,
+    pub size: Option<i64>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvalidateListingCache {
+    pub file_srv: Option<String>
+}
+
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct AddVideoFiles {
     #[validate(custom(function = "validators::check_source_files_paths"))]
-    pub full_paths: Vec<String>
+    pub full_paths: Vec<String>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validators::check_clip"))]
+    pub clip: Clip
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct AddDirectory {
+    pub file_srv: String,
+
+    #[validate(custom(function = "validators::check_path"))]
+    pub path: String,
+
+    pub recursive: bool
 }
 
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct AddUrls {
     #[validate(custom(function = "validators::check_urls"))]
-    pub urls: Vec<String>
+    pub urls: Vec<String>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validators::check_clip"))]
+    pub clip: Clip
 }
 
 #[derive(Debug, Clone, Validate, Deserialize)]
@@ -290,6 +444,21 @@ pub struct PlaylistEntryIdStruct {
     pub playlist_entry_id: PlaylistEntryId
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct PlaylistEntryClip {
+    #[validate(custom(function = "validators::check_playlist_entry_id"))]
+    pub playlist_entry_id: PlaylistEntryId,
+
+    #[validate(custom(function = "validators::check_clip"))]
+    pub clip: Clip
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipEnded {
+    pub playlist_entry_id: PlaylistEntryId,
+    pub timestamp: f64
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct PlaylistOrder {
     #[validate(custom(function = "validators::check_playlist_order"))]
@@ -339,6 +508,268 @@ pub struct MpvState {
     pub playing_state: PlayingState,
     pub playback_speed: Decimal,
     pub timestamp: f64,
+    pub ab_loop: Option<AbLoop>
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UploadSubtitleStart {
+    #[validate(custom(function = "validators::check_playlist_entry_id"))]
+    pub playlist_entry_id: PlaylistEntryId,
+
+    #[validate(custom(function = "validators::check_subtitle_name"))]
+    pub name: String,
+
+    #[validate(custom(function = "validators::check_subtitle_size"))]
+    pub size: usize
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UploadSubtitleChunk {
+    pub upload_id: SubtitleId,
+
+    #[validate(custom(function = "validators::check_subtitle_chunk"))]
+    pub data: Vec<u8>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadSubtitleId {
+    pub upload_id: SubtitleId
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct GetSubtitle {
+    #[validate(custom(function = "validators::check_playlist_entry_id"))]
+    pub playlist_entry_id: PlaylistEntryId,
+
+    pub subtitle_id: SubtitleId
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubtitleInfo {
+    pub playlist_entry_id: PlaylistEntryId,
+    pub id: SubtitleId,
+    pub uid: Id,
+    pub name: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubtitleBin {
+    pub name: String,
+    pub data: Vec<u8>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistEntryMedia {
+    pub playlist_entry_id: PlaylistEntryId,
+    pub media: MediaInfo
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistEntryMetadata {
+    pub playlist_entry_id: PlaylistEntryId,
+    pub metadata: EntryMetadata
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct AddBookmark {
+    #[validate(custom(function = "validators::check_playlist_entry_id"))]
+    pub playlist_entry_id: PlaylistEntryId,
+
+    #[validate(custom(function = "validators::check_media_timestamp"))]
+    pub timestamp: f64,
+
+    #[validate(custom(function = "validators::check_bookmark_label"))]
+    pub label: String
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct BookmarkIdStruct {
+    #[validate(custom(function = "validators::check_playlist_entry_id"))]
+    pub playlist_entry_id: PlaylistEntryId,
+
+    pub bookmark_id: Id
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BookmarkAdded {
+    pub playlist_entry_id: PlaylistEntryId,
+    pub bookmark: Bookmark
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BookmarkDeleted {
+    pub playlist_entry_id: PlaylistEntryId,
+    pub bookmark_id: Id
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct GetComments {
+    #[validate(custom(function = "validators::check_playlist_entry_id"))]
+    pub playlist_entry_id: PlaylistEntryId,
+
+    #[validate(custom(function = "validators::check_media_timestamp"))]
+    pub from: f64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentsWindow {
+    pub from: f64,
+    pub to: f64,
+    pub comments: Vec<Comment>
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct AddComment {
+    #[validate(custom(function = "validators::check_playlist_entry_id"))]
+    pub playlist_entry_id: PlaylistEntryId,
+
+    #[validate(custom(function = "validators::check_media_timestamp"))]
+    pub timestamp: f64,
+
+    #[validate(custom(function = "validators::check_comment_content"))]
+    pub content: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentAdded {
+    pub playlist_entry_id: PlaylistEntryId,
+    pub comment: Comment
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct React {
+    #[validate(custom(function = "validators::check_reaction"))]
+    pub reaction: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserReaction {
+    pub uid: Id,
+    pub reaction: String
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SendChatMessage {
+    #[validate(custom(function = "validators::check_chat_message_content"))]
+    pub content: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatMessage {
+    pub uid: Id,
+    pub content: String
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreatePoll {
+    #[validate(custom(function = "validators::check_poll_question"))]
+    pub question: String,
+
+    #[validate(custom(function = "validators::check_poll_options"))]
+    pub options: Vec<PollOption>,
+
+    pub multi_choice: bool,
+    pub activate_winner: bool,
+
+    #[validate(custom(function = "validators::check_poll_duration"))]
+    pub duration: Option<u64>
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct PollIdStruct {
+    #[validate(custom(function = "validators::check_poll_id"))]
+    pub id: PollId
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct VotePoll {
+    #[validate(custom(function = "validators::check_poll_id"))]
+    pub id: PollId,
+
+    #[validate(length(max = 10))]
+    pub choices: Vec<usize>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollState {
+    pub poll: PollInfo,
+    pub votes: Vec<usize>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollTallies {
+    pub id: PollId,
+    pub tallies: Vec<usize>
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SendDirectMessage {
+    #[validate(range(min = 1))]
+    pub uid: Id,
+
+    #[validate(custom(function = "validators::check_direct_message_content"))]
+    pub content: String
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct GetDirectMessages {
+    #[validate(range(min = 1))]
+    pub uid: Id,
+
+    #[validate(range(min = 1))]
+    pub before_id: Option<Id>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectMessagesRead {
+    pub uid: Id
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub enum PresenceStatus {
+    Online, Idle
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Watching {
+    pub rid: Id,
+    pub playlist_entry_id: PlaylistEntryId,
+    pub title: String,
+    pub position: Option<f64>,
+    pub playing_state: PlayingState
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Presence {
+    pub uid: Id,
+    pub status: PresenceStatus,
+    pub custom_status: Option<String>,
+    pub watching: Option<Watching>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomPreview {
+    pub rid: Id,
+    pub members: Vec<Id>,
+    pub watching: Option<Watching>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomsWPreviews {
+    #[serde(flatten)]
+    pub rooms: RoomsClientWOrder,
+    pub previews: Vec<RoomPreview>
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CustomStatus {
+    #[validate(custom(function = "validators::check_custom_status"))]
+    pub custom_status: Option<String>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PosterBin {
+    pub data: Vec<u8>
 }
 
 #[serde_with::serde_as]
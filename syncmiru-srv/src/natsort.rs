@@ -0,0 +1,87 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) => {
+                let ord = if ca.is_ascii_digit() && cb.is_ascii_digit() {
+                    cmp_numbers(&take_digits(&mut a_chars), &take_digits(&mut b_chars))
+                }
+                else {
+                    a_chars.next();
+                    b_chars.next();
+                    ca.to_lowercase().cmp(cb.to_lowercase())
+                };
+                if ord != Ordering::Equal {
+                    return ord
+                }
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break
+        }
+        digits.push(*c);
+        chars.next();
+    }
+    digits
+}
+
+fn cmp_numbers(a: &str, b: &str) -> Ordering {
+    let a_trimmed = a.trim_start_matches('0');
+    let b_trimmed = b.trim_start_matches('0');
+    a_trimmed.len()
+        .cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use super::natural_cmp;
+
+    #[test]
+    fn test_numbers_compared_by_value() {
+        assert_eq!(natural_cmp("Episode 2.mkv", "Episode 10.mkv"), Ordering::Less);
+        assert_eq!(natural_cmp("Episode 10.mkv", "Episode 2.mkv"), Ordering::Greater);
+        assert_eq!(natural_cmp("a1b2", "a1b10"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        assert_eq!(natural_cmp("ep02", "ep2"), Ordering::Less);
+        assert_eq!(natural_cmp("ep002", "ep10"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert_eq!(natural_cmp("alpha", "Beta"), Ordering::Less);
+        assert_eq!(natural_cmp("Alpha", "alpha"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut v = vec!["s1e10", "s1e2", "S1E1", "s10e1", "s2e1"];
+        v.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(v, vec!["S1E1", "s1e2", "s1e10", "s2e1", "s10e1"]);
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(natural_cmp("movie", "movie 2"), Ordering::Less);
+        assert_eq!(natural_cmp("", "a"), Ordering::Less);
+        assert_eq!(natural_cmp("same", "same"), Ordering::Equal);
+    }
+}
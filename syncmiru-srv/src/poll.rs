@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::models::query::Id;
+use crate::srvstate::PlaylistEntryId;
+
+pub type PollId = u64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub text: String,
+    pub playlist_entry_id: Option<PlaylistEntryId>
+}
+
+#[derive(Debug)]
+pub struct Poll {
+    pub id: PollId,
+    pub uid: Id,
+    pub question: String,
+    pub options: Vec<PollOption>,
+    pub multi_choice: bool,
+    pub activate_winner: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub closed: bool,
+    votes: HashMap<Id, Vec<usize>>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollInfo {
+    pub id: PollId,
+    pub uid: Id,
+    pub question: String,
+    pub options: Vec<PollOption>,
+    pub multi_choice: bool,
+    pub activate_winner: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub closed: bool,
+    pub tallies: Vec<usize>,
+    pub winner: Option<usize>
+}
+
+impl Poll {
+    pub fn new(
+        id: PollId,
+        uid: Id,
+        question: String,
+        options: Vec<PollOption>,
+        multi_choice: bool,
+        activate_winner: bool,
+        closes_at: Option<DateTime<Utc>>
+    ) -> Self {
+        Self { id, uid, question, options, multi_choice, activate_winner, closes_at, closed: false, votes: HashMap::new() }
+    }
+
+    /// Replaces the previous choices of the user, an empty list withdraws the vote
+    pub fn vote(&mut self, uid: Id, choices: &[usize]) -> bool {
+        if self.closed {
+            return false
+        }
+        if !self.multi_choice && choices.len() > 1 {
+            return false
+        }
+        let mut sorted = choices.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != choices.len() || sorted.iter().any(|x| *x >= self.options.len()) {
+            return false
+        }
+        if sorted.is_empty() {
+            self.votes.remove(&uid);
+        }
+        else {
+            self.votes.insert(uid, sorted);
+        }
+        true
+    }
+
+    pub fn votes_of(&self, uid: Id) -> Vec<usize> {
+        self.votes.get(&uid).cloned().unwrap_or_default()
+    }
+
+    pub fn tallies(&self) -> Vec<usize> {
+        let mut tallies = vec![0usize; self.options.len()];
+        for choices in self.votes.values() {
+            for choice in choices {
+                tallies[*choice] += 1;
+            }
+        }
+        tallies
+    }
+
+    /// Option with the most votes, a poll without votes or with a tie has no winner
+    pub fn winner(&self) -> Option<usize> {
+        let tallies = self.tallies();
+        let max = *tallies.iter().max()?;
+        if max == 0 || tallies.iter().filter(|x| **x == max).count() > 1 {
+            return None
+        }
+        tallies.iter().position(|x| *x == max)
+    }
+
+    pub fn info(&self) -> PollInfo {
+        PollInfo {
+            id: self.id,
+            uid: self.uid,
+            question: self.question.clone(),
+            options: self.options.clone(),
+            multi_choice: self.multi_choice,
+            activate_winner: self.activate_winner,
+            closes_at: self.closes_at,
+            closed: self.closed,
+            tallies: self.tallies(),
+            winner: if self.closed { self.winner() } else { None }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Poll, PollOption};
+
+    fn poll(multi_choice: bool) -> Poll {
+        let options = ["a", "b", "c"]
+            .iter()
+            .map(|x| PollOption { text: x.to_string(), playlist_entry_id: None })
+            .collect();
+        Poll::new(1, 1, "?".to_string(), options, multi_choice, false, None)
+    }
+
+    #[test]
+    fn test_vote_single_choice() {
+        let mut p = poll(false);
+        assert!(p.vote(1, &[0]));
+        assert!(p.vote(2, &[1]));
+        assert!(!p.vote(3, &[0, 1]));
+        assert!(!p.vote(3, &[3]));
+        assert_eq!(p.tallies(), vec![1, 1, 0]);
+        assert_eq!(p.winner(), None);
+
+        assert!(p.vote(2, &[0]));
+        assert_eq!(p.tallies(), vec![2, 0, 0]);
+        assert_eq!(p.winner(), Some(0));
+
+        assert!(p.vote(1, &[]));
+        assert_eq!(p.votes_of(1), Vec::<usize>::new());
+        assert_eq!(p.tallies(), vec![1, 0, 0]);
+
+        p.closed = true;
+        assert!(!p.vote(1, &[2]));
+    }
+
+    #[test]
+    fn test_vote_multi_choice() {
+        let mut p = poll(true);
+        assert!(p.vote(1, &[2, 0]));
+        assert!(!p.vote(2, &[1, 1]));
+        assert!(p.vote(2, &[2]));
+        assert_eq!(p.votes_of(1), vec![0, 2]);
+        assert_eq!(p.tallies(), vec![1, 0, 2]);
+        assert_eq!(p.winner(), Some(2));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::anyhow;
+use indexmap::IndexMap;
+use log::debug;
+use tokio::sync::RwLock;
+use crate::{constants, file, Result};
+use crate::config::Source;
+use crate::error::SyncmiruError;
+use crate::models::file::MediaInfo;
+use crate::models::query::Id;
+use crate::models::socketio::PlaylistEntryMedia;
+use crate::srvstate::{PlaylistEntry, PlaylistEntryId, SrvState};
+
+mod mkv;
+mod mp4;
+
+pub struct ProbeCache {
+    entries: RwLock<HashMap<(String, String), MediaInfo>>
+}
+
+impl ProbeCache {
+    pub fn new() -> Self {
+        Self { entries: RwLock::new(HashMap::new()) }
+    }
+
+    pub async fn probe(
+        &self,
+        source_name: &str,
+        source: &Source,
+        path: &str
+    ) -> Result<MediaInfo> {
+        let key = (source_name.to_string(), path.to_string());
+        if let Some(media) = self.entries.read().await.get(&key) {
+            return Ok(media.clone())
+        }
+
+        let media = probe(source, path).await?;
+        let mut entries_wl = self.entries.write().await;
+        if entries_wl.len() >= constants::PROBE_CACHE_MAX_ENTRIES {
+            entries_wl.clear();
+        }
+        entries_wl.insert(key, media.clone());
+        Ok(media)
+    }
+}
+
+pub async fn probe(source: &Source, path: &str) -> Result<MediaInfo> {
+    let head = file::read_range(source, path, 0, constants::PROBE_HEAD_SIZE).await?;
+    if mkv::is_mkv(&head) {
+        return mkv::parse(&head)
+            .ok_or(SyncmiruError::from(anyhow!("no tracks found in the first bytes of {}", path)))
+    }
+    if !mp4::is_mp4(&head) {
+        return Err(SyncmiruError::from(anyhow!("unsupported container of {}", path)))
+    }
+
+    // moov is either right after ftyp or at the very end, behind mdat
+    let mut offset = 0u64;
+    for _ in 0..constants::PROBE_MP4_MAX_BOXES {
+        let header_bytes = bytes_at(source, path, &head, offset, 16).await?;
+        let header_opt = mp4::box_header(&header_bytes);
+        if header_opt.is_none() {
+            break
+        }
+        let header = header_opt.unwrap();
+        if header.size != 0 && header.size < header.header_len as u64 {
+            break
+        }
+        if &header.box_type == b"moov" {
+            if header.size == 0 || header.size > constants::PROBE_MOOV_MAX_SIZE as u64 {
+                return Err(SyncmiruError::from(anyhow!("moov of {} is too large", path)))
+            }
+            let moov = bytes_at(source, path, &head, offset, header.size as usize).await?;
+            if moov.len() < header.size as usize {
+                return Err(SyncmiruError::from(anyhow!("moov of {} is truncated", path)))
+            }
+            return mp4::parse_moov(&moov[header.header_len..])
+                .ok_or(SyncmiruError::from(anyhow!("invalid moov in {}", path)))
+        }
+        if header.size == 0 {
+            break
+        }
+        match offset.checked_add(header.size) {
+            Some(next) => offset = next,
+            None => break
+        }
+    }
+    Err(SyncmiruError::from(anyhow!("moov not found in {}", path)))
+}
+
+async fn bytes_at(
+    source: &Source,
+    path: &str,
+    head: &[u8],
+    offset: u64,
+    len: usize
+) -> Result<Vec<u8>> {
+    let end = offset.saturating_add(len as u64);
+    if end <= head.len() as u64 {
+        return Ok(head[offset as usize..end as usize].to_vec())
+    }
+    file::read_range(source, path, offset, len).await
+}
+
+/// Probes the added entries one by one and hands the results to the room as they come
+pub async fn probe_entries(state: Arc<SrvState>, rid: Id, entries: Vec<(PlaylistEntryId, String, String)>) {
+    for (entry_id, source_name, path) in entries {
+        if !state.playlist.read().await.contains_key(&entry_id) {
+            continue
+        }
+        let source_opt = state.config.sources.get(&source_name);
+        if source_opt.is_none() {
+            continue
+        }
+        let media_r = state.probe_cache.probe(&source_name, source_opt.unwrap(), &path).await;
+        if let Err(e) = media_r {
+            debug!("Probing {}:{} failed: {}", source_name, path, e);
+            continue
+        }
+        let media = media_r.unwrap();
+
+        let mut playlist_wl = state.playlist.write().await;
+        if let Some(PlaylistEntry::Video { media: entry_media, .. }) = playlist_wl.get_mut(&entry_id) {
+            *entry_media = Some(Box::new(media.clone()));
+        }
+        else {
+            continue
+        }
+        drop(playlist_wl);
+
+        let io_rl = state.io.read().await;
+        let io = io_rl.as_ref().unwrap();
+        io.within(rid.to_string())
+            .emit("playlist_entry_media", &PlaylistEntryMedia { playlist_entry_id: entry_id, media })
+            .ok();
+    }
+}
+
+pub fn spawn_probe(state: &Arc<SrvState>, rid: Id, entries: &IndexMap<PlaylistEntryId, PlaylistEntry>) {
+    let to_probe = entries
+        .iter()
+        .filter_map(|(id, entry)| match entry {
+            PlaylistEntry::Video { source, path, .. } => Some((*id, source.clone(), path.clone())),
+            _ => None
+        })
+        .collect::<Vec<(PlaylistEntryId, String, String)>>();
+    if !to_probe.is_empty() {
+        tokio::spawn(probe_entries(state.clone(), rid, to_probe));
+    }
+}
@@ -0,0 +1,256 @@
+use crate::models::file::{MediaInfo, TrackInfo};
+
+const EBML: u32 = 0x1A45DFA3;
+const SEGMENT: u32 = 0x18538067;
+const INFO: u32 = 0x1549A966;
+const TIMECODE_SCALE: u32 = 0x2AD7B1;
+const DURATION: u32 = 0x4489;
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_TYPE: u32 = 0x83;
+const CODEC_ID: u32 = 0x86;
+const LANGUAGE: u32 = 0x22B59C;
+const LANGUAGE_IETF: u32 = 0x22B59D;
+const NAME: u32 = 0x536E;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const CLUSTER: u32 = 0x1F43B675;
+
+const TRACK_TYPE_VIDEO: u64 = 1;
+const TRACK_TYPE_AUDIO: u64 = 2;
+const TRACK_TYPE_SUBTITLE: u64 = 17;
+
+struct Element {
+    id: u32,
+    start: usize,
+    end: usize,
+    complete: bool
+}
+
+pub fn is_mkv(data: &[u8]) -> bool {
+    data.len() >= 4 && u32::from_be_bytes([data[0], data[1], data[2], data[3]]) == EBML
+}
+
+/// Parses the segment info and tracks from the beginning of a Matroska/WebM file,
+/// the data may be cut off anywhere, incomplete track entries are skipped
+pub fn parse(data: &[u8]) -> Option<MediaInfo> {
+    let segment = children(data, 0, data.len())
+        .into_iter()
+        .find(|x| x.id == SEGMENT)?;
+
+    let mut info = MediaInfo::default();
+    let mut tracks_found = false;
+    for element in children(data, segment.start, segment.end) {
+        match element.id {
+            INFO => info.duration = parse_duration(data, &element),
+            TRACKS => {
+                tracks_found = true;
+                parse_tracks(data, &element, &mut info);
+            }
+            CLUSTER => break,
+            _ => {}
+        }
+    }
+    if !tracks_found {
+        return None
+    }
+    Some(info)
+}
+
+fn parse_duration(data: &[u8], info: &Element) -> Option<f64> {
+    let mut timecode_scale = 1_000_000u64;
+    let mut duration: Option<f64> = None;
+    for element in children(data, info.start, info.end) {
+        if !element.complete {
+            continue
+        }
+        let bytes = &data[element.start..element.end];
+        match element.id {
+            TIMECODE_SCALE => timecode_scale = read_uint(bytes)?,
+            DURATION => duration = read_float(bytes),
+            _ => {}
+        }
+    }
+    duration.map(|x| x * timecode_scale as f64 / 1e9)
+}
+
+fn parse_tracks(data: &[u8], tracks: &Element, info: &mut MediaInfo) {
+    for entry in children(data, tracks.start, tracks.end) {
+        if entry.id != TRACK_ENTRY || !entry.complete {
+            continue
+        }
+        let mut track_type = 0u64;
+        let mut track = TrackInfo::default();
+        let mut language: Option<String> = None;
+        let mut language_ietf: Option<String> = None;
+        let mut dimensions: (Option<u32>, Option<u32>) = (None, None);
+        for element in children(data, entry.start, entry.end) {
+            let bytes = &data[element.start..element.end];
+            match element.id {
+                TRACK_TYPE => track_type = read_uint(bytes).unwrap_or(0),
+                CODEC_ID => track.codec = read_string(bytes),
+                LANGUAGE => language = read_string(bytes),
+                LANGUAGE_IETF => language_ietf = read_string(bytes),
+                NAME => track.name = read_string(bytes),
+                VIDEO => {
+                    for v in children(data, element.start, element.end) {
+                        let v_bytes = &data[v.start..v.end];
+                        match v.id {
+                            PIXEL_WIDTH => dimensions.0 = read_uint(v_bytes).map(|x| x as u32),
+                            PIXEL_HEIGHT => dimensions.1 = read_uint(v_bytes).map(|x| x as u32),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        // the language element defaults to eng when it is missing
+        track.language = language_ietf
+            .or(language)
+            .or(Some("eng".to_string()))
+            .filter(|x| x != "und");
+        match track_type {
+            TRACK_TYPE_VIDEO if info.width.is_none() => (info.width, info.height) = dimensions,
+            TRACK_TYPE_AUDIO => info.audio_tracks.push(track),
+            TRACK_TYPE_SUBTITLE => info.subtitle_tracks.push(track),
+            _ => {}
+        }
+    }
+}
+
+fn children(data: &[u8], start: usize, end: usize) -> Vec<Element> {
+    let mut elements = Vec::<Element>::new();
+    let mut pos = start;
+    while pos < end {
+        let Some((id, size_pos)) = read_id(data, pos) else { break };
+        let Some((size, data_start)) = read_size(data, size_pos) else { break };
+        let declared_end = size.map(|x| data_start as u64 + x).unwrap_or(end as u64);
+        let complete = declared_end <= data.len() as u64;
+        let element_end = declared_end.min(end as u64) as usize;
+        elements.push(Element { id, start: data_start.min(element_end), end: element_end, complete });
+        pos = element_end;
+    }
+    elements
+}
+
+fn read_id(data: &[u8], pos: usize) -> Option<(u32, usize)> {
+    let first = *data.get(pos)?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 4 || pos + len > data.len() {
+        return None
+    }
+    let id = data[pos..pos + len]
+        .iter()
+        .fold(0u32, |acc, x| (acc << 8) | *x as u32);
+    Some((id, pos + len))
+}
+
+fn read_size(data: &[u8], pos: usize) -> Option<(Option<u64>, usize)> {
+    let first = *data.get(pos)?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 || pos + len > data.len() {
+        return None
+    }
+    let mask = if len == 8 { 0 } else { 0xFFu8 >> len };
+    let mut value = (first & mask) as u64;
+    for b in &data[pos + 1..pos + len] {
+        value = (value << 8) | *b as u64;
+    }
+    let unknown = value == (1u64 << (7 * len)) - 1;
+    Some((if unknown { None } else { Some(value) }, pos + len))
+}
+
+fn read_uint(bytes: &[u8]) -> Option<u64> {
+    if bytes.len() > 8 {
+        return None
+    }
+    Some(bytes.iter().fold(0u64, |acc, x| (acc << 8) | *x as u64))
+}
+
+fn read_float(bytes: &[u8]) -> Option<f64> {
+    match bytes.len() {
+        4 => Some(f32::from_be_bytes(bytes.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(bytes.try_into().ok()?)),
+        _ => None
+    }
+}
+
+fn read_string(bytes: &[u8]) -> Option<String> {
+    let s = String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_string();
+    if s.is_empty() {
+        return None
+    }
+    Some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::file::{MediaInfo, TrackInfo};
+    use super::{is_mkv, parse};
+
+    fn element(id: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut e = id.to_vec();
+        e.push(0x08);
+        e.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        e.extend_from_slice(payload);
+        e
+    }
+
+    fn sample() -> Vec<u8> {
+        let info = element(&[0x15, 0x49, 0xA9, 0x66], &[
+            element(&[0x2A, 0xD7, 0xB1], &[0x0F, 0x42, 0x40]),
+            element(&[0x44, 0x89], &1_440_500f64.to_be_bytes()),
+        ].concat());
+        let video = element(&[0xAE], &[
+            element(&[0x83], &[1]),
+            element(&[0x86], b"V_MPEG4/ISO/AVC"),
+            element(&[0xE0], &[element(&[0xB0], &[0x07, 0x80]), element(&[0xBA], &[0x04, 0x38])].concat()),
+        ].concat());
+        let audio = element(&[0xAE], &[
+            element(&[0x83], &[2]),
+            element(&[0x86], b"A_OPUS"),
+            element(&[0x22, 0xB5, 0x9C], b"jpn"),
+        ].concat());
+        let subtitle = element(&[0xAE], &[
+            element(&[0x83], &[17]),
+            element(&[0x86], b"S_TEXT/ASS"),
+            element(&[0x22, 0xB5, 0x9C], b"cze"),
+            element(&[0x22, 0xB5, 0x9D], b"cs"),
+            element(&[0x53, 0x6E], b"Full"),
+        ].concat());
+        let tracks = element(&[0x16, 0x54, 0xAE, 0x6B], &[video, audio, subtitle].concat());
+        let cluster = element(&[0x1F, 0x43, 0xB6, 0x75], &[0u8; 32]);
+
+        // segment of unknown size, as written by live muxers
+        let mut segment = vec![0x18, 0x53, 0x80, 0x67, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        segment.extend([info, tracks, cluster].concat());
+        [element(&[0x1A, 0x45, 0xDF, 0xA3], &element(&[0x42, 0x82], b"matroska")), segment].concat()
+    }
+
+    #[test]
+    fn test_parse() {
+        let data = sample();
+        assert!(is_mkv(&data));
+        assert_eq!(parse(&data).unwrap(), MediaInfo {
+            duration: Some(1440.5),
+            width: Some(1920),
+            height: Some(1080),
+            audio_tracks: vec![TrackInfo { language: Some("jpn".to_string()), name: None, codec: Some("A_OPUS".to_string()) }],
+            subtitle_tracks: vec![TrackInfo { language: Some("cs".to_string()), name: Some("Full".to_string()), codec: Some("S_TEXT/ASS".to_string()) }],
+        });
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        let data = sample();
+        let cut = &data[..data.len() - 60];
+        let info = parse(cut).unwrap();
+        assert_eq!(info.width, Some(1920));
+        assert_eq!(info.audio_tracks.len(), 1);
+        assert!(info.subtitle_tracks.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::models::file::{MediaInfo, TrackInfo};
+
+pub struct BoxHeader {
+    pub box_type: [u8; 4],
+    pub size: u64,
+    pub header_len: usize
+}
+
+pub fn is_mp4(data: &[u8]) -> bool {
+    data.len() >= 8 && &data[4..8] == b"ftyp"
+}
+
+/// Reads the header of the box starting at the beginning of `data`,
+/// a size of 0 means that the box extends to the end of the file
+pub fn box_header(data: &[u8]) -> Option<BoxHeader> {
+    if data.len() < 8 {
+        return None
+    }
+    let size = u32::from_be_bytes(data[0..4].try_into().ok()?) as u64;
+    let box_type: [u8; 4] = data[4..8].try_into().ok()?;
+    if size == 1 {
+        if data.len() < 16 {
+            return None
+        }
+        let large_size = u64::from_be_bytes(data[8..16].try_into().ok()?);
+        if large_size < 16 {
+            return None
+        }
+        return Some(BoxHeader { box_type, size: large_size, header_len: 16 })
+    }
+    if size != 0 && size < 8 {
+        return None
+    }
+    Some(BoxHeader { box_type, size, header_len: 8 })
+}
+
+/// Parses the payload of the moov box
+pub fn parse_moov(moov: &[u8]) -> Option<MediaInfo> {
+    let mut info = MediaInfo::default();
+    let mut mvhd_found = false;
+    for (box_type, payload) in children(moov) {
+        match &box_type {
+            b"mvhd" => {
+                mvhd_found = true;
+                info.duration = parse_mvhd(payload);
+            }
+            b"trak" => parse_trak(payload, &mut info),
+            _ => {}
+        }
+    }
+    if !mvhd_found {
+        return None
+    }
+    Some(info)
+}
+
+fn parse_mvhd(payload: &[u8]) -> Option<f64> {
+    let (timescale, duration) = match *payload.first()? {
+        0 => (read_u32(payload, 12)? as u64, read_u32(payload, 16)? as u64),
+        1 => (read_u32(payload, 20)? as u64, read_u64(payload, 24)?),
+        _ => return None
+    };
+    if timescale == 0 || duration == u32::MAX as u64 || duration == u64::MAX {
+        return None
+    }
+    Some(duration as f64 / timescale as f64)
+}
+
+fn parse_trak(trak: &[u8], info: &mut MediaInfo) {
+    let mut dimensions: (Option<u32>, Option<u32>) = (None, None);
+    let mut handler: Option<[u8; 4]> = None;
+    let mut track = TrackInfo::default();
+    let mut language: Option<String> = None;
+    let mut extended_language: Option<String> = None;
+    for (box_type, payload) in children(trak) {
+        match &box_type {
+            b"tkhd" if payload.len() >= 8 => {
+                // width and height are 16.16 fixed point numbers at the end of tkhd
+                let width = read_u32(payload, payload.len() - 8).map(|x| x >> 16);
+                let height = read_u32(payload, payload.len() - 4).map(|x| x >> 16);
+                dimensions = (width.filter(|x| *x > 0), height.filter(|x| *x > 0));
+            }
+            b"mdia" => {
+                for (mdia_type, mdia_payload) in children(payload) {
+                    match &mdia_type {
+                        b"mdhd" => language = parse_mdhd_language(mdia_payload),
+                        b"elng" => extended_language = read_cstring(mdia_payload.get(4..).unwrap_or(&[])),
+                        b"hdlr" => handler = mdia_payload.get(8..12).and_then(|x| x.try_into().ok()),
+                        b"minf" => track.codec = parse_codec(mdia_payload),
+                        _ => {}
+                    }
+                }
+            }
+            b"udta" => {
+                if let Some((_, name)) = children(payload).into_iter().find(|(t, _)| t == b"name") {
+                    track.name = read_cstring(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    track.language = extended_language.or(language);
+    match handler.as_ref() {
+        Some(b"vide") if info.width.is_none() => (info.width, info.height) = dimensions,
+        Some(b"soun") => info.audio_tracks.push(track),
+        Some(b"sbtl" | b"subt" | b"text" | b"clcp") => info.subtitle_tracks.push(track),
+        _ => {}
+    }
+}
+
+fn parse_mdhd_language(payload: &[u8]) -> Option<String> {
+    let offset = match *payload.first()? {
+        0 => 20,
+        1 => 32,
+        _ => return None
+    };
+    let packed = u16::from_be_bytes(payload.get(offset..offset + 2)?.try_into().ok()?);
+    let language: String = [10, 5, 0]
+        .iter()
+        .map(|shift| (((packed >> shift) & 0x1F) as u8 + 0x60) as char)
+        .collect();
+    if !language.chars().all(|x| x.is_ascii_lowercase()) || language == "und" {
+        return None
+    }
+    Some(language)
+}
+
+fn parse_codec(minf: &[u8]) -> Option<String> {
+    let (_, stbl) = children(minf).into_iter().find(|(t, _)| t == b"stbl")?;
+    let (_, stsd) = children(stbl).into_iter().find(|(t, _)| t == b"stsd")?;
+    // version, flags and entry count precede the first sample entry
+    let entry = box_header(stsd.get(8..)?)?;
+    let codec = String::from_utf8_lossy(&entry.box_type).trim().to_string();
+    Some(codec)
+}
+
+fn children(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut boxes = Vec::<([u8; 4], &[u8])>::new();
+    let mut pos = 0usize;
+    while pos < data.len() {
+        let Some(header) = box_header(&data[pos..]) else { break };
+        let end = if header.size == 0 {
+            data.len()
+        }
+        else {
+            pos.saturating_add(header.size as usize)
+        };
+        if end > data.len() || pos + header.header_len > end {
+            break
+        }
+        boxes.push((header.box_type, &data[pos + header.header_len..end]));
+        pos = end;
+    }
+    boxes
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+fn read_cstring(data: &[u8]) -> Option<String> {
+    let end = data.iter().position(|x| *x == 0).unwrap_or(data.len());
+    let s = String::from_utf8_lossy(&data[..end]).trim().to_string();
+    if s.is_empty() {
+        return None
+    }
+    Some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::file::{MediaInfo, TrackInfo};
+    use super::{box_header, is_mp4, parse_moov};
+
+    fn mp4_box(box_type: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut b = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        b.extend_from_slice(box_type);
+        b.extend_from_slice(payload);
+        b
+    }
+
+    fn trak(handler: &[u8; 4], width: u32, height: u32, language: &[u8; 3], codec: &[u8; 4]) -> Vec<u8> {
+        let mut tkhd = vec![0u8; 76];
+        tkhd.extend_from_slice(&(width << 16).to_be_bytes());
+        tkhd.extend_from_slice(&(height << 16).to_be_bytes());
+
+        let packed = language.iter().fold(0u16, |acc, x| (acc << 5) | (*x - 0x60) as u16);
+        let mut mdhd = vec![0u8; 20];
+        mdhd.extend_from_slice(&packed.to_be_bytes());
+        mdhd.extend_from_slice(&[0, 0]);
+
+        let mut hdlr = vec![0u8; 8];
+        hdlr.extend_from_slice(handler);
+        hdlr.extend_from_slice(&[0u8; 13]);
+
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stsd.extend(mp4_box(codec, &[0u8; 16]));
+        let minf = mp4_box(b"minf", &mp4_box(b"stbl", &mp4_box(b"stsd", &stsd)));
+
+        let mdia = mp4_box(b"mdia", &[mp4_box(b"mdhd", &mdhd), mp4_box(b"hdlr", &hdlr), minf].concat());
+        mp4_box(b"trak", &[mp4_box(b"tkhd", &tkhd), mdia].concat())
+    }
+
+    #[test]
+    fn test_parse_moov() {
+        let mut mvhd = vec![0u8; 12];
+        mvhd.extend_from_slice(&1000u32.to_be_bytes());
+        mvhd.extend_from_slice(&1_440_500u32.to_be_bytes());
+        mvhd.extend_from_slice(&[0u8; 80]);
+
+        let moov = [
+            mp4_box(b"mvhd", &mvhd),
+            trak(b"vide", 1920, 1080, b"und", b"avc1"),
+            trak(b"soun", 0, 0, b"jpn", b"mp4a"),
+            trak(b"sbtl", 0, 0, b"ces", b"tx3g"),
+        ].concat();
+        assert_eq!(parse_moov(&moov).unwrap(), MediaInfo {
+            duration: Some(1440.5),
+            width: Some(1920),
+            height: Some(1080),
+            audio_tracks: vec![TrackInfo { language: Some("jpn".to_string()), name: None, codec: Some("mp4a".to_string()) }],
+            subtitle_tracks: vec![TrackInfo { language: Some("ces".to_string()), name: None, codec: Some("tx3g".to_string()) }],
+        });
+    }
+
+    #[test]
+    fn test_box_header() {
+        let ftyp = mp4_box(b"ftyp", b"isom");
+        assert!(is_mp4(&ftyp));
+        let header = box_header(&ftyp).unwrap();
+        assert_eq!((&header.box_type, header.size, header.header_len), (b"ftyp", 12, 8));
+
+        let mut mdat = vec![0, 0, 0, 1];
+        mdat.extend_from_slice(b"mdat");
+        mdat.extend_from_slice(&(5u64 << 32).to_be_bytes());
+        let header = box_header(&mdat).unwrap();
+        assert_eq!((&header.box_type, header.size, header.header_len), (b"mdat", 5u64 << 32, 16));
+
+        let mut undersized = vec![0, 0, 0, 1];
+        undersized.extend_from_slice(b"moov");
+        undersized.extend_from_slice(&12u64.to_be_bytes());
+        assert!(box_header(&undersized).is_none());
+    }
+}
@@ -1,7 +1,8 @@
+use chrono::Utc;
 use rust_decimal::Decimal;
 use sqlx::{PgPool, Postgres, Transaction};
 use crate::models::User;
-use crate::models::query::{EmailTknType, Id, RegDetail, RegTkn, RoomClient, RoomSettings};
+use crate::models::query::{Bookmark, Comment, DirectMessage, EmailTknType, Id, IndexedFile, RegDetail, RegTkn, RoomCategory, RoomClient, RoomSettings, UnreadCount, Upload};
 use crate::models::query::UserSession;
 use crate::result::Result;
 
@@ -861,7 +862,7 @@ pub async fn new_room(
 
 pub async fn get_rooms_for_update(db: &mut Transaction<'_, Postgres>) -> Result<Vec<RoomClient>> {
     let query = r#"
-           select id, name from room for update
+           select id, name, description, topic, icon from room for update
     "#;
     let rooms: Vec<RoomClient> = sqlx::query_as::<_, RoomClient>(query)
         .fetch_all(&mut **db)
@@ -878,6 +879,41 @@ pub async fn set_room_name(db: &PgPool, rid: Id, room_name: &str) -> Result<()>
     Ok(())
 }
 
+pub async fn get_room(db: &PgPool, rid: Id) -> Result<Option<RoomClient>> {
+    let room = sqlx::query_as::<_, RoomClient>("select id, name, description, topic, icon from room where id = $1 limit 1")
+        .bind(rid)
+        .fetch_optional(db)
+        .await?;
+    Ok(room)
+}
+
+pub async fn set_room_description(db: &PgPool, rid: Id, description: Option<&str>) -> Result<bool> {
+    let result = sqlx::query("update room set description = $1 where id = $2")
+        .bind(description)
+        .bind(rid)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn set_room_topic(db: &PgPool, rid: Id, topic: Option<&str>) -> Result<bool> {
+    let result = sqlx::query("update room set topic = $1 where id = $2")
+        .bind(topic)
+        .bind(rid)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn set_room_icon(db: &PgPool, rid: Id, icon: Option<&[u8]>) -> Result<bool> {
+    let result = sqlx::query("update room set icon = $1 where id = $2")
+        .bind(icon)
+        .bind(rid)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn delete_room(db: &mut Transaction<'_, Postgres>, rid: Id) -> Result<()> {
     sqlx::query("delete from room where id = $1")
         .bind(rid)
@@ -1034,6 +1070,44 @@ pub async fn set_room_major_desync_min(
     }
 }
 
+pub async fn get_room_auto_next_episode(
+    db: &PgPool,
+    rid: Id
+) -> Result<Option<bool>> {
+    if let Some(auto_next_episode) = sqlx::query_as::<_, (bool,)>("select auto_next_episode from room where id = $1 limit 1")
+        .bind(rid)
+        .fetch_optional(db).await? {
+        Ok(Some(auto_next_episode.0))
+    }
+    else {
+        Ok(None)
+    }
+}
+
+pub async fn set_room_auto_next_episode(
+    db: &PgPool,
+    rid: Id,
+    auto_next_episode: bool
+) -> Result<bool> {
+    let mut transaction = db.begin().await?;
+    if sqlx::query_as::<_, (Id,)>("select id from room where id = $1 for update limit 1")
+        .bind(rid)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .is_some() {
+        sqlx::query("update room set auto_next_episode = $1 where id = $2")
+            .bind(auto_next_episode)
+            .bind(rid)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(true)
+    }
+    else {
+        Ok(false)
+    }
+}
+
 pub async fn get_room_order_for_update(
     db: &mut Transaction<'_, Postgres>,
 ) -> Result<Vec<Id>> {
@@ -1054,6 +1128,120 @@ pub async fn set_room_order(
     Ok(())
 }
 
+pub async fn get_category_order_for_update(
+    db: &mut Transaction<'_, Postgres>,
+) -> Result<Vec<Id>> {
+    let category_order = sqlx::query_as::<_, (Vec<Id>,)>("select category_order from settings for update limit 1")
+        .fetch_one(&mut **db)
+        .await?;
+    Ok(category_order.0)
+}
+
+pub async fn set_category_order(
+    db: &mut Transaction<'_, Postgres>,
+    category_order: &[Id]
+) -> Result<()> {
+    sqlx::query("update settings set category_order = $1")
+        .bind(category_order)
+        .execute(&mut **db)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_room_categories_for_update(
+    db: &mut Transaction<'_, Postgres>,
+) -> Result<Vec<RoomCategory>> {
+    let query = r#"
+        select c.id, c.name, c.room_order
+        from room_category c
+        join unnest((select category_order from settings limit 1)) with ordinality o(id, n) on o.id = c.id
+        order by o.n
+        for update of c
+    "#;
+    let categories = sqlx::query_as::<_, RoomCategory>(query)
+        .fetch_all(&mut **db)
+        .await?;
+    Ok(categories)
+}
+
+pub async fn get_room_category_for_update(
+    db: &mut Transaction<'_, Postgres>,
+    id: Id
+) -> Result<Option<RoomCategory>> {
+    let category = sqlx::query_as::<_, RoomCategory>("select id, name, room_order from room_category where id = $1 for update limit 1")
+        .bind(id)
+        .fetch_optional(&mut **db)
+        .await?;
+    Ok(category)
+}
+
+/// Whether no category other than `exclude_id` is named `name`
+pub async fn room_category_name_unique(
+    db: &mut Transaction<'_, Postgres>,
+    name: &str,
+    exclude_id: Option<Id>
+) -> Result<bool> {
+    let unique: (bool, ) = sqlx::query_as("select COUNT(*) = 0 from room_category where name = $1 and id is distinct from $2 limit 1")
+        .bind(name)
+        .bind(exclude_id)
+        .fetch_one(&mut **db)
+        .await?;
+    Ok(unique.0)
+}
+
+pub async fn new_room_category(db: &mut Transaction<'_, Postgres>, name: &str) -> Result<Id> {
+    let id: (Id, ) = sqlx::query_as("insert into room_category (name) values ($1) returning id")
+        .bind(name)
+        .fetch_one(&mut **db)
+        .await?;
+    Ok(id.0)
+}
+
+pub async fn set_room_category_name(db: &mut Transaction<'_, Postgres>, id: Id, name: &str) -> Result<bool> {
+    let result = sqlx::query("update room_category set name = $1 where id = $2")
+        .bind(name)
+        .bind(id)
+        .execute(&mut **db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn set_room_category_room_order(
+    db: &mut Transaction<'_, Postgres>,
+    id: Id,
+    room_order: &[Id]
+) -> Result<()> {
+    sqlx::query("update room_category set room_order = $1 where id = $2")
+        .bind(room_order)
+        .bind(id)
+        .execute(&mut **db)
+        .await?;
+    Ok(())
+}
+
+pub async fn remove_room_from_categories(db: &mut Transaction<'_, Postgres>, rid: Id) -> Result<()> {
+    sqlx::query("update room_category set room_order = array_remove(room_order, $1)")
+        .bind(rid)
+        .execute(&mut **db)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_room_category(db: &mut Transaction<'_, Postgres>, id: Id) -> Result<()> {
+    sqlx::query("delete from room_category where id = $1")
+        .bind(id)
+        .execute(&mut **db)
+        .await?;
+    Ok(())
+}
+
+pub async fn room_count_for_update(db: &mut Transaction<'_, Postgres>) -> Result<usize> {
+    let ids = sqlx::query_as::<_, (Id,)>("select id from room for update")
+        .fetch_all(&mut **db)
+        .await?;
+    Ok(ids.len())
+}
+
 pub async fn room_exists_for_update(
     db: &mut Transaction<'_, Postgres>,
     rid: Id
@@ -1094,4 +1282,333 @@ pub async fn get_room_settings(db: &mut Transaction<'_, Postgres>, rid: Id) -> R
         .fetch_one(&mut **db)
         .await?;
     Ok(room_settings)
+}
+
+pub async fn upsert_indexed_files(
+    db: &mut Transaction<'_, Postgres>,
+    source: &str,
+    files: &[IndexedFile],
+    indexed_at: &chrono::DateTime<Utc>
+) -> Result<()> {
+    let query = r#"
+        insert into file_index (source, path, name, mtime, size, indexed_at)
+        select $1, path, name, mtime, size, $6
+        from unnest($2::text[], $3::text[], $4::timestamptz[], $5::bigint[]) as t(path, name, mtime, size)
+        on conflict (source, path) do update
+        set name = excluded.name, mtime = excluded.mtime, size = excluded.size, indexed_at = excluded.indexed_at
+    "#;
+    sqlx::query(query)
+        .bind(source)
+        .bind(files.iter().map(|x| x.path.clone()).collect::<Vec<String>>())
+        .bind(files.iter().map(|x| x.name.clone()).collect::<Vec<String>>())
+        .bind(files.iter().map(|x| x.mtime).collect::<Vec<chrono::DateTime<Utc>>>())
+        .bind(files.iter().map(|x| x.size).collect::<Vec<Option<i64>>>())
+        .bind(indexed_at)
+        .execute(&mut **db)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_indexed_files_older_than(
+    db: &mut Transaction<'_, Postgres>,
+    source: &str,
+    indexed_at: &chrono::DateTime<Utc>
+) -> Result<()> {
+    sqlx::query("delete from file_index where source = $1 and indexed_at < $2")
+        .bind(source)
+        .bind(indexed_at)
+        .execute(&mut **db)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_indexed_files_not_in_sources(db: &PgPool, sources: &[String]) -> Result<()> {
+    sqlx::query("delete from file_index where source <> all($1::text[])")
+        .bind(sources)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn search_indexed_files(
+    db: &PgPool,
+    pattern: &str,
+    limit: i64
+) -> Result<Vec<IndexedFile>> {
+    let query = r#"
+        select source, path, name, mtime, size
+        from file_index
+        where name ilike '%' || $1 || '%' escape '\'
+        order by similarity(name, $2) desc, name
+        limit $3
+    "#;
+    let escaped = pattern
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let files = sqlx::query_as::<_, IndexedFile>(query)
+        .bind(escaped)
+        .bind(pattern)
+        .bind(limit)
+        .fetch_all(db)
+        .await?;
+    Ok(files)
+}
+
+pub async fn new_upload(db: &PgPool, uid: Id, name: &str, size: i64) -> Result<Id> {
+    let id: (Id, ) = sqlx::query_as("insert into upload (user_id, name, size) values ($1, $2, $3) returning id")
+        .bind(uid)
+        .bind(name)
+        .bind(size)
+        .fetch_one(db)
+        .await?;
+    Ok(id.0)
+}
+
+pub async fn upload_name_unique(db: &PgPool, name: &str) -> Result<bool> {
+    let unique: (bool, ) = sqlx::query_as("select COUNT(*) = 0 from upload where name = $1 limit 1")
+        .bind(name)
+        .fetch_one(db)
+        .await?;
+    Ok(unique.0)
+}
+
+pub async fn get_uploads_size_by_uid(db: &PgPool, uid: Id) -> Result<i64> {
+    let size: (i64, ) = sqlx::query_as("select COALESCE(SUM(size), 0)::bigint from upload where user_id = $1")
+        .bind(uid)
+        .fetch_one(db)
+        .await?;
+    Ok(size.0)
+}
+
+pub async fn get_uploads_size(db: &PgPool) -> Result<i64> {
+    let size: (i64, ) = sqlx::query_as("select COALESCE(SUM(size), 0)::bigint from upload")
+        .fetch_one(db)
+        .await?;
+    Ok(size.0)
+}
+
+pub async fn get_upload(db: &PgPool, id: Id, uid: Id) -> Result<Option<Upload>> {
+    let upload: Option<Upload> = sqlx::query_as("select id, name, size, finished from upload where id = $1 and user_id = $2")
+        .bind(id)
+        .bind(uid)
+        .fetch_optional(db)
+        .await?;
+    Ok(upload)
+}
+
+pub async fn get_uploads_by_uid(db: &PgPool, uid: Id) -> Result<Vec<Upload>> {
+    let uploads: Vec<Upload> = sqlx::query_as("select id, name, size, finished from upload where user_id = $1 order by id")
+        .bind(uid)
+        .fetch_all(db)
+        .await?;
+    Ok(uploads)
+}
+
+pub async fn set_upload_finished(db: &PgPool, id: Id) -> Result<()> {
+    sqlx::query("update upload set finished = true where id = $1")
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_upload(db: &PgPool, id: Id) -> Result<()> {
+    sqlx::query("delete from upload where id = $1")
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn new_bookmark(
+    db: &PgPool,
+    rid: Id,
+    uid: Id,
+    media: &str,
+    timestamp: f64,
+    label: &str
+) -> Result<Id> {
+    let id: (Id, ) = sqlx::query_as("insert into bookmark (room_id, user_id, media, \"timestamp\", label) values ($1, $2, $3, $4, $5) returning id")
+        .bind(rid)
+        .bind(uid)
+        .bind(media)
+        .bind(timestamp)
+        .bind(label)
+        .fetch_one(db)
+        .await?;
+    Ok(id.0)
+}
+
+pub async fn get_bookmarks(db: &PgPool, rid: Id, media: &str) -> Result<Vec<Bookmark>> {
+    let bookmarks: Vec<Bookmark> = sqlx::query_as("select id, \"timestamp\", label, user_id as uid from bookmark where room_id = $1 and media = $2 order by \"timestamp\", id")
+        .bind(rid)
+        .bind(media)
+        .fetch_all(db)
+        .await?;
+    Ok(bookmarks)
+}
+
+pub async fn delete_bookmark(db: &PgPool, id: Id, rid: Id, media: &str, uid: Id) -> Result<bool> {
+    let result = sqlx::query("delete from bookmark where id = $1 and room_id = $2 and media = $3 and user_id = $4")
+        .bind(id)
+        .bind(rid)
+        .bind(media)
+        .bind(uid)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn new_comment(db: &PgPool, uid: Id, media: &str, timestamp: f64, content: &str) -> Result<Id> {
+    let id: (Id, ) = sqlx::query_as("insert into comment (user_id, media, \"timestamp\", content) values ($1, $2, $3, $4) returning id")
+        .bind(uid)
+        .bind(media)
+        .bind(timestamp)
+        .bind(content)
+        .fetch_one(db)
+        .await?;
+    Ok(id.0)
+}
+
+pub async fn get_comments(db: &PgPool, media: &str, from: f64, to: f64, limit: i64) -> Result<Vec<Comment>> {
+    let comments: Vec<Comment> = sqlx::query_as("select id, \"timestamp\", content, user_id as uid from comment where media = $1 and \"timestamp\" >= $2 and \"timestamp\" < $3 order by \"timestamp\", id limit $4")
+        .bind(media)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(db)
+        .await?;
+    Ok(comments)
+}
+
+pub async fn user_exists(db: &PgPool, uid: Id) -> Result<bool> {
+    let exists: (bool, ) = sqlx::query_as("select count(*) = 1 from users where id = $1 and verified = true limit 1")
+        .bind(uid)
+        .fetch_one(db)
+        .await?;
+    Ok(exists.0)
+}
+
+pub async fn new_direct_message(db: &PgPool, sender: Id, recipient: Id, content: &str) -> Result<DirectMessage> {
+    let query = r#"
+        insert into direct_message (sender_id, recipient_id, content)
+        values ($1, $2, $3)
+        returning id, sender_id as sender, recipient_id as recipient, content, created_at, read_at is not null as read
+    "#;
+    let message: DirectMessage = sqlx::query_as(query)
+        .bind(sender)
+        .bind(recipient)
+        .bind(content)
+        .fetch_one(db)
+        .await?;
+    Ok(message)
+}
+
+/// Messages between the two users, newest first, older than the message `before_id`
+pub async fn get_direct_messages(
+    db: &PgPool,
+    uid: Id,
+    other_uid: Id,
+    before_id: Option<Id>,
+    limit: i64
+) -> Result<Vec<DirectMessage>> {
+    let query = r#"
+        select id, sender_id as sender, recipient_id as recipient, content, created_at, read_at is not null as read
+        from direct_message
+        where
+        ((sender_id = $1 and recipient_id = $2) or (sender_id = $2 and recipient_id = $1))
+        and ($3::integer is null or id < $3)
+        order by id desc
+        limit $4
+    "#;
+    let messages: Vec<DirectMessage> = sqlx::query_as(query)
+        .bind(uid)
+        .bind(other_uid)
+        .bind(before_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await?;
+    Ok(messages)
+}
+
+pub async fn get_unread_counts(db: &PgPool, uid: Id) -> Result<Vec<UnreadCount>> {
+    let query = r#"
+        select sender_id as uid, count(*) as count
+        from direct_message
+        where recipient_id = $1 and read_at is null
+        group by sender_id
+    "#;
+    let counts: Vec<UnreadCount> = sqlx::query_as(query)
+        .bind(uid)
+        .fetch_all(db)
+        .await?;
+    Ok(counts)
+}
+
+pub async fn set_direct_messages_read(db: &PgPool, uid: Id, sender: Id) -> Result<u64> {
+    let result = sqlx::query(
+        "update direct_message set read_at = now() where recipient_id = $1 and sender_id = $2 and read_at is null"
+    )
+        .bind(uid)
+        .bind(sender)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn get_blocked_users(db: &PgPool, uid: Id) -> Result<Vec<Id>> {
+    let blocked: Vec<(Id, )> = sqlx::query_as("select blocked_id from user_block where user_id = $1 order by created_at")
+        .bind(uid)
+        .fetch_all(db)
+        .await?;
+    Ok(blocked.into_iter().map(|x| x.0).collect())
+}
+
+pub async fn is_blocked_between(db: &PgPool, uid: Id, other_uid: Id) -> Result<bool> {
+    let query = r#"
+        select count(*) > 0 from user_block
+        where (user_id = $1 and blocked_id = $2) or (user_id = $2 and blocked_id = $1)
+    "#;
+    let blocked: (bool, ) = sqlx::query_as(query)
+        .bind(uid)
+        .bind(other_uid)
+        .fetch_one(db)
+        .await?;
+    Ok(blocked.0)
+}
+
+pub async fn block_user(db: &PgPool, uid: Id, blocked_uid: Id) -> Result<()> {
+    sqlx::query("insert into user_block (user_id, blocked_id) values ($1, $2) on conflict do nothing")
+        .bind(uid)
+        .bind(blocked_uid)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn unblock_user(db: &PgPool, uid: Id, blocked_uid: Id) -> Result<()> {
+    sqlx::query("delete from user_block where user_id = $1 and blocked_id = $2")
+        .bind(uid)
+        .bind(blocked_uid)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_custom_status(db: &PgPool, uid: Id) -> Result<Option<String>> {
+    let custom_status: (Option<String>, ) = sqlx::query_as("select custom_status from users where id = $1 limit 1")
+        .bind(uid)
+        .fetch_one(db)
+        .await?;
+    Ok(custom_status.0)
+}
+
+pub async fn set_custom_status(db: &PgPool, uid: Id, custom_status: Option<&str>) -> Result<()> {
+    sqlx::query("update users set custom_status = $1 where id = $2")
+        .bind(custom_status)
+        .bind(uid)
+        .execute(db)
+        .await?;
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use crate::models::query::Id;
+
+/// Sliding window limit of at most `max` actions of a user per `per`
+pub struct RateLimiter {
+    max: usize,
+    per: Duration,
+    hits: Mutex<HashMap<Id, VecDeque<Instant>>>
+}
+
+impl RateLimiter {
+    pub fn new(max: usize, per: Duration) -> Self {
+        Self { max, per, hits: Mutex::new(HashMap::new()) }
+    }
+
+    /// Records the action of the user, returns false if the user is over the limit
+    pub async fn hit(&self, uid: Id) -> bool {
+        let now = Instant::now();
+        let mut hits_lock = self.hits.lock().await;
+        hits_lock.retain(|_, x| x.back().is_some_and(|last| now.duration_since(*last) < self.per));
+        let user_hits = hits_lock.entry(uid).or_default();
+        hit(user_hits, now, self.max, self.per)
+    }
+}
+
+fn hit(hits: &mut VecDeque<Instant>, now: Instant, max: usize, per: Duration) -> bool {
+    while hits.front().is_some_and(|x| now.duration_since(*x) >= per) {
+        hits.pop_front();
+    }
+    if hits.len() >= max {
+        return false
+    }
+    hits.push_back(now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use super::hit;
+
+    #[test]
+    fn test_hit() {
+        let per = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut hits = VecDeque::new();
+
+        assert!(hit(&mut hits, start, 2, per));
+        assert!(hit(&mut hits, start + Duration::from_secs(1), 2, per));
+        assert!(!hit(&mut hits, start + Duration::from_secs(2), 2, per));
+        assert_eq!(hits.len(), 2);
+
+        assert!(hit(&mut hits, start + Duration::from_secs(10), 2, per));
+        assert!(!hit(&mut hits, start + Duration::from_secs(10), 2, per));
+        assert!(hit(&mut hits, start + Duration::from_secs(11), 2, per));
+    }
+}
\ No newline at end of file
@@ -8,15 +8,22 @@ use socketioxide::extract::SocketRef;
 use socketioxide::socket::Sid;
 use socketioxide::SocketIo;
 use sqlx::{PgPool};
-use tokio::sync::{RwLock};
+use tokio::sync::{Mutex, RwLock};
 use tokio::sync::mpsc::Sender;
 use tokio::time::Instant;
 use crate::bimultimap::BiMultiMap;
 use crate::config::Config;
+use crate::file::ListingCache;
+use crate::probe::ProbeCache;
+use crate::ratelimit::RateLimiter;
+use crate::poll::{Poll, PollId};
+use crate::query;
 use crate::handlers::timers::DesyncTimerInterface;
 use crate::models::query::{Id, RoomSettings};
+use crate::models::file::{EntryMetadata, MediaInfo};
 
 pub type PlaylistEntryId = u64;
+pub type SubtitleId = u64;
 
 pub struct SrvState {
     pub config: Config,
@@ -40,7 +47,25 @@ pub struct SrvState {
     pub uid2play_info: RwLock<HashMap<Id, UserPlayInfo>>,
 
     pub uid2timestamp: RwLock<HashMap<Id, TimestampInfo>>,
-    pub uid2minor_desync: RwLock<HashSet<Id>>
+    pub uid2presence: RwLock<HashMap<Id, UserPresence>>,
+    pub rid_presence_broadcast_pending: Mutex<HashSet<Id>>,
+    pub uid2minor_desync: RwLock<HashSet<Id>>,
+
+    pub subtitle_next_id: RwLock<SubtitleId>,
+    pub subtitle_uploads: RwLock<HashMap<SubtitleId, SubtitleUpload>>,
+    pub entry_subtitles: RwLock<HashMap<PlaylistEntryId, Vec<UploadedSubtitle>>>,
+
+    pub active_uploads: Mutex<HashSet<Id>>,
+
+    pub listing_cache: ListingCache,
+    pub probe_cache: ProbeCache,
+    pub comment_limiter: RateLimiter,
+    pub reaction_limiter: RateLimiter,
+    pub chat_limiter: RateLimiter,
+    pub direct_message_limiter: RateLimiter,
+
+    pub poll_next_id: RwLock<PollId>,
+    pub rid2poll: RwLock<HashMap<Id, Poll>>
 }
 
 impl SrvState {
@@ -54,6 +79,16 @@ impl SrvState {
         socket_uid_lock.get_by_right(&uid).map(|x|x.clone())
     }
 
+    pub async fn is_admin(&self, uid: Id) -> bool {
+        if self.config.admins.usernames.is_empty() {
+            return false
+        }
+        let username = query::get_username_by_uid(&self.db, uid)
+            .await
+            .expect("db error");
+        self.config.admins.usernames.contains(&username)
+    }
+
     pub async fn socket2hwid_hash(&self, s: &SocketRef) -> String {
         let sid_hwid_hash_rl = self.sid_hwid_hash.read().await;
         sid_hwid_hash_rl.get(&s.id).map(|x| x.clone()).unwrap()
@@ -77,6 +112,20 @@ impl SrvState {
         ret_id
     }
 
+    pub async fn next_subtitle_id(&self) -> SubtitleId {
+        let mut wl = self.subtitle_next_id.write().await;
+        let ret_id = *wl;
+        *wl = ret_id + 1;
+        ret_id
+    }
+
+    pub async fn next_poll_id(&self) -> PollId {
+        let mut wl = self.poll_next_id.write().await;
+        let ret_id = *wl;
+        *wl = ret_id + 1;
+        ret_id
+    }
+
     pub async fn clear_uid2play_info_by_rid(&self, rid: Id) {
         let mut uid2play_info_wl = self.uid2play_info.write().await;
         let rid_uids_rl = self.rid_uids.read().await;
@@ -93,6 +142,9 @@ impl SrvState {
         rid_video_id_wl.remove_by_right(&entry_id);
         playlist_wl.remove(&entry_id);
 
+        let mut entry_subtitles_wl = self.entry_subtitles.write().await;
+        entry_subtitles_wl.remove(&entry_id);
+
         if playlist_wl.is_empty() {
             self.desync_timer_tx.send(DesyncTimerInterface::Sleep).await.ok();
         }
@@ -147,6 +199,12 @@ impl SrvState {
                 duration.as_secs_f64() * room_runtime_state.playback_speed.to_f64().unwrap()
                 + (ping_ms / 1000f64);
 
+            // a client that reported a position inside the loop has been wrapped back by mpv meanwhile
+            if let Some(ab_loop) = play_info.ab_loop {
+                if timestamp_info.timestamp >= ab_loop.a && timestamp_info.timestamp < ab_loop.b {
+                    compensated_timestamp = ab_loop.wrap(compensated_timestamp);
+                }
+            }
             Some(compensated_timestamp)
         }
         else {
@@ -168,16 +226,94 @@ impl SrvState {
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum PlaylistEntry {
-    Video { source: String, path: String },
-    Url { url: String }
+    Video { source: String, path: String, subtitles: Vec<String>, metadata: Option<EntryMetadata>, media: Option<Box<MediaInfo>>, clip: Clip },
+    Url { url: String, clip: Clip }
 }
 
+impl PlaylistEntry {
+    /// Name shown to users, the scraped title if there is one
+    pub fn title(&self) -> String {
+        match self {
+            PlaylistEntry::Video { metadata: Some(EntryMetadata { title: Some(title), .. }), .. } => title.clone(),
+            PlaylistEntry::Video { path, .. } => path.rsplit('/').next().unwrap_or(path).to_string(),
+            PlaylistEntry::Url { url, .. } => url.clone()
+        }
+    }
+
+    pub fn clip(&self) -> Clip {
+        match self {
+            PlaylistEntry::Video { clip, .. } | PlaylistEntry::Url { clip, .. } => *clip
+        }
+    }
+
+    /// Identifies the played media across playlists, bookmarks are stored under this key
+    pub fn media_key(&self) -> String {
+        match self {
+            PlaylistEntry::Video { source, path, .. } => format!("file:{}:{}", source, path),
+            PlaylistEntry::Url { url, .. } => format!("url:{}", url)
+        }
+    }
+}
+
+/// Part of the entry that is played, in seconds from the beginning of the file
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Clip {
+    pub start: Option<f64>,
+    pub end: Option<f64>
+}
+
+
+#[derive(Debug)]
+pub struct SubtitleUpload {
+    pub uid: Id,
+    pub playlist_entry_id: PlaylistEntryId,
+    pub name: String,
+    pub size: usize,
+    pub data: Vec<u8>
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadedSubtitle {
+    pub id: SubtitleId,
+    pub uid: Id,
+    pub name: String,
+    pub data: Vec<u8>
+}
 
 #[derive(Debug)]
 pub struct RoomPlayInfo {
     pub playing_entry_id: PlaylistEntryId,
     pub playing_state: PlayingState,
-    pub last_change_at: Instant
+    pub last_change_at: Instant,
+    pub clip_ended: bool,
+    pub ab_loop: Option<AbLoop>,
+    /// Members whose player reached the end of the entry
+    pub ended_uids: HashSet<Id>
+}
+
+/// Segment repeated by the whole room, playback reaching `b` continues from `a`
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbLoop {
+    pub a: f64,
+    pub b: f64
+}
+
+impl AbLoop {
+    pub fn wrap(&self, timestamp: f64) -> f64 {
+        if timestamp < self.b {
+            return timestamp
+        }
+        self.a + (timestamp - self.a) % (self.b - self.a)
+    }
+
+    /// How far `to` is ahead of `from`, playback inside the loop returns to `a` after `b`
+    pub fn distance(&self, from: f64, to: f64) -> f64 {
+        let in_loop = |x: f64| x >= self.a && x < self.b;
+        if !in_loop(from) || !in_loop(to) {
+            return to - from
+        }
+        (to - from).rem_euclid(self.b - self.a)
+    }
 }
 
 #[derive(Debug)]
@@ -208,8 +344,40 @@ pub enum UserReadyStatus {
     Ready, NotReady, Loading, Error
 }
 
+/// Presence reported by the user, what the user watches is derived from the room state
+#[derive(Debug, Clone, Default)]
+pub struct UserPresence {
+    pub idle: bool,
+    pub custom_status: Option<String>
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct TimestampInfo {
     pub timestamp: f64,
     pub recv: Instant
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AbLoop;
+
+    const AB_LOOP: AbLoop = AbLoop { a: 10f64, b: 20f64 };
+
+    #[test]
+    fn test_ab_loop_wrap() {
+        assert_eq!(AB_LOOP.wrap(5f64), 5f64);
+        assert_eq!(AB_LOOP.wrap(15f64), 15f64);
+        assert_eq!(AB_LOOP.wrap(20f64), 10f64);
+        assert_eq!(AB_LOOP.wrap(47f64), 17f64);
+    }
+
+    #[test]
+    fn test_ab_loop_distance() {
+        assert_eq!(AB_LOOP.distance(5f64, 8f64), 3f64);
+        assert_eq!(AB_LOOP.distance(5f64, 15f64), 10f64);
+        assert_eq!(AB_LOOP.distance(12f64, 18f64), 6f64);
+        assert_eq!(AB_LOOP.distance(18f64, 12f64), 4f64);
+        assert_eq!(AB_LOOP.distance(15f64, 20f64), 5f64);
+        assert_eq!(AB_LOOP.distance(15f64, 40f64), 25f64);
+    }
 }
\ No newline at end of file
@@ -2,9 +2,10 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use url::Url;
 use validator::ValidationError;
-use crate::constants::SOCKETIO_ACK_TIMEOUT;
+use crate::constants::{POLL_DURATION_MAX, POLL_DURATION_MIN, POLL_OPTIONS_MAX, REACTIONS, CHAT_MESSAGE_MAX_LENGTH, ROOM_DESCRIPTION_MAX_LENGTH, ROOM_TOPIC_MAX_LENGTH, ROOM_CATEGORY_NAME_MAX_LENGTH, SOCKETIO_ACK_TIMEOUT, SUBTITLE_UPLOAD_CHUNK_MAX_SIZE, SUBTITLE_UPLOAD_FORMATS, SUBTITLE_UPLOAD_MAX_SIZE};
 use crate::models::query::Id;
-use crate::srvstate::{PlaylistEntryId, UserReadyStatus};
+use crate::poll::{PollId, PollOption};
+use crate::srvstate::{AbLoop, Clip, PlaylistEntryId, UserReadyStatus};
 
 pub fn check_username_format(username: &str) -> Result<(), ValidationError> {
     if username.chars().count() < 4 || username.chars().count() > 16 {
@@ -136,6 +137,35 @@ pub fn check_room_name(room_name: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+pub fn check_room_description(description: &str) -> Result<(), ValidationError> {
+    let len = description.chars().count();
+    if !(1..=ROOM_DESCRIPTION_MAX_LENGTH).contains(&len) || description.trim().is_empty() {
+        return Err(ValidationError::new("invalid room description length"))
+    }
+    Ok(())
+}
+
+pub fn check_room_topic(topic: &str) -> Result<(), ValidationError> {
+    let len = topic.chars().count();
+    if !(1..=ROOM_TOPIC_MAX_LENGTH).contains(&len) || topic.trim().is_empty() {
+        return Err(ValidationError::new("invalid room topic length"))
+    }
+    if topic.contains(['\n', '\r']) {
+        return Err(ValidationError::new("invalid characters"))
+    }
+    Ok(())
+}
+
+pub fn check_room_category_name(name: &str) -> Result<(), ValidationError> {
+    if !(1..=ROOM_CATEGORY_NAME_MAX_LENGTH).contains(&name.chars().count()) {
+        return Err(ValidationError::new("invalid category name length"))
+    }
+    if name.starts_with(char::is_whitespace) || name.ends_with(char::is_whitespace) {
+        return Err(ValidationError::new("category name starts or ends with whitespace"))
+    }
+    Ok(())
+}
+
 pub fn check_room_order(room_order: &Vec<Id>) -> Result<(), ValidationError> {
     let valid = room_order.iter().all(|&x|x >= 1);
     if !valid {
@@ -215,6 +245,134 @@ pub fn check_playlist_order(order: &Vec<PlaylistEntryId>) -> Result<(), Validati
     Ok(())
 }
 
+pub fn check_clip(clip: &Clip) -> Result<(), ValidationError> {
+    for t in [clip.start, clip.end].into_iter().flatten() {
+        if !t.is_finite() || t < 0f64 {
+            return Err(ValidationError::new("invalid clip time"))
+        }
+    }
+    if let (Some(start), Some(end)) = (clip.start, clip.end) {
+        if end <= start {
+            return Err(ValidationError::new("clip end before start"))
+        }
+    }
+    Ok(())
+}
+
+pub fn check_ab_loop(ab_loop: &AbLoop) -> Result<(), ValidationError> {
+    if !ab_loop.a.is_finite() || !ab_loop.b.is_finite() || ab_loop.a < 0f64 {
+        return Err(ValidationError::new("invalid loop time"))
+    }
+    if ab_loop.b <= ab_loop.a {
+        return Err(ValidationError::new("loop end before start"))
+    }
+    Ok(())
+}
+
+pub fn check_media_timestamp(timestamp: f64) -> Result<(), ValidationError> {
+    if !timestamp.is_finite() || timestamp < 0f64 {
+        return Err(ValidationError::new("invalid timestamp"))
+    }
+    Ok(())
+}
+
+pub fn check_bookmark_label(label: &str) -> Result<(), ValidationError> {
+    let len = label.chars().count();
+    if !(1..=64).contains(&len) || label.trim().is_empty() {
+        return Err(ValidationError::new("invalid length"))
+    }
+    Ok(())
+}
+
+pub fn check_comment_content(content: &str) -> Result<(), ValidationError> {
+    let len = content.chars().count();
+    if !(1..=200).contains(&len) || content.trim().is_empty() {
+        return Err(ValidationError::new("invalid length"))
+    }
+    if content.contains(['\n', '\r']) {
+        return Err(ValidationError::new("invalid characters"))
+    }
+    Ok(())
+}
+
+pub fn check_reaction(reaction: &str) -> Result<(), ValidationError> {
+    if !REACTIONS.contains(&reaction) {
+        return Err(ValidationError::new("unknown reaction"))
+    }
+    Ok(())
+}
+
+pub fn check_chat_message_content(content: &str) -> Result<(), ValidationError> {
+    let len = content.chars().count();
+    if !(1..=CHAT_MESSAGE_MAX_LENGTH).contains(&len) || content.trim().is_empty() {
+        return Err(ValidationError::new("invalid length"))
+    }
+    if content.contains(['\n', '\r']) {
+        return Err(ValidationError::new("invalid characters"))
+    }
+    Ok(())
+}
+
+pub fn check_direct_message_content(content: &str) -> Result<(), ValidationError> {
+    let len = content.chars().count();
+    if !(1..=1000).contains(&len) || content.trim().is_empty() {
+        return Err(ValidationError::new("invalid length"))
+    }
+    Ok(())
+}
+
+pub fn check_custom_status(custom_status: &str) -> Result<(), ValidationError> {
+    let len = custom_status.chars().count();
+    if !(1..=64).contains(&len) || custom_status.trim().is_empty() {
+        return Err(ValidationError::new("invalid length"))
+    }
+    if custom_status.contains(['\n', '\r']) {
+        return Err(ValidationError::new("invalid characters"))
+    }
+    Ok(())
+}
+
+pub fn check_poll_question(question: &str) -> Result<(), ValidationError> {
+    let len = question.chars().count();
+    if !(1..=200).contains(&len) || question.trim().is_empty() {
+        return Err(ValidationError::new("invalid length"))
+    }
+    if question.contains(['\n', '\r']) {
+        return Err(ValidationError::new("invalid characters"))
+    }
+    Ok(())
+}
+
+pub fn check_poll_options(options: &Vec<PollOption>) -> Result<(), ValidationError> {
+    if !(2..=POLL_OPTIONS_MAX).contains(&options.len()) {
+        return Err(ValidationError::new("invalid number of options"))
+    }
+    for option in options {
+        let len = option.text.chars().count();
+        if !(1..=100).contains(&len) || option.text.trim().is_empty() || option.text.contains(['\n', '\r']) {
+            return Err(ValidationError::new("invalid option text"))
+        }
+        if let Some(id) = option.playlist_entry_id.as_ref() {
+            check_playlist_entry_id(id)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn check_poll_duration(duration: u64) -> Result<(), ValidationError> {
+    if !(POLL_DURATION_MIN..=POLL_DURATION_MAX).contains(&duration) {
+        return Err(ValidationError::new("invalid poll duration"))
+    }
+    Ok(())
+}
+
+pub fn check_poll_id(id: &PollId) -> Result<(), ValidationError> {
+    if *id < 1u64 {
+        return Err(ValidationError::new("invalid poll id"))
+    }
+    Ok(())
+}
+
 pub fn check_aid_sid(aid_sid: u64) -> Result<(), ValidationError> {
     if aid_sid > 0 {
         Ok(())
@@ -231,4 +389,114 @@ pub fn check_ready_not_ready(r: &UserReadyStatus) -> Result<(), ValidationError>
     else {
         Err(ValidationError::new("User ready status is not ready or not ready"))
     }
+}
+
+pub fn check_subtitle_name(name: &str) -> Result<(), ValidationError> {
+    if name.chars().count() < 1 || name.chars().count() > 255 {
+        return Err(ValidationError::new("invalid length"))
+    }
+    if name.contains('/') || name.contains('\\') {
+        return Err(ValidationError::new("invalid characters"))
+    }
+    if !SUBTITLE_UPLOAD_FORMATS.contains(&subtitle_format(name).as_str()) {
+        return Err(ValidationError::new("unsupported subtitle format"))
+    }
+    Ok(())
+}
+
+pub fn check_subtitle_size(size: usize) -> Result<(), ValidationError> {
+    if !(1..=SUBTITLE_UPLOAD_MAX_SIZE).contains(&size) {
+        return Err(ValidationError::new("invalid subtitle size"))
+    }
+    Ok(())
+}
+
+pub fn check_subtitle_chunk(data: &[u8]) -> Result<(), ValidationError> {
+    if data.is_empty() || data.len() > SUBTITLE_UPLOAD_CHUNK_MAX_SIZE {
+        return Err(ValidationError::new("invalid chunk size"))
+    }
+    Ok(())
+}
+
+pub fn check_subtitle_data(name: &str, data: &[u8]) -> Result<(), ValidationError> {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| ValidationError::new("subtitles are not valid UTF-8"))?
+        .trim_start_matches('\u{feff}');
+    let valid = match subtitle_format(name).as_str() {
+        "srt" => text.contains("-->"),
+        "vtt" => text.starts_with("WEBVTT"),
+        "ass" | "ssa" => text.contains("[Script Info]"),
+        _ => false
+    };
+    if !valid {
+        return Err(ValidationError::new("invalid subtitle format"))
+    }
+    Ok(())
+}
+
+fn subtitle_format(name: &str) -> String {
+    name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default()
+}
+
+pub fn check_upload_name(name: &str) -> Result<(), ValidationError> {
+    if name.chars().count() < 1 || name.chars().count() > 255 {
+        return Err(ValidationError::new("invalid length"))
+    }
+    if name.starts_with('.') {
+        return Err(ValidationError::new("hidden file name"))
+    }
+    if name.contains('/') || name.contains('\\') {
+        return Err(ValidationError::new("invalid characters"))
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::{ROOM_CATEGORY_NAME_MAX_LENGTH, ROOM_DESCRIPTION_MAX_LENGTH, ROOM_TOPIC_MAX_LENGTH, SUBTITLE_UPLOAD_CHUNK_MAX_SIZE, SUBTITLE_UPLOAD_MAX_SIZE};
+    use super::{check_room_category_name, check_room_description, check_room_topic, check_subtitle_chunk, check_subtitle_size};
+
+    #[test]
+    fn test_check_subtitle_size() {
+        assert!(check_subtitle_size(0).is_err());
+        assert!(check_subtitle_size(1).is_ok());
+        assert!(check_subtitle_size(SUBTITLE_UPLOAD_MAX_SIZE).is_ok());
+        assert!(check_subtitle_size(SUBTITLE_UPLOAD_MAX_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn test_check_subtitle_chunk() {
+        assert!(check_subtitle_chunk(&[]).is_err());
+        assert!(check_subtitle_chunk(&[0]).is_ok());
+        assert!(check_subtitle_chunk(&vec![0; SUBTITLE_UPLOAD_CHUNK_MAX_SIZE]).is_ok());
+        assert!(check_subtitle_chunk(&vec![0; SUBTITLE_UPLOAD_CHUNK_MAX_SIZE + 1]).is_err());
+    }
+
+    #[test]
+    fn test_check_room_description() {
+        assert!(check_room_description("").is_err());
+        assert!(check_room_description(" \t ").is_err());
+        assert!(check_room_description("a\nb").is_ok());
+        assert!(check_room_description(&"é".repeat(ROOM_DESCRIPTION_MAX_LENGTH)).is_ok());
+        assert!(check_room_description(&"é".repeat(ROOM_DESCRIPTION_MAX_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_check_room_topic() {
+        assert!(check_room_topic("").is_err());
+        assert!(check_room_topic(" \t ").is_err());
+        assert!(check_room_topic("a\nb").is_err());
+        assert!(check_room_topic("a\rb").is_err());
+        assert!(check_room_topic(&"é".repeat(ROOM_TOPIC_MAX_LENGTH)).is_ok());
+        assert!(check_room_topic(&"é".repeat(ROOM_TOPIC_MAX_LENGTH + 1)).is_err());
+    }
+    #[test]
+    fn test_check_room_category_name() {
+        assert!(check_room_category_name("").is_err());
+        assert!(check_room_category_name(" a").is_err());
+        assert!(check_room_category_name("a ").is_err());
+        assert!(check_room_category_name("a b").is_ok());
+        assert!(check_room_category_name(&"é".repeat(ROOM_CATEGORY_NAME_MAX_LENGTH)).is_ok());
+        assert!(check_room_category_name(&"é".repeat(ROOM_CATEGORY_NAME_MAX_LENGTH + 1)).is_err());
+    }
 }
\ No newline at end of file